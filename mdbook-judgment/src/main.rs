@@ -6,6 +6,7 @@ use std::process;
 use mdbook_preprocessor::book::{Book, BookItem};
 use mdbook_preprocessor::{Preprocessor, PreprocessorContext};
 use regex::Regex;
+use serde_json::Value;
 
 const GITHUB_BASE: &str = "https://github.com/dada-lang/dada-model/blob/main";
 
@@ -563,12 +564,174 @@ fn render_anchor(anchor: &Anchor) -> String {
     render_figure("anchor", &id, &anchor.name, &link, &anchor.content, None)
 }
 
+/// Render the proof tree of `program` (see [`render_proof_node`]) below the program itself.
+/// If the program does not type-check, the error is rendered instead.
+fn render_proof(program: &str, index: &SourceIndex) -> String {
+    let program = dedent(program.trim_matches('\n'));
+
+    let tree = match dada_model::proof_tree::prove_program("proof", &program) {
+        Ok(tree) => {
+            let json = dada_model::proof_tree::proof_tree_to_json(&tree);
+            let mut out = String::from("<ul class=\"proof-tree\">\n");
+            render_proof_node(&json, index, true, &mut out);
+            out.push_str("</ul>\n");
+            out
+        }
+        Err(e) => {
+            eprintln!("warning: proof failed: {e}");
+            format!(
+                "<pre class=\"proof-error\">{}</pre>\n",
+                escape_html(&e.to_string())
+            )
+        }
+    };
+
+    format!(
+        "<figure class=\"proof\">\n\
+         \n\
+         ```dada\n\
+         {program}\n\
+         ```\n\
+         \n\
+         {tree}\
+         \n\
+         </figure>\n"
+    )
+}
+
+/// Render a node of a proof tree (as produced by `proof_tree_to_json`) as a collapsible
+/// list item showing the judgment, the rule that proved it (linked to its source),
+/// its inputs, and its children. The program being checked is elided from the inputs
+/// since it is shown above the tree. No blank lines are emitted, so that markdown
+/// leaves the HTML alone.
+fn render_proof_node(node: &Value, index: &SourceIndex, open: bool, out: &mut String) {
+    let judgment = node["judgment"].as_str().unwrap_or_default();
+    let rule = node["rule"].as_str();
+
+    let rule_html = match rule {
+        Some(rule) => {
+            let link = index.judgments.get(judgment).and_then(|j| {
+                let r = j.rules.iter().find(|r| r.name == rule)?;
+                Some(github_link(&j.file_path, r.line_number))
+            });
+            match link {
+                Some(link) => format!(
+                    " <a class=\"judgment-src\" href=\"{link}\" title=\"View source\" target=\"_blank\">(\"{}\")</a>",
+                    escape_html(rule)
+                ),
+                None => format!(" <span class=\"proof-rule\">(\"{}\")</span>", escape_html(rule)),
+            }
+        }
+        None => String::new(),
+    };
+
+    let inputs: Vec<String> = node["inputs"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| *name != "program")
+        .map(|(name, value)| {
+            format!(
+                "{name}: {}",
+                escape_html(value.as_str().unwrap_or_default())
+            )
+        })
+        .chain(
+            node["output"]
+                .as_str()
+                .map(|o| format!("=> {}", escape_html(o))),
+        )
+        .collect();
+
+    out.push_str(&format!(
+        "<li><details{}><summary><code>{}</code>{rule_html}</summary>\n",
+        if open { " open" } else { "" },
+        escape_html(judgment),
+    ));
+    if !inputs.is_empty() {
+        out.push_str(&format!(
+            "<pre class=\"proof-inputs\">{}</pre>\n",
+            inputs.join("\n")
+        ));
+    }
+    let children = node["children"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if !children.is_empty() {
+        out.push_str("<ul>\n");
+        for child in children {
+            render_proof_node(child, index, false, out);
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</details></li>\n");
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 // --- Markdown replacement ---
 
+/// Find each `{{#proof <program>}}` directive in `content`, returning its byte range
+/// and the program. The program may contain braces as long as they are balanced:
+/// the directive ends at the first `}}` that is not within them.
+fn find_proof_directives(content: &str) -> Vec<(std::ops::Range<usize>, &str)> {
+    const OPEN: &str = "{{#proof";
+
+    let mut directives = Vec::new();
+    let mut pos = 0;
+    while let Some(start) = content[pos..].find(OPEN) {
+        let start = pos + start;
+        let program_start = start + OPEN.len();
+
+        let mut depth = 0;
+        let mut end = None;
+        for (i, ch) in content[program_start..].char_indices() {
+            match ch {
+                '{' => depth += 1,
+                '}' if depth == 0 => {
+                    if content[program_start + i..].starts_with("}}") {
+                        end = Some(program_start + i);
+                    }
+                    break;
+                }
+                '}' => depth -= 1,
+                _ => {}
+            }
+        }
+
+        let Some(end) = end else {
+            eprintln!("warning: unterminated `{OPEN}` directive");
+            pos = program_start;
+            continue;
+        };
+
+        directives.push((start..end + 2, &content[program_start..end]));
+        pos = end + 2;
+    }
+    directives
+}
+
 fn replace_refs(content: &str, index: &SourceIndex) -> String {
-    // First pass: {judgment-rule}`fn_name, rule_name`
+    // First pass: {{#proof program}}
+    let mut with_proofs = String::new();
+    let mut pos = 0;
+    for (range, program) in find_proof_directives(content) {
+        with_proofs.push_str(&content[pos..range.start]);
+        with_proofs.push_str(&render_proof(program, index));
+        pos = range.end;
+    }
+    with_proofs.push_str(&content[pos..]);
+    let content = with_proofs;
+
+    // Second pass: {judgment-rule}`fn_name, rule_name`
     let rule_re = Regex::new(r#"\{judgment-rule\}`(\w+),\s*([^`]+)`"#).unwrap();
-    let content = rule_re.replace_all(content, |caps: &regex::Captures| {
+    let content = rule_re.replace_all(&content, |caps: &regex::Captures| {
         let fn_name = &caps[1];
         let rule_name = caps[2].trim();
 
@@ -581,7 +744,7 @@ fn replace_refs(content: &str, index: &SourceIndex) -> String {
         }
     });
 
-    // Second pass: {judgment}`fn_name`
+    // Third pass: {judgment}`fn_name`
     let judgment_re = Regex::new(r#"\{judgment\}`(\w+)`"#).unwrap();
     let content = judgment_re
         .replace_all(&content, |caps: &regex::Captures| {
@@ -597,7 +760,7 @@ fn replace_refs(content: &str, index: &SourceIndex) -> String {
         })
         .to_string();
 
-    // Third pass: {anchor}`anchor_name`
+    // Fourth pass: {anchor}`anchor_name`
     let anchor_re = Regex::new(r#"\{anchor\}`(\w+)`"#).unwrap();
     anchor_re
         .replace_all(&content, |caps: &regex::Captures| {
@@ -759,4 +922,45 @@ Rule: {judgment-rule}`move_place, copy`"#;
         assert!(output.contains("[src]"), "output: {output}");
         assert!(!output.contains("{anchor}"), "output: {output}");
     }
+
+    #[test]
+    fn test_find_proof_directives() {
+        let input =
+            "before {{#proof class Data { x: Int; } }} middle {{#proof class Unit { }}} after";
+        let directives = find_proof_directives(input);
+        assert_eq!(directives.len(), 2, "directives: {directives:?}");
+        assert_eq!(directives[0].1, " class Data { x: Int; } ");
+        assert_eq!(
+            &input[directives[0].0.clone()],
+            "{{#proof class Data { x: Int; } }}"
+        );
+        assert_eq!(directives[1].1, " class Unit { }");
+    }
+
+    #[test]
+    fn test_find_proof_directives_unterminated() {
+        let directives = find_proof_directives("{{#proof class Data { }");
+        assert!(directives.is_empty(), "directives: {directives:?}");
+    }
+
+    #[test]
+    fn test_proof_replacement() {
+        let index = make_index();
+        let input = "A proof:\n\n{{#proof\nclass Data { }\n}}\n";
+        let output = replace_refs(input, &index);
+        assert!(output.contains("class Data"), "output: {output}");
+        assert!(output.contains("proof-tree"), "output: {output}");
+        assert!(output.contains("<details open>"), "output: {output}");
+        assert!(output.contains("check_program"), "output: {output}");
+        assert!(!output.contains("{{#proof"), "output: {output}");
+    }
+
+    #[test]
+    fn test_proof_replacement_error() {
+        let index = make_index();
+        let input = "{{#proof\nclass Data { x: Missing; }\n}}";
+        let output = replace_refs(input, &index);
+        assert!(output.contains("proof-error"), "output: {output}");
+        assert!(!output.contains("proof-tree"), "output: {output}");
+    }
 }
@@ -0,0 +1,385 @@
+//! Source locations for type-check failures.
+//!
+//! The parser records where each block, statement, place expression and method or
+//! function header is written (see [`Span`]), and these terms carry their span through the
+//! judgments that check them. A [`FailedJudgment`] keeps only the `Debug` text of the
+//! judgments along the path to a failure, so to locate a failure we check the program
+//! again with spans shown in that text (see [`with_spans_shown`]) and take the span of
+//! the term checked by the innermost of [`LOCATED_JUDGMENTS`] along the same path.
+//! Each of those judgments takes the term it checks as its first input, so the span is
+//! printed at the start of that input. An expression that starts with a place expression,
+//! such as the call `x.ref.get()`, prints the span of the place expression first, and so
+//! it is located there.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use formality_core::judgment::{FailedJudgment, RuleFailureCause};
+
+use crate::grammar::{with_spans_shown, Decl, Program, Span};
+use crate::test_util::format_error_leaves;
+use crate::type_system;
+
+mod errors;
+pub use errors::DadaError;
+
+mod origins;
+
+/// The text of a source file together with the name used to report it.
+pub struct SourceFile<'s> {
+    pub path: &'s str,
+    pub text: &'s str,
+}
+
+impl<'s> SourceFile<'s> {
+    pub fn new(path: &'s str, text: &'s str) -> Self {
+        Self { path, text }
+    }
+
+    /// 1-based line and column of the byte offset `offset`.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let before = &self.text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let col = self.text[line_start..offset].chars().count() + 1;
+        (line, col)
+    }
+
+    /// Locate the innermost term along `path`, a list of judgments from the outermost
+    /// to the innermost printed with spans shown (see [`failure_paths`]).
+    pub fn locate(&self, path: &[String]) -> Option<Range<usize>> {
+        path.iter()
+            .rev()
+            .filter(|judgment| is_located(judgment))
+            .find_map(|judgment| self.text_range(&first_input_span(judgment)?))
+    }
+
+    /// The byte range of `span` within the source text, if it was written there.
+    pub fn text_range(&self, span: &Span) -> Option<Range<usize>> {
+        span.range_in(self.text)
+    }
+
+    /// The source name of the universal variable numbered `var` in the method or function
+    /// of `program` whose header is written at `header`. Universal variables are numbered
+    /// in the order they are brought into scope: first the generic parameters of the
+    /// enclosing class or impl, then those of the method itself. The parameters of an
+    /// impl are not named.
+    pub fn universal_var_name(
+        &self,
+        program: &Program,
+        header: &Span,
+        var: usize,
+    ) -> Option<String> {
+        let (outer, class_name) = enclosing_parameters(program, header)?;
+        let header = self.text_range(header)?;
+
+        if let Some(var) = var.checked_sub(outer) {
+            return generic_names(&self.text[header.end..])
+                .get(var)
+                .map(|n| n.to_string());
+        }
+
+        // The class is declared before its methods.
+        let class = keyword_then_name(&self.text[..header.start], "class", &class_name?).last()?;
+        generic_names(&self.text[class.end..])
+            .get(var)
+            .map(|n| n.to_string())
+    }
+
+    /// Render `range` as a `file:line:col` header followed by the source line with the
+    /// range underlined.
+    pub fn render_span(&self, range: Range<usize>) -> String {
+        let (line, col) = self.line_col(range.start);
+        let line_text = self.text.lines().nth(line - 1).unwrap_or("");
+        let line_chars = line_text.chars().count();
+        let span_chars = self.text[range].chars().count();
+        let underline = span_chars.min(line_chars + 1 - col).max(1);
+        let gutter = " ".repeat(line.to_string().len());
+        format!(
+            "{path}:{line}:{col}\n{gutter} |\n{line} | {line_text}\n{gutter} | {pad}{carets}",
+            path = self.path,
+            pad = " ".repeat(col - 1),
+            carets = "^".repeat(underline),
+        )
+    }
+}
+
+/// Format a failure `e` to type-check `program`, parsed from `source`, as a diagnostic.
+/// If the failure is one we can explain (see [`DadaError`]), we report that along with its
+/// location and, for a conflicting lien, where the lien is created. Otherwise we report the
+/// location of the innermost block, statement, place expression or method being checked
+/// (see the module docs) followed by the leaf failures as reported by [`format_error_leaves`].
+pub fn format_diagnostic(
+    source: &SourceFile<'_>,
+    program: &Arc<Program>,
+    e: &anyhow::Error,
+) -> String {
+    let Some(failed) = failed_judgment(e) else {
+        return format!("error: {}\n{}", source.path, format_error_leaves(e));
+    };
+
+    let paths = failure_paths(failed);
+
+    // The same failure with spans shown, whose paths line up with `paths`.
+    let located_paths =
+        match with_spans_shown(|| type_system::check_program(program).into_singleton()) {
+            Ok(_) => vec![],
+            Err(e) => failed_judgment(&e.into())
+                .map(failure_paths)
+                .unwrap_or_default(),
+        };
+    let located_path = |index: usize| located_paths.get(index).map_or(&[][..], Vec::as_slice);
+
+    if let Some((index, error)) = paths
+        .iter()
+        .enumerate()
+        .find_map(|(index, path)| Some((index, DadaError::classify(failed, path)?)))
+    {
+        let path = located_path(index);
+        let header = path
+            .iter()
+            .find(|judgment| is_header(judgment))
+            .and_then(|judgment| first_input_span(judgment));
+        let var_name = error
+            .universal_var()
+            .zip(header.as_ref())
+            .and_then(|(var, header)| source.universal_var_name(program, header, var));
+        let error = match var_name {
+            Some(name) => error.with_var_name(&name),
+            None => error,
+        };
+
+        let Some(range) = source.locate(path) else {
+            return format!("error: {error}\n{}", source.path);
+        };
+        let mut output = format!("error: {error}\n{}", source.render_span(range.clone()));
+        // The last place the lien is created before the failure, if any.
+        let origin = error
+            .lien()
+            .zip(header.as_ref())
+            .and_then(|(lien, header)| {
+                origins::lien_origins(program, header, lien)
+                    .iter()
+                    .filter_map(|origin| source.text_range(origin))
+                    .filter(|origin| origin.end <= range.start)
+                    .last()
+                    .map(|origin| (lien, origin))
+            });
+        if let Some((lien, origin)) = origin {
+            output.push_str(&format!(
+                "\nnote: `{}` is created here\n{}",
+                errors::written_lien(lien),
+                source.render_span(origin)
+            ));
+        }
+        if let Some(help) = error.help() {
+            if let Some(header) = header.and_then(|header| source.text_range(&header)) {
+                output.push_str(&format!("\nhelp: {help}\n{}", source.render_span(header)));
+            }
+        }
+        return output;
+    }
+
+    let leaves = format_error_leaves(e);
+    let deepest = (0..paths.len()).fold(0, |deepest, index| {
+        if paths[index].len() > paths[deepest].len() {
+            index
+        } else {
+            deepest
+        }
+    });
+    match source.locate(located_path(deepest)) {
+        Some(range) => format!("error: {}\n{leaves}", source.render_span(range)),
+        None => format!("error: {}\n{leaves}", source.path),
+    }
+}
+
+/// The failed judgment behind `e`, if it is one.
+fn failed_judgment(e: &anyhow::Error) -> Option<&FailedJudgment> {
+    e.chain().find_map(|cause| {
+        if let Some(failed) = cause.downcast_ref::<Box<FailedJudgment>>() {
+            return Some(&**failed);
+        }
+        cause.downcast_ref::<FailedJudgment>()
+    })
+}
+
+/// Every path from `failed` to a leaf failure, each given as the `Debug` text
+/// of the judgments along it, outermost first.
+pub fn failure_paths(failed: &FailedJudgment) -> Vec<Vec<String>> {
+    let mut paths: Vec<Vec<String>> = vec![];
+    for rule in &failed.failed_rules {
+        if let RuleFailureCause::FailedJudgment(child) = &rule.cause {
+            paths.extend(failure_paths(child));
+        }
+    }
+    if paths.is_empty() {
+        paths.push(vec![]);
+    }
+    for path in &mut paths {
+        path.insert(0, failed.judgment.clone());
+    }
+    paths
+}
+
+/// The rules of the last judgment along `path`, a path through `failed` (see
+/// [`failure_paths`]), that failed, each with its name and the cause of its failure.
+pub(crate) fn failed_rules<'f>(
+    failed: &'f FailedJudgment,
+    path: &[String],
+) -> Vec<(&'f str, &'f RuleFailureCause)> {
+    let mut judgment = failed;
+    for step in path.iter().skip(1) {
+        let child = judgment
+            .failed_rules
+            .iter()
+            .find_map(|rule| match &rule.cause {
+                RuleFailureCause::FailedJudgment(child) if child.judgment == *step => {
+                    Some(&**child)
+                }
+                _ => None,
+            });
+        let Some(child) = child else {
+            return vec![];
+        };
+        judgment = child;
+    }
+    judgment
+        .failed_rules
+        .iter()
+        .filter_map(|rule| {
+            let (name, _) = rule.rule_name_index.as_ref()?;
+            Some((name.as_str(), &rule.cause))
+        })
+        .collect()
+}
+
+/// If `judgment` is the `Debug` text of an instance of the judgment `name`, the `Debug`
+/// text of its input `input`. Judgments print as `name { input: value, .. }`, so the
+/// inputs are separated by the commas that are not nested within a value.
+pub(crate) fn judgment_input<'j>(judgment: &'j str, name: &str, input: &str) -> Option<&'j str> {
+    let inputs = judgment
+        .strip_prefix(name)?
+        .strip_prefix(" { ")?
+        .strip_suffix(" }")?;
+    let mut depth = 0_usize;
+    let mut start = 0;
+    let mut values = vec![];
+    for (index, c) in inputs.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                values.push(&inputs[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    values.push(&inputs[start..]);
+    values
+        .into_iter()
+        .find_map(|value| value.trim_start().strip_prefix(input)?.strip_prefix(": "))
+}
+
+/// The judgments that check a term recording its span (a method or function,
+/// a block, a statement or an expression), which they take as their first input.
+const LOCATED_JUDGMENTS: &[&str] = &[
+    "check_method",
+    "check_fn",
+    "type_block",
+    "type_statement",
+    "type_expr_as",
+    "type_expr",
+];
+
+/// True if `judgment` is the `Debug` text of one of [`LOCATED_JUDGMENTS`].
+fn is_located(judgment: &str) -> bool {
+    LOCATED_JUDGMENTS.iter().any(|name| {
+        judgment
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with(" {"))
+    })
+}
+
+/// True if `judgment` checks a method or function.
+fn is_header(judgment: &str) -> bool {
+    ["check_method {", "check_fn {"]
+        .iter()
+        .any(|prefix| judgment.starts_with(prefix))
+}
+
+/// The span shown at the start of the first input of `judgment`, if any.
+fn first_input_span(judgment: &str) -> Option<Span> {
+    let (_, inputs) = judgment.split_once(" { ")?;
+    let (_, first) = inputs.split_once(": ")?;
+    Span::shown_at_start_of(first)
+}
+
+/// The number of generic parameters in scope around the method or function of `program`
+/// whose header is written at `header`, and the name of its class, if it is in one.
+fn enclosing_parameters(program: &Program, header: &Span) -> Option<(usize, Option<String>)> {
+    program.decls.iter().find_map(|decl| match decl {
+        Decl::ClassDecl(class) => {
+            let (vars, data) = class.binder.open();
+            data.methods
+                .iter()
+                .any(|method| method.span == *header)
+                .then(|| (vars.len(), Some(format!("{:?}", class.name))))
+        }
+        Decl::ImplDecl(impl_decl) => {
+            let (vars, data) = impl_decl.binder.open();
+            data.methods
+                .iter()
+                .any(|method| method.span == *header)
+                .then_some((vars.len(), None))
+        }
+        Decl::TraitDecl(trait_decl) => trait_decl
+            .methods
+            .iter()
+            .any(|method| method.span == *header)
+            .then_some((0, None)),
+        Decl::FnDecl(fn_decl) => (fn_decl.span == *header).then_some((0, None)),
+        Decl::EnumDecl(_) => None,
+    })
+}
+
+/// The names declared by the generic parameter list (`[ty T, perm P]`) at the start of
+/// `text`, if there is one.
+fn generic_names(text: &str) -> Vec<&str> {
+    let Some((parameters, _)) = text
+        .trim_start()
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once(']'))
+    else {
+        return vec![];
+    };
+    parameters
+        .split(',')
+        .filter_map(|parameter| parameter.split_whitespace().nth(1))
+        .collect()
+}
+
+/// The byte ranges of the occurrences of the keyword `keyword` followed by the name `name`
+/// (e.g., `let x`) in `text`.
+fn keyword_then_name<'t>(
+    text: &'t str,
+    keyword: &'t str,
+    name: &'t str,
+) -> impl Iterator<Item = Range<usize>> + 't {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(keyword).filter_map(move |(start, _)| {
+        if text[..start].ends_with(is_ident) {
+            return None;
+        }
+        let rest = &text[start + keyword.len()..];
+        let after = rest
+            .strip_prefix(char::is_whitespace)?
+            .trim_start()
+            .strip_prefix(name)?;
+        if after.starts_with(is_ident) {
+            return None;
+        }
+        Some(start..text.len() - after.len())
+    })
+}
@@ -0,0 +1,372 @@
+//! Classification of common borrow-check failures.
+//!
+//! Most borrow-check failures bottom out in a handful of leaf judgments (see `accesses.rs`
+//! and `move_place` in `expressions.rs`). Rather than showing the user the proof tree, we
+//! recognize the rule of those judgments that failed on a path through the failed judgment,
+//! read back the places it was checking, and report them as a [`DadaError`] naming the
+//! places and liens involved.
+
+use std::fmt;
+
+use formality_core::judgment::{FailedJudgment, RuleFailureCause};
+use formality_core::parse::CoreParse;
+
+use crate::dada_lang::{self, FormalityLang};
+use crate::grammar::{Access, Place, Projection, Statement, Var};
+use crate::type_system::local_liens::Lien;
+
+use super::{failed_rules, judgment_input};
+
+/// A borrow-check failure we know how to explain.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DadaError {
+    /// `place` is given away but is used again later.
+    GiveOfLivePlace { place: Place },
+
+    /// `place` is given away while `lien` (held by the live variable `borrower`,
+    /// if known) still refers to it.
+    GiveWhileBorrowed {
+        place: Place,
+        lien: Lien,
+        borrower: Option<Var>,
+    },
+
+    /// `place` is mutated or dropped while a `ref[..]` lien
+    /// (held by the live variable `borrower`, if known) refers to it.
+    MutateWhileShared {
+        access: Access,
+        place: Place,
+        lien: Lien,
+        borrower: Option<Var>,
+    },
+
+    /// `place` is accessed while a `mut[..]` lien
+    /// (held by the live variable `borrower`, if known) refers to it.
+    AccessWhileLeased {
+        access: Access,
+        place: Place,
+        lien: Lien,
+        borrower: Option<Var>,
+    },
+
+    /// A field of `place` is assigned but `place` is only reachable through
+    /// a permission that is not unique (e.g., `ref[..]`).
+    MutationThroughRef { place: Place },
+
+    /// `place` is mutated through its owner `owner`, whose permission involves a permission
+    /// variable or an `or(..)` not known to be unique. If the permission is a permission
+    /// variable of the enclosing method or function, `var` is its index and `var_name`
+    /// its name, once known (see [`DadaError::with_var_name`]).
+    MutationThroughMaybeShared {
+        owner: Place,
+        var: Option<usize>,
+        var_name: Option<String>,
+    },
+
+    /// `place` holds a value of a `tracked class` but is dropped without being given away.
+    TrackedValueDropped { place: Place },
+}
+
+impl DadaError {
+    /// The lien that conflicts with the access, if any.
+    pub fn lien(&self) -> Option<&Lien> {
+        match self {
+            DadaError::GiveWhileBorrowed { lien, .. }
+            | DadaError::MutateWhileShared { lien, .. }
+            | DadaError::AccessWhileLeased { lien, .. } => Some(lien),
+            DadaError::GiveOfLivePlace { .. }
+            | DadaError::MutationThroughRef { .. }
+            | DadaError::MutationThroughMaybeShared { .. }
+            | DadaError::TrackedValueDropped { .. } => None,
+        }
+    }
+
+    /// The index of the universal variable this error refers to, if any.
+    pub fn universal_var(&self) -> Option<usize> {
+        match self {
+            DadaError::MutationThroughMaybeShared { var, .. } => *var,
+            _ => None,
+        }
+    }
+
+    /// Refer to the universal variable of [`Self::universal_var`] by its source name `name`.
+    pub fn with_var_name(self, name: &str) -> DadaError {
+        match self {
+            DadaError::MutationThroughMaybeShared {
+                owner,
+                var: var @ Some(_),
+                ..
+            } => DadaError::MutationThroughMaybeShared {
+                owner,
+                var,
+                var_name: Some(name.to_string()),
+            },
+            error => error,
+        }
+    }
+
+    /// A suggestion to show at the header of the enclosing method or function, if any.
+    pub fn help(&self) -> Option<String> {
+        match self {
+            // Only a permission variable can be constrained by a `where` clause.
+            DadaError::MutationThroughMaybeShared {
+                var_name: Some(name),
+                ..
+            } => Some(format!(
+                "add a `where` clause requiring `{name}` to be `mut`"
+            )),
+            _ => None,
+        }
+    }
+
+    /// Classify the failure recorded along `path` through `failed`, a list of judgments
+    /// from the outermost to the innermost (see [`super::failure_paths`]), based on the
+    /// innermost judgment we recognize.
+    pub fn classify(failed: &FailedJudgment, path: &[String]) -> Option<DadaError> {
+        (0..path.len())
+            .rev()
+            .find_map(|index| Self::classify_at(failed, path, index))
+    }
+
+    fn classify_at(failed: &FailedJudgment, path: &[String], index: usize) -> Option<DadaError> {
+        let judgment = &path[index];
+        let rules = failed_rules(failed, &path[..=index]);
+        let failed_on_condition = |names: &[&str]| {
+            rules.iter().any(|(name, cause)| {
+                names.contains(name) && matches!(cause, RuleFailureCause::IfFalse { .. })
+            })
+        };
+        let failed_in_next = |name: &str| {
+            let Some(next) = path.get(index + 1) else {
+                return false;
+            };
+            rules.iter().any(|(rule, cause)| match cause {
+                RuleFailureCause::FailedJudgment(child) => *rule == name && child.judgment == *next,
+                _ => false,
+            })
+        };
+        let borrower = || {
+            path[..index]
+                .iter()
+                .rev()
+                .find_map(|j| term_input::<Var>(j, "live_variable_permits_access", "var"))
+        };
+
+        // A place that is not copied can only be given away if it is dead; that is the
+        // condition of the "give" rule. Any other failure to move is not about liveness.
+        if let Some(place) = term_input::<Place>(judgment, "move_place", "place") {
+            return failed_on_condition(&["give"]).then_some(DadaError::GiveOfLivePlace { place });
+        }
+
+        if let Some(shared_place) =
+            term_input::<Place>(judgment, "ref_place_permits_access", "shared_place")
+        {
+            if !failed_on_condition(&["share-mutation", "share-give"]) {
+                return None;
+            }
+            let access = term_input::<Access>(judgment, "ref_place_permits_access", "access")?;
+            let place =
+                term_input::<Place>(judgment, "ref_place_permits_access", "accessed_place")?;
+            let lien = Lien::Rf(shared_place);
+            let borrower = borrower();
+            return Some(match access {
+                Access::Gv => DadaError::GiveWhileBorrowed {
+                    place,
+                    lien,
+                    borrower,
+                },
+                _ => DadaError::MutateWhileShared {
+                    access,
+                    place,
+                    lien,
+                    borrower,
+                },
+            });
+        }
+
+        if let Some(leased_place) =
+            term_input::<Place>(judgment, "mut_place_permits_access", "leased_place")
+        {
+            if !failed_on_condition(&["lease-mutation", "lease-give"]) {
+                return None;
+            }
+            let access = term_input::<Access>(judgment, "mut_place_permits_access", "access")?;
+            let place =
+                term_input::<Place>(judgment, "mut_place_permits_access", "accessed_place")?;
+            let lien = Lien::Mt(leased_place);
+            let borrower = borrower();
+            return Some(match access {
+                Access::Gv => DadaError::GiveWhileBorrowed {
+                    place,
+                    lien,
+                    borrower,
+                },
+                _ => DadaError::AccessWhileLeased {
+                    access,
+                    place,
+                    lien,
+                    borrower,
+                },
+            });
+        }
+
+        if let Some(place) = term_input::<Place>(judgment, "place_not_abandoned", "place") {
+            return failed_in_next("droppable").then_some(DadaError::TrackedValueDropped { place });
+        }
+
+        if let Some(owner) = term_input::<Place>(judgment, "owner_permits_mutation", "owner") {
+            if !failed_in_next("perm may be shared") {
+                return None;
+            }
+            let perm = judgment_input(&path[index + 1], "prove_is_move", "a")?;
+            return Some(DadaError::MutationThroughMaybeShared {
+                owner,
+                var: universal_perm_var(perm),
+                var_name: None,
+            });
+        }
+
+        // The "reassign" rule requires the owner of the assigned field to be unique.
+        let statement = term_input::<Statement>(judgment, "type_statement", "statement")?;
+        let Statement::Reassign(place, ..) = statement else {
+            return None;
+        };
+        if !failed_in_next("reassign") || !path[index + 1].starts_with("prove_is_move {") {
+            return None;
+        }
+        Some(DadaError::MutationThroughRef {
+            place: place.owner().unwrap_or(place),
+        })
+    }
+}
+
+impl fmt::Display for DadaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DadaError::GiveOfLivePlace { place } => {
+                write!(
+                    f,
+                    "cannot give `{}` because it is used later",
+                    written(place)
+                )
+            }
+            DadaError::GiveWhileBorrowed {
+                place,
+                lien,
+                borrower,
+            } => {
+                write!(f, "cannot give `{}` because it is borrowed", written(place))?;
+                write_borrower(f, lien, borrower)
+            }
+            DadaError::MutateWhileShared {
+                access,
+                place,
+                lien,
+                borrower,
+            } => {
+                write!(
+                    f,
+                    "cannot {} `{}` because it is referenced",
+                    verb(access),
+                    written(place)
+                )?;
+                write_borrower(f, lien, borrower)
+            }
+            DadaError::AccessWhileLeased {
+                access,
+                place,
+                lien,
+                borrower,
+            } => {
+                write!(
+                    f,
+                    "cannot {} `{}` because it is leased",
+                    verb(access),
+                    written(place)
+                )?;
+                write_borrower(f, lien, borrower)
+            }
+            DadaError::MutationThroughRef { place } => write!(
+                f,
+                "cannot assign to a field of `{}` because it is not uniquely owned",
+                written(place)
+            ),
+            DadaError::MutationThroughMaybeShared {
+                owner, var_name, ..
+            } => match var_name {
+                Some(name) => write!(
+                    f,
+                    "cannot mutate through `{}` because its permission `{name}` may be shared",
+                    written(owner)
+                ),
+                None => write!(
+                    f,
+                    "cannot mutate through `{}` because its permission may be shared",
+                    written(owner)
+                ),
+            },
+            DadaError::TrackedValueDropped { place } => write!(
+                f,
+                "`{}` holds a tracked value, which is dropped here without being given away",
+                written(place)
+            ),
+        }
+    }
+}
+
+fn write_borrower(f: &mut fmt::Formatter<'_>, lien: &Lien, borrower: &Option<Var>) -> fmt::Result {
+    match borrower {
+        Some(borrower) => write!(
+            f,
+            " by `{borrower:?}` (`{}`, live here)",
+            written_lien(lien)
+        ),
+        None => write!(f, " (`{}`, live here)", written_lien(lien)),
+    }
+}
+
+fn verb(access: &Access) -> &'static str {
+    match access {
+        Access::Rf => "reference",
+        Access::Mt => "mutate",
+        Access::Drop => "drop",
+        Access::Gv => "give",
+    }
+}
+
+/// `place` as it is written in the source (e.g., `p.value`), rather than as it
+/// is printed by `Debug` (`p . value`).
+pub(super) fn written(place: &Place) -> String {
+    let mut text = format!("{:?}", place.var);
+    for projection in &place.projections {
+        match projection {
+            Projection::Field(field) => text.push_str(&format!(".{field:?}")),
+            Projection::Index(index) => text.push_str(&format!("[{index}]")),
+        }
+    }
+    text
+}
+
+/// `lien` as it is written in a permission (e.g., `ref[p.value]`).
+pub(super) fn written_lien(lien: &Lien) -> String {
+    match lien {
+        Lien::Rf(place) => format!("ref[{}]", written(place)),
+        Lien::Mt(place) => format!("mut[{}]", written(place)),
+    }
+}
+
+/// If `judgment` is the `Debug` text of an instance of the judgment `name`,
+/// its input `input` parsed back into a term.
+fn term_input<T>(judgment: &str, name: &str, input: &str) -> Option<T>
+where
+    T: CoreParse<FormalityLang>,
+{
+    dada_lang::try_term(judgment_input(judgment, name, input)?).ok()
+}
+
+/// The index of the universal permission variable printed as `text`, if it is exactly one
+/// (a `Perm::Var`, printed as `!perm_0`) rather than a permission involving one.
+/// Universal variables cannot be parsed back, so we read the index from the text.
+fn universal_perm_var(text: &str) -> Option<usize> {
+    text.strip_prefix("!perm_")?.parse().ok()
+}
@@ -0,0 +1,166 @@
+//! Where the liens named in a [`DadaError`](super::DadaError) are created.
+//!
+//! A `ref[p]` lien is created by the place expression `p.ref` and a `mut[p]` lien by
+//! `p.mut`, or by a closure that captures `p` with that access (see
+//! [`Expr::closure_captures`]). The type system does not record where a lien came from,
+//! so we look for those expressions in the body of the method or function that failed.
+
+use formality_core::Upcast;
+
+use crate::grammar::{
+    Access, Arguments, Block, Decl, Expr, MatchArm, MethodBody, Place, Program, Span, Statement,
+};
+use crate::type_system::local_liens::Lien;
+
+/// The spans of the expressions in the body of the method or function of `program` whose
+/// header is written at `header` that create `lien`, in the order they are written.
+/// A closure has no span of its own, so for a closure we give that of its statement.
+pub(super) fn lien_origins(program: &Program, header: &Span, lien: &Lien) -> Vec<Span> {
+    let Some(body) = body_of(program, header) else {
+        return vec![];
+    };
+    let (place, access) = match lien {
+        Lien::Rf(place) => (place, Access::Rf),
+        Lien::Mt(place) => (place, Access::Mt),
+    };
+    let mut origins = LienOrigins {
+        place,
+        access,
+        statement: Span::default(),
+        found: vec![],
+    };
+    origins.block(&body);
+    origins.found
+}
+
+/// The body of the method or function of `program` whose header is written at `header`.
+fn body_of(program: &Program, header: &Span) -> Option<Block> {
+    let body = program.decls.iter().find_map(|decl| match decl {
+        Decl::ClassDecl(class) => {
+            let (_, data) = class.binder.open();
+            data.methods
+                .into_iter()
+                .find(|method| method.span == *header)
+                .map(|method| method.binder.open().1.body)
+        }
+        Decl::ImplDecl(impl_decl) => {
+            let (_, data) = impl_decl.binder.open();
+            data.methods
+                .into_iter()
+                .find(|method| method.span == *header)
+                .map(|method| method.binder.open().1.body)
+        }
+        Decl::TraitDecl(trait_decl) => trait_decl
+            .methods
+            .iter()
+            .find(|method| method.span == *header)
+            .map(|method| method.binder.open().1.body),
+        Decl::FnDecl(fn_decl) => (fn_decl.span == *header).then(|| fn_decl.binder.open().1.body),
+        Decl::EnumDecl(_) => None,
+    })?;
+    match body {
+        MethodBody::Trusted => None,
+        MethodBody::Block(block) => Some(block),
+    }
+}
+
+struct LienOrigins<'l> {
+    place: &'l Place,
+    access: Access,
+
+    /// The span of the innermost statement being visited.
+    statement: Span,
+
+    found: Vec<Span>,
+}
+
+impl LienOrigins<'_> {
+    /// True if accessing `place` with `access` creates the lien we are looking for.
+    fn creates_lien(&self, place: &Place, access: Access) -> bool {
+        place == self.place && access == self.access
+    }
+
+    fn block(&mut self, block: &Block) {
+        for statement in &block.statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let outer = std::mem::replace(&mut self.statement, statement.span().clone());
+        match statement {
+            Statement::Expr(expr, _)
+            | Statement::Let(_, _, expr, _)
+            | Statement::Reassign(_, expr, _)
+            | Statement::Return(expr, _)
+            | Statement::Print(expr, _) => self.expr(expr),
+            Statement::Loop(block, _) => self.block(block),
+            Statement::Break(_) => {}
+        }
+        self.statement = outer;
+    }
+
+    fn exprs<'e>(&mut self, exprs: impl IntoIterator<Item = &'e Expr>) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Block(block) => self.block(block),
+            Expr::Integer(_)
+            | Expr::String(_)
+            | Expr::Char(_)
+            | Expr::True
+            | Expr::False
+            | Expr::SizeOf(_)
+            | Expr::Panic
+            | Expr::Clear(_) => {}
+            Expr::Or(lhs, rhs)
+            | Expr::And(lhs, rhs)
+            | Expr::Comparison(lhs, _, rhs)
+            | Expr::Additive(lhs, _, rhs)
+            | Expr::Multiplicative(lhs, _, rhs) => self.exprs([&**lhs, &**rhs]),
+            Expr::Unary(_op, expr) => self.expr(expr),
+            Expr::Place(place_expr) => {
+                if self.creates_lien(&place_expr.place, place_expr.access) {
+                    self.found.push(place_expr.span.clone());
+                }
+            }
+            Expr::Share(expr) | Expr::Await(expr) | Expr::Spawn(expr) => self.expr(expr),
+            // The places used in the body of a closure are its captures,
+            // whose liens are created where the closure is.
+            Expr::Closure(inputs, _output, body) => {
+                let captures = Expr::closure_captures(inputs, body);
+                let captured = captures
+                    .into_iter()
+                    .any(|(var, access)| self.creates_lien(&var.upcast(), access));
+                if captured {
+                    self.found.push(self.statement.clone());
+                }
+            }
+            Expr::Tuple(exprs)
+            | Expr::CallFn(_, _, Arguments { exprs, .. })
+            | Expr::New(_, _, Arguments { exprs, .. })
+            | Expr::NewVariant(_, _, _, Arguments { exprs, .. }) => self.exprs(exprs),
+            Expr::Call(receiver, _method_name, _parameters, Arguments { exprs, .. }) => {
+                self.expr(receiver);
+                self.exprs(exprs);
+            }
+            Expr::Match(scrutinee, arms) => {
+                self.expr(scrutinee);
+                for MatchArm { body, .. } in arms {
+                    self.block(body);
+                }
+            }
+            Expr::If(cond, if_true, if_false) => self.exprs([&**cond, &**if_true, &**if_false]),
+            Expr::ArrayNew(_, expr) | Expr::ArrayCapacity(_, expr) | Expr::IsLastRef(_, expr) => {
+                self.expr(expr)
+            }
+            Expr::ArrayGive(_, array, index) => self.exprs([&**array, &**index]),
+            Expr::ArrayDrop(_, array, from, to) => self.exprs([&**array, &**from, &**to]),
+            Expr::ArrayWrite(_, array, index, value) => self.exprs([&**array, &**index, &**value]),
+        }
+    }
+}
@@ -0,0 +1,679 @@
+//! Differential soundness fuzzing.
+//!
+//! The type checker is meant to be sound with respect to the interpreter:
+//! a program that type-checks must never fault when it runs (e.g., with an
+//! "access of uninitialized value" or a refcount or flags mismatch).
+//! [`fuzz`] tests that claim by generating random programs (see [`generate_program`]),
+//! discarding those that the type checker rejects, and interpreting the rest.
+
+use std::fmt;
+use std::sync::Arc;
+
+use formality_core::judgment::ProofTree;
+
+use crate::{
+    dada_lang, grammar::Program, interpreter::Fault, minimize::interpret_fault,
+    proof_tree::proof_tree_to_json, rng::Rng, type_system,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// The result of a [`fuzz`] run.
+#[derive(Debug)]
+pub struct FuzzReport {
+    /// Number of programs generated.
+    pub generated: usize,
+
+    /// Number of generated programs that do not parse, which is a bug in the generator.
+    pub unparsed: usize,
+
+    /// Number of generated programs that the type checker accepted.
+    pub well_typed: usize,
+
+    pub bugs: Vec<SoundnessBug>,
+}
+
+/// A program that type-checks and yet faults in the interpreter.
+#[derive(Debug)]
+pub struct SoundnessBug {
+    /// The seed from which [`generate_program`] produced `program`.
+    pub seed: u64,
+    pub program: String,
+    pub fault: String,
+    pub proof_tree: ProofTree,
+}
+
+impl fmt::Display for SoundnessBug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "soundness bug (seed {}): {}", self.seed, self.fault)?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.program)?;
+        let json = proof_tree_to_json(&self.proof_tree);
+        let json = serde_json::to_string_pretty(&json).map_err(|_| fmt::Error)?;
+        write!(f, "proof tree:\n{json}")
+    }
+}
+
+/// Generate `count` programs from the seeds `seed`, `seed + 1`, ...
+/// and report those that type-check but fault in the interpreter.
+pub fn fuzz(seed: u64, count: usize) -> FuzzReport {
+    let mut report = FuzzReport {
+        generated: 0,
+        unparsed: 0,
+        well_typed: 0,
+        bugs: vec![],
+    };
+
+    for seed in (seed..).take(count) {
+        let program = generate_program(seed);
+        report.generated += 1;
+        match check_and_run(&program) {
+            Verdict::Unparsed => report.unparsed += 1,
+            Verdict::IllTyped => {}
+            Verdict::Ok => report.well_typed += 1,
+            Verdict::Fault { fault, proof_tree } => {
+                report.well_typed += 1;
+                report.bugs.push(SoundnessBug {
+                    seed,
+                    program,
+                    fault,
+                    proof_tree,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// The outcome of [`check_and_run`].
+#[derive(Debug)]
+pub enum Verdict {
+    /// The program does not parse.
+    Unparsed,
+
+    /// The program does not type-check.
+    IllTyped,
+
+    /// The program type-checks and runs without an unexpected fault.
+    Ok,
+
+    /// The program type-checks but the interpreter faults (or panics).
+    Fault {
+        fault: String,
+        proof_tree: ProofTree,
+    },
+}
+
+/// Type-check `text` and, if it is accepted, interpret it.
+pub fn check_and_run(text: &str) -> Verdict {
+    let Ok(program) = dada_lang::try_term::<Arc<Program>>(text) else {
+        return Verdict::Unparsed;
+    };
+    let Ok(((), proof_tree)) = type_system::check_program(&program).into_singleton() else {
+        return Verdict::IllTyped;
+    };
+
+    // A well-typed program can always be elaborated, so a failure here is a bug too.
+    let fault = match type_system::elaborate_program(&program) {
+        Ok(program) => interpret_fault(&program),
+        Err(e) => Some(e),
+    };
+    let Some(fault) = fault else {
+        return Verdict::Ok;
+    };
+
+    // The faults that the interpreter checks for by design do not indicate that
+    // the type checker accepted a bad program; any other fault, or a panic, does.
+    if Fault::kind_of(&fault).is_some() {
+        return Verdict::Ok;
+    }
+    Verdict::Fault {
+        fault: format!("{fault:#}"),
+        proof_tree,
+    }
+}
+
+/// Generate a random program from `seed`; the same seed always yields the same program.
+///
+/// The program declares a few classes (possibly generic, with methods and drop bodies)
+/// and a `Main.main` that creates values and then gives, references, mutates, shares,
+/// and drops them, with some arrays, blocks, `if`s, and loops mixed in.
+/// The generator tracks the types of local variables and which of them have been moved
+/// so that a useful fraction of the programs type-check; it makes no attempt to
+/// get borrows right, which is left to the type checker.
+pub fn generate_program(seed: u64) -> String {
+    Generator::new(seed).program()
+}
+
+/// The type of a value in a generated program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum GenTy {
+    Int,
+
+    /// An `Array[Int]`.
+    Array,
+
+    /// The class with the given index, with its type argument if it is generic.
+    Class(usize, Option<Box<GenTy>>),
+}
+
+/// The declared type of a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum FieldTy {
+    Int,
+    Array,
+
+    /// A (non-generic) class declared earlier.
+    Class(usize),
+
+    /// The class's type parameter `T`.
+    Param,
+}
+
+struct GenClass {
+    name: String,
+    generic: bool,
+    fields: Vec<FieldTy>,
+
+    /// The permission of `self` in the class's `get` method, if it has one.
+    method: Option<&'static str>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LocalPerm {
+    Given,
+    Shared,
+    Ref,
+    Mut,
+}
+
+#[derive(Clone, Debug)]
+struct Local {
+    name: String,
+    ty: GenTy,
+    perm: LocalPerm,
+    moved: bool,
+}
+
+struct Generator {
+    rng: Rng,
+    classes: Vec<GenClass>,
+    locals: Vec<Local>,
+    next_local: usize,
+    out: String,
+}
+
+/// How deeply blocks, `if`s, and loops may nest in `main`.
+const MAX_DEPTH: usize = 2;
+
+impl Generator {
+    fn new(seed: u64) -> Self {
+        Generator {
+            rng: Rng::new(seed),
+            classes: vec![],
+            locals: vec![],
+            next_local: 0,
+            out: String::new(),
+        }
+    }
+
+    fn program(mut self) -> String {
+        for index in 0..1 + self.rng.below(3) {
+            self.class(index);
+        }
+
+        self.line(0, "class Main {");
+        self.line(1, "fn main(given self) -> Int {");
+        for _ in 0..3 + self.rng.below(8) {
+            self.statement(2, 0);
+        }
+        self.line(2, "0;");
+        self.line(1, "}");
+        self.line(0, "}");
+        self.out
+    }
+
+    fn line(&mut self, indent: usize, text: &str) {
+        for _ in 0..indent {
+            self.out.push_str("    ");
+        }
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    // --- Declarations ---
+
+    fn class(&mut self, index: usize) {
+        let name = format!("C{index}");
+        let generic = self.rng.one_in(3);
+        let predicate = match self.rng.below(8) {
+            0 => "given ",
+            1 => "shared ",
+            _ => "",
+        };
+
+        let mut fields = vec![];
+        for _ in 0..self.rng.below(4) {
+            let earlier: Vec<usize> = (0..index).filter(|&i| !self.classes[i].generic).collect();
+            let field = match self.rng.below(6) {
+                0 if generic => FieldTy::Param,
+                1 => FieldTy::Array,
+                2 | 3 if !earlier.is_empty() => FieldTy::Class(self.rng.pick(&earlier)),
+                _ => FieldTy::Int,
+            };
+            fields.push(field);
+        }
+        let int_fields: Vec<usize> = (0..fields.len())
+            .filter(|&f| fields[f] == FieldTy::Int)
+            .collect();
+
+        let method = if self.rng.one_in(2) {
+            Some(self.rng.pick(&["ref", "given", "mut"]))
+        } else {
+            None
+        };
+
+        let header = if generic {
+            format!("{predicate}class {name}[ty T] {{")
+        } else {
+            format!("{predicate}class {name} {{")
+        };
+        self.line(0, &header);
+        for (f, field) in fields.iter().enumerate() {
+            let ty = match field {
+                FieldTy::Int => "Int".to_string(),
+                FieldTy::Array => "Array[Int]".to_string(),
+                FieldTy::Class(c) => self.classes[*c].name.clone(),
+                FieldTy::Param => "T".to_string(),
+            };
+            self.line(1, &format!("f{f}: {ty};"));
+        }
+
+        if let Some(perm) = method {
+            self.line(1, &format!("fn get({perm} self) -> Int {{"));
+            match int_fields.first() {
+                Some(f) => {
+                    if perm == "mut" && self.rng.one_in(2) {
+                        let value = self.rng.below(100);
+                        self.line(2, &format!("self.f{f} = {value};"));
+                    }
+                    self.line(2, &format!("self.f{f}.give;"));
+                }
+                None => self.line(2, "0;"),
+            }
+            self.line(1, "}");
+        }
+
+        if self.rng.one_in(3) {
+            self.line(1, "drop {");
+            match int_fields.first() {
+                Some(f) => self.line(2, &format!("print(self.f{f}.ref);")),
+                None => self.line(2, &format!("print({index});")),
+            }
+            self.line(1, "}");
+        }
+        self.line(0, "}");
+
+        self.classes.push(GenClass {
+            name,
+            generic,
+            fields,
+            method,
+        });
+    }
+
+    // --- Types and values ---
+
+    fn random_ty(&mut self) -> GenTy {
+        match self.rng.below(6) {
+            0 => GenTy::Int,
+            1 => GenTy::Array,
+            _ => {
+                let class = self.rng.below(self.classes.len());
+                let argument = if self.classes[class].generic {
+                    let non_generic: Vec<usize> = (0..self.classes.len())
+                        .filter(|&c| !self.classes[c].generic)
+                        .collect();
+                    Some(Box::new(match self.rng.below(3) {
+                        0 if !non_generic.is_empty() => {
+                            GenTy::Class(self.rng.pick(&non_generic), None)
+                        }
+                        _ => GenTy::Int,
+                    }))
+                } else {
+                    None
+                };
+                GenTy::Class(class, argument)
+            }
+        }
+    }
+
+    fn ty_text(&self, ty: &GenTy) -> String {
+        match ty {
+            GenTy::Int => "Int".to_string(),
+            GenTy::Array => "Array[Int]".to_string(),
+            GenTy::Class(c, None) => self.classes[*c].name.clone(),
+            GenTy::Class(c, Some(argument)) => {
+                format!("{}[{}]", self.classes[*c].name, self.ty_text(argument))
+            }
+        }
+    }
+
+    fn field_tys(&self, ty: &GenTy) -> Vec<GenTy> {
+        let GenTy::Class(c, argument) = ty else {
+            return vec![];
+        };
+        self.classes[*c]
+            .fields
+            .iter()
+            .map(|field| match field {
+                FieldTy::Int => GenTy::Int,
+                FieldTy::Array => GenTy::Array,
+                FieldTy::Class(d) => GenTy::Class(*d, None),
+                FieldTy::Param => (**argument.as_ref().unwrap()).clone(),
+            })
+            .collect()
+    }
+
+    /// An expression that produces an owned value of type `ty`.
+    fn value(&mut self, ty: &GenTy) -> String {
+        if self.rng.one_in(3) {
+            if let Some(local) = self
+                .pick_local(|l| l.ty == *ty && (l.perm == LocalPerm::Given || l.ty == GenTy::Int))
+            {
+                return self.give(local);
+            }
+        }
+
+        match ty {
+            GenTy::Int if self.rng.one_in(4) => {
+                let lhs = self.value(ty);
+                format!("{lhs} + {}", self.rng.below(10))
+            }
+            GenTy::Int => self.rng.below(100).to_string(),
+            // Arrays are only ever read after all of their elements are written
+            // (see `array_statements`), so new arrays elsewhere are empty.
+            GenTy::Array => "array_new[Int](0)".to_string(),
+            GenTy::Class(c, argument) => {
+                let parameters = match argument {
+                    Some(argument) => format!("[{}]", self.ty_text(argument)),
+                    None => String::new(),
+                };
+                let arguments: Vec<String> =
+                    self.field_tys(ty).iter().map(|f| self.value(f)).collect();
+                format!(
+                    "new {}{parameters}({})",
+                    self.classes[*c].name,
+                    arguments.join(", ")
+                )
+            }
+        }
+    }
+
+    // --- Locals ---
+
+    /// Pick one of the locals that have not been moved and satisfy `filter`.
+    fn pick_local(&mut self, filter: impl Fn(&Local) -> bool) -> Option<usize> {
+        let candidates: Vec<usize> = (0..self.locals.len())
+            .filter(|&l| !self.locals[l].moved && filter(&self.locals[l]))
+            .collect();
+        if candidates.is_empty() {
+            None
+        } else {
+            Some(self.rng.pick(&candidates))
+        }
+    }
+
+    /// `local.give`, marking the local as moved unless it is copied.
+    fn give(&mut self, local: usize) -> String {
+        let local = &mut self.locals[local];
+        if local.ty != GenTy::Int && matches!(local.perm, LocalPerm::Given | LocalPerm::Mut) {
+            local.moved = true;
+        }
+        format!("{}.give", local.name)
+    }
+
+    fn declare(&mut self, indent: usize, expr: &str, ty: GenTy, perm: LocalPerm) -> usize {
+        let name = format!("v{}", self.next_local);
+        self.next_local += 1;
+        self.line(indent, &format!("let {name} = {expr};"));
+        self.locals.push(Local {
+            name,
+            ty,
+            perm,
+            moved: false,
+        });
+        self.locals.len() - 1
+    }
+
+    // --- Statements ---
+
+    fn statement(&mut self, indent: usize, depth: usize) {
+        match self.rng.below(14) {
+            0 | 1 => self.let_access(indent),
+            2 => self.print(indent),
+            3 | 4 => self.assign_field(indent),
+            5 => self.reassign(indent),
+            6 => self.call_method(indent),
+            7 => self.array_statements(indent),
+            8 => self.drop_local(indent),
+            9 if depth < MAX_DEPTH => self.nested(indent, depth),
+            _ => {
+                let ty = self.random_ty();
+                let expr = self.value(&ty);
+                self.declare(indent, &expr, ty, LocalPerm::Given);
+            }
+        }
+    }
+
+    /// `let v = w.give`, `w.ref`, `w.mut`, `w.give.share` or `w.fN.give`.
+    fn let_access(&mut self, indent: usize) {
+        let Some(local) = self.pick_local(|l| l.ty != GenTy::Int) else {
+            return;
+        };
+        let Local { name, ty, perm, .. } = self.locals[local].clone();
+
+        match self.rng.below(5) {
+            0 => {
+                let expr = self.give(local);
+                self.declare(indent, &expr, ty, perm);
+            }
+            1 => {
+                self.declare(indent, &format!("{name}.ref"), ty, LocalPerm::Ref);
+            }
+            2 if matches!(perm, LocalPerm::Given | LocalPerm::Mut) => {
+                self.declare(indent, &format!("{name}.mut"), ty, LocalPerm::Mut);
+            }
+            3 if perm == LocalPerm::Given => {
+                self.locals[local].moved = true;
+                self.declare(indent, &format!("{name}.give.share"), ty, LocalPerm::Shared);
+            }
+            _ => {
+                let field_tys = self.field_tys(&ty);
+                if field_tys.is_empty() {
+                    return;
+                }
+                let f = self.rng.below(field_tys.len());
+                let field_perm = if field_tys[f] == GenTy::Int {
+                    LocalPerm::Given
+                } else {
+                    // Giving a field out of an owned local moves (part of) it;
+                    // we conservatively consider the whole local moved.
+                    if perm == LocalPerm::Given {
+                        self.locals[local].moved = true;
+                    }
+                    perm
+                };
+                let field_ty = field_tys[f].clone();
+                self.declare(indent, &format!("{name}.f{f}.give"), field_ty, field_perm);
+            }
+        }
+    }
+
+    fn print(&mut self, indent: usize) {
+        let Some(local) = self.pick_local(|_| true) else {
+            return;
+        };
+        let name = self.locals[local].name.clone();
+        let field_count = self.field_tys(&self.locals[local].ty).len();
+        if field_count > 0 && self.rng.one_in(2) {
+            let f = self.rng.below(field_count);
+            self.line(indent, &format!("print({name}.f{f}.ref);"));
+        } else {
+            self.line(indent, &format!("print({name}.ref);"));
+        }
+    }
+
+    fn assign_field(&mut self, indent: usize) {
+        let Some(local) = self.pick_local(|l| {
+            matches!(l.perm, LocalPerm::Given | LocalPerm::Mut) && matches!(l.ty, GenTy::Class(..))
+        }) else {
+            return;
+        };
+        let field_tys = self.field_tys(&self.locals[local].ty);
+        if field_tys.is_empty() {
+            return;
+        }
+        let f = self.rng.below(field_tys.len());
+        let value = self.value(&field_tys[f]);
+        let name = &self.locals[local].name;
+        let text = format!("{name}.f{f} = {value};");
+        self.line(indent, &text);
+    }
+
+    /// Assign a new value to an owned local, which re-initializes it if it was moved.
+    fn reassign(&mut self, indent: usize) {
+        let candidates: Vec<usize> = (0..self.locals.len())
+            .filter(|&l| self.locals[l].perm == LocalPerm::Given)
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+        let local = self.rng.pick(&candidates);
+        let ty = self.locals[local].ty.clone();
+        let value = self.value(&ty);
+        let text = format!("{} = {value};", self.locals[local].name);
+        self.line(indent, &text);
+        self.locals[local].moved = false;
+    }
+
+    /// `let v = w.ref.get();` (or `w.mut`, `w.give`, depending on the method's `self`).
+    fn call_method(&mut self, indent: usize) {
+        let with_method: Vec<usize> = (0..self.classes.len())
+            .filter(|&c| self.classes[c].method.is_some())
+            .collect();
+        let Some(local) =
+            self.pick_local(|l| matches!(&l.ty, GenTy::Class(c, _) if with_method.contains(c)))
+        else {
+            return;
+        };
+        let GenTy::Class(c, _) = self.locals[local].ty else {
+            unreachable!()
+        };
+        let method = self.classes[c].method;
+        let receiver = match method {
+            Some("given") => self.give(local),
+            Some(access) => format!("{}.{access}", self.locals[local].name),
+            None => unreachable!(),
+        };
+        self.declare(
+            indent,
+            &format!("{receiver}.get()"),
+            GenTy::Int,
+            LocalPerm::Given,
+        );
+    }
+
+    /// Create an array, write each of its elements, and read some of them back.
+    fn array_statements(&mut self, indent: usize) {
+        let capacity = 1 + self.rng.below(3);
+        let array = self.declare(
+            indent,
+            &format!("array_new[Int]({capacity})"),
+            GenTy::Array,
+            LocalPerm::Given,
+        );
+        let name = self.locals[array].name.clone();
+        for index in 0..capacity {
+            let value = self.value(&GenTy::Int);
+            self.line(
+                indent,
+                &format!("array_write[Int, mut[{name}]]({name}.mut, {index}, {value});"),
+            );
+        }
+        for _ in 0..self.rng.below(3) {
+            let index = self.rng.below(capacity);
+            let expr = format!("array_give[Int, ref[{name}], ref[{name}]]({name}.ref, {index})");
+            self.declare(indent, &expr, GenTy::Int, LocalPerm::Given);
+        }
+        if self.rng.one_in(2) {
+            self.line(
+                indent,
+                &format!("print(array_capacity[Int, ref[{name}]]({name}.ref));"),
+            );
+        }
+    }
+
+    fn drop_local(&mut self, indent: usize) {
+        let Some(local) = self.pick_local(|l| l.ty != GenTy::Int) else {
+            return;
+        };
+        self.locals[local].moved = true;
+        let text = format!("{}.drop;", self.locals[local].name);
+        self.line(indent, &text);
+    }
+
+    /// A block, an `if`, or a `loop` that runs once. Locals declared within are
+    /// not in scope afterwards; a local moved in either branch of an `if` is moved.
+    fn nested(&mut self, indent: usize, depth: usize) {
+        match self.rng.below(3) {
+            0 => {
+                self.line(indent, "{");
+                self.scoped_statements(indent + 1, depth + 1);
+                self.line(indent, "};");
+            }
+            1 => {
+                let condition = match self.pick_local(|l| l.ty == GenTy::Int) {
+                    Some(local) => {
+                        let bound = self.rng.below(100);
+                        format!("{}.give >= {bound}", self.locals[local].name)
+                    }
+                    None => self.rng.pick(&["true", "false"]).to_string(),
+                };
+                let before: Vec<bool> = self.locals.iter().map(|l| l.moved).collect();
+
+                self.line(indent, &format!("if {condition} {{"));
+                self.scoped_statements(indent + 1, depth + 1);
+                let after_then: Vec<bool> = self.locals.iter().map(|l| l.moved).collect();
+
+                for (local, moved) in self.locals.iter_mut().zip(&before) {
+                    local.moved = *moved;
+                }
+                self.line(indent, "} else {");
+                self.scoped_statements(indent + 1, depth + 1);
+                self.line(indent, "};");
+
+                for (local, moved) in self.locals.iter_mut().zip(&after_then) {
+                    local.moved |= *moved;
+                }
+            }
+            _ => {
+                self.line(indent, "loop {");
+                self.scoped_statements(indent + 1, depth + 1);
+                self.line(indent + 1, "break;");
+                self.line(indent, "}");
+            }
+        }
+    }
+
+    fn scoped_statements(&mut self, indent: usize, depth: usize) {
+        let in_scope = self.locals.len();
+        for _ in 0..1 + self.rng.below(3) {
+            self.statement(indent, depth);
+        }
+        self.line(indent, "();");
+        self.locals.truncate(in_scope);
+    }
+}
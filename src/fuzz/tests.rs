@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::{
+    dada_lang,
+    grammar::Program,
+    interpreter::{Fault, FaultKind},
+    minimize::interpret_fault,
+};
+
+use super::{check_and_run, fuzz, generate_program, Verdict};
+
+#[test]
+fn generate_program_is_deterministic() {
+    assert_eq!(generate_program(22), generate_program(22));
+    assert_ne!(generate_program(22), generate_program(23));
+}
+
+#[test]
+fn generated_programs_parse() {
+    for seed in 0..50 {
+        let program = generate_program(seed);
+        if let Err(e) = dada_lang::try_term::<Arc<Program>>(&program) {
+            panic!("seed {seed} generated a program that does not parse: {e:?}\n{program}");
+        }
+    }
+}
+
+#[test]
+fn check_and_run_well_typed() {
+    let verdict = check_and_run(
+        "
+        class Data { x: Int; }
+        class Main {
+            fn main(given self) -> Int {
+                let d = new Data(22);
+                d.x.give;
+            }
+        }
+        ",
+    );
+    assert!(matches!(verdict, Verdict::Ok), "{verdict:?}");
+}
+
+#[test]
+fn check_and_run_unparsed() {
+    let verdict = check_and_run("class Main { fn main(given self) -> Int { 1 + } }");
+    assert!(matches!(verdict, Verdict::Unparsed), "{verdict:?}");
+}
+
+#[test]
+fn check_and_run_ill_typed() {
+    let verdict = check_and_run(
+        "
+        class Data { x: Int; }
+        class Main {
+            fn main(given self) -> Int {
+                let d = new Data(22);
+                let e = d.give;
+                d.x.give;
+            }
+        }
+        ",
+    );
+    assert!(matches!(verdict, Verdict::IllTyped), "{verdict:?}");
+}
+
+/// A fault that the interpreter checks for by design is not a soundness bug.
+#[test]
+fn check_and_run_expected_fault() {
+    let text = "
+        class Main {
+            fn main(given self) -> Int {
+                let a = array_new[Int](2);
+                array_give[Int, given, given](a.give, 5);
+            }
+        }
+        ";
+    let program: Arc<Program> = dada_lang::term(text);
+    let fault = interpret_fault(&program).expect("program should fault");
+    assert_eq!(Fault::kind_of(&fault), Some(FaultKind::ArrayBounds));
+
+    let verdict = check_and_run(text);
+    assert!(matches!(verdict, Verdict::Ok), "{verdict:?}");
+}
+
+/// A panic of the interpreter is always a bug, whatever its message.
+#[test]
+fn interpreter_panic_is_not_an_expected_fault() {
+    let fault = anyhow::anyhow!("interpreter panicked: index out of bounds: the len is 0");
+    assert_eq!(Fault::kind_of(&fault), None);
+}
+
+#[test]
+fn fuzz_counts_programs() {
+    let report = fuzz(0, 10);
+    assert_eq!(report.generated, 10);
+    assert_eq!(report.unparsed, 0);
+    assert!(report.well_typed > 0, "{report:?}");
+    assert!(report.well_typed <= report.generated);
+    assert!(report.bugs.len() <= report.well_typed);
+}
@@ -22,18 +22,70 @@ impl Program {
             .next()
             .ok_or_else(|| anyhow::anyhow!("no class named `{:?}`", name))
     }
+
+    pub fn enum_named(&self, name: &ValueId) -> Fallible<&EnumDecl> {
+        self.decls
+            .iter()
+            .filter_map(|d| d.as_enum_decl())
+            .filter(|d| d.name == *name)
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no enum named `{:?}`", name))
+    }
+
+    pub fn fn_named(&self, name: &ValueId) -> Fallible<&FnDecl> {
+        self.decls
+            .iter()
+            .filter_map(|d| d.as_fn_decl())
+            .filter(|d| d.name == *name)
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no function named `{:?}`", name))
+    }
+
+    pub fn trait_named(&self, name: &ValueId) -> Fallible<&TraitDecl> {
+        self.decls
+            .iter()
+            .filter_map(|d| d.as_trait_decl())
+            .filter(|d| d.name == *name)
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("no trait named `{:?}`", name))
+    }
+
+    /// Returns the impls that apply to `class_ty`, instantiated with the
+    /// parameters that make their class type equal to `class_ty`.
+    pub fn impls_for(&self, class_ty: &NamedTy) -> Fallible<Vec<ImplDeclBoundData>> {
+        let mut impls = vec![];
+        for impl_decl in self.decls.iter().filter_map(|d| d.as_impl_decl()) {
+            if let Some(parameters) = impl_decl.match_class_ty(class_ty) {
+                impls.push(impl_decl.binder.instantiate_with(&parameters)?);
+            }
+        }
+        Ok(impls)
+    }
 }
+mod spawn_impls;
 
 #[term]
 pub enum Decl {
     #[cast]
     ClassDecl(ClassDecl),
+
+    #[cast]
+    EnumDecl(EnumDecl),
+
+    #[cast]
+    FnDecl(FnDecl),
+
+    #[cast]
+    TraitDecl(TraitDecl),
+
+    #[cast]
+    ImplDecl(ImplDecl),
 }
 
 /// Class predicates categorize classes according to how they
 /// can be used. The eventual hierarchy will be
 ///
-/// * `tracked class` -- true linear type that must be moved (not yet fully designed)
+/// * `tracked class` -- true linear type that must be moved
 /// * `given class` -- affine type that must be dropped
 /// * `class` -- the default, a class that whose fields can be mutated
 /// * `shared class` -- a value type that is always considered shared
@@ -45,6 +97,13 @@ pub enum Decl {
 #[term]
 #[derive(Copy, Default)]
 pub enum ClassPredicate {
+    /// `Tracked` classes are linear: their values can never be dropped implicitly
+    /// (at the end of their scope, by being overwritten, or by being discarded as the
+    /// value of a statement). Instead they must be given away, ultimately to one of the
+    /// class's `given self` methods, which consume them. Like `Given` classes, they cannot be shared.
+    #[grammar(tracked)]
+    Tracked,
+
     /// `Given` classes are permitted to have destructors (FIXME: we don't model those right now).
     /// A `Given` class cannot be shared and, since they have a destructor, we cannot drop them
     /// from borrow chains (i.e., `mut[guard] mut[data]` cannot be converted to `mut[data]`
@@ -69,7 +128,7 @@ impl ClassPredicate {
     /// Returns the parameter predicates that type parameters of this class must satisfy.
     pub fn parameter_predicates(self) -> Vec<ParameterPredicate> {
         match self {
-            ClassPredicate::Given => vec![],
+            ClassPredicate::Tracked | ClassPredicate::Given => vec![],
             ClassPredicate::Share => vec![ParameterPredicate::Share],
             ClassPredicate::Shared => vec![ParameterPredicate::Shared],
         }
@@ -77,9 +136,10 @@ impl ClassPredicate {
 }
 
 // ANCHOR: ClassDecl
-#[term($?class_predicate class $name $binder)]
+#[term($?boxed $?class_predicate class $name $binder)]
 pub struct ClassDecl {
     pub name: ValueId,
+    pub boxed: Boxed,
     pub class_predicate: ClassPredicate,
     pub binder: Binder<ClassDeclBoundData>,
 }
@@ -102,6 +162,58 @@ pub struct DropBody {
 
 // ANCHOR_END: ClassDecl
 
+// ANCHOR: Boxed
+/// A `boxed class` stores its fields in a refcounted heap allocation,
+/// like `Array`, so a value of the class is just a `[Flags, Pointer]` pair.
+/// This is what permits a class to (indirectly) contain itself, e.g.
+/// `boxed class List { next: Option[List]; }`.
+#[term]
+#[derive(Copy, Default)]
+pub enum Boxed {
+    #[default]
+    #[grammar(unboxed)]
+    No,
+
+    #[grammar(boxed)]
+    Yes,
+}
+// ANCHOR_END: Boxed
+
+// ANCHOR: EnumDecl
+/// An enum is a value that is exactly one of its variants, each of which carries
+/// its own fields. The class predicate plays the same role as for classes:
+/// a `shared enum` is a value type, a `given enum` cannot be shared.
+#[term($?class_predicate enum $name $binder)]
+pub struct EnumDecl {
+    pub name: ValueId,
+    pub class_predicate: ClassPredicate,
+    pub binder: Binder<EnumDeclBoundData>,
+}
+
+#[term($:where $,predicates { $*variants })]
+pub struct EnumDeclBoundData {
+    pub predicates: Vec<Predicate>,
+    pub variants: Vec<VariantDecl>,
+}
+
+#[term($name { $*fields })]
+pub struct VariantDecl {
+    pub name: ValueId,
+    pub fields: Vec<FieldDecl>,
+}
+// ANCHOR_END: EnumDecl
+
+impl EnumDeclBoundData {
+    /// Returns the index (i.e., the discriminant) and declaration of the variant named `name`.
+    pub fn variant_named(&self, name: &ValueId) -> Fallible<(usize, &VariantDecl)> {
+        self.variants
+            .iter()
+            .enumerate()
+            .find(|(_, v)| v.name == *name)
+            .ok_or_else(|| anyhow::anyhow!("no variant named `{:?}`", name))
+    }
+}
+
 // ANCHOR: FieldDecl
 #[term($?atomic $name : $ty ;)]
 pub struct FieldDecl {
@@ -124,11 +236,57 @@ pub enum Atomic {
 }
 // ANCHOR_END: Atomic
 
+// ANCHOR: Async
+/// Calling an `async fn` does not run its body: it returns a `Future[R, C]`, where `R` is
+/// the declared output type and `C` is the tuple of the types of the arguments (including
+/// `self`) moved into the future. The body runs when the future is `await`ed.
+#[term]
+#[derive(Copy, Default)]
+pub enum Async {
+    #[default]
+    #[grammar(sync)]
+    No,
+
+    #[grammar(async)]
+    Yes,
+}
+// ANCHOR_END: Async
+
+// ANCHOR: CallKind
+/// How a closure may be called, which depends on how it captures the places its
+/// body uses. A `Closure[K, (A...), R, (C...)]` takes arguments of types `A...`,
+/// returns `R`, and holds captured values of types `C...`: `ref[x] T` for a place
+/// `x` that is only read, `mut[x] T` for one that is mutated, and `T` for one
+/// whose value is given away, which moves it into the closure.
+/// Variants are ordered from the weakest receiver permission to the strongest.
+#[term]
+#[derive(Copy)]
+pub enum CallKind {
+    /// Only reads its captures, so it can be called through `ref self`.
+    #[grammar(shared)]
+    Shared,
+
+    /// Mutates its captures, so it must be called through `mut[self] self`.
+    #[grammar(mut)]
+    Mut,
+
+    /// Gives away a captured value, so it can only be called once, through `given self`.
+    #[grammar(once)]
+    Once,
+}
+// ANCHOR_END: CallKind
+mod closure_impls;
+
 // ANCHOR: MethodDecl
-#[term(fn $name $binder)]
+/// `async fn name[...](...) -> ... { ... }` (with `async` optional). The span is that of
+/// the header, `async fn name`. Method declarations are parsed and printed in `span_impls`.
+#[term]
+#[customize(parse, debug)]
 pub struct MethodDecl {
     pub name: MethodId,
+    pub is_async: Async,
     pub binder: Binder<MethodDeclBoundData>,
+    pub span: Span,
 }
 
 // FIXME: need to guard `$inputs` by a comma and output by `->`, using customized parse
@@ -144,6 +302,83 @@ pub struct MethodDeclBoundData {
 // ANCHOR_END: MethodDecl
 mod method_impls;
 
+// ANCHOR: FnDecl
+/// A function declared at the program level. Its signature is that of
+/// a method (see [`MethodDeclBoundData`]) without the `self` parameter.
+/// Like a method, it records the span of its header, `fn name`.
+#[term]
+#[customize(parse, debug)]
+pub struct FnDecl {
+    pub name: ValueId,
+    pub binder: Binder<FnDeclBoundData>,
+    pub span: Span,
+}
+
+#[term(($,inputs) -> $output $:where $,predicates $body)]
+#[customize(parse)]
+pub struct FnDeclBoundData {
+    pub inputs: Vec<LocalVariableDecl>,
+    pub output: Ty,
+    pub predicates: Vec<Predicate>,
+    pub body: MethodBody,
+}
+// ANCHOR_END: FnDecl
+
+// ANCHOR: TraitDecl
+/// A trait is a set of method signatures (with `...;` bodies).
+/// A class implements the trait with an [`ImplDecl`]; code that is generic
+/// over `T` can then call the trait's methods on `T` given a `T is Trait` predicate.
+#[term(trait $name { $*methods })]
+pub struct TraitDecl {
+    pub name: ValueId,
+    pub methods: Vec<MethodDecl>,
+}
+
+/// `impl[ty T] Trait for Class[T] where ... { methods }`
+#[term(impl $binder)]
+pub struct ImplDecl {
+    pub binder: Binder<ImplDeclBoundData>,
+}
+
+#[term($trait_name for $class_ty $:where $,predicates { $*methods })]
+pub struct ImplDeclBoundData {
+    pub trait_name: ValueId,
+    pub class_ty: NamedTy,
+    pub predicates: Vec<Predicate>,
+    pub methods: Vec<MethodDecl>,
+}
+// ANCHOR_END: TraitDecl
+
+impl ImplDecl {
+    /// If this impl applies to `class_ty`, returns the parameters for its binder.
+    ///
+    /// Each parameter of the impl's class type must either be one of the impl's
+    /// own variables or be exactly equal to the corresponding parameter of `class_ty`.
+    pub fn match_class_ty(&self, class_ty: &NamedTy) -> Option<Vec<Parameter>> {
+        let (vars, data) = self.binder.open();
+        if data.class_ty.name != class_ty.name
+            || data.class_ty.parameters.len() != class_ty.parameters.len()
+        {
+            return None;
+        }
+
+        let vars: Vec<Parameter> = vars.iter().map(|v| v.upcast()).collect();
+        let mut parameters: Vec<Option<Parameter>> = vec![None; vars.len()];
+        for (pattern, parameter) in data.class_ty.parameters.iter().zip(&class_ty.parameters) {
+            match vars.iter().position(|v| v == pattern) {
+                Some(index) => match &parameters[index] {
+                    Some(p) if p != parameter => return None,
+                    _ => parameters[index] = Some(parameter.clone()),
+                },
+                None if pattern == parameter => {}
+                None => return None,
+            }
+        }
+
+        parameters.into_iter().collect()
+    }
+}
+
 #[term]
 pub enum MethodBody {
     #[grammar( ...;)]
@@ -165,39 +400,56 @@ pub struct LocalVariableDecl {
 }
 
 // ANCHOR: Block
-#[term({ $*statements })]
+/// `{ statements }`, together with where it was written (see [`Span`]).
+/// Blocks are parsed and printed in `span_impls`.
+#[term]
+#[customize(parse, debug)]
 #[derive(Default)]
 pub struct Block {
     pub statements: Vec<Statement>,
+    pub span: Span,
 }
 // ANCHOR_END: Block
 
+impl Block {
+    /// True if control never reaches the end of this block
+    /// because its final statement is a `break` or `return`.
+    pub fn diverges(&self) -> bool {
+        matches!(
+            self.statements.last(),
+            Some(Statement::Break(_) | Statement::Return(..))
+        )
+    }
+}
+
+/// A statement, together with where it was written (see [`Span`]).
+/// Statements are parsed and printed in `span_impls`.
 #[term]
+#[customize(parse, debug)]
 pub enum Statement {
     // ANCHOR: Statement_Expr
-    #[grammar($v0 ;)]
-    #[cast]
-    Expr(Expr),
+    /// `e;`
+    Expr(Expr, Span),
     // ANCHOR_END: Statement_Expr
 
     // ANCHOR: Statement_Let
-    #[grammar(let $v0 $?v1 = $v2 ;)]
-    Let(ValueId, Ascription, Arc<Expr>),
+    /// `let x = e;` or `let x: T = e;`
+    Let(ValueId, Ascription, Arc<Expr>, Span),
     // ANCHOR_END: Statement_Let
-    #[grammar($v0 = $v1 ;)]
-    Reassign(Place, Expr),
+    /// `p = e;`
+    Reassign(Place, Expr, Span),
 
-    #[grammar(loop $v0)]
-    Loop(Block),
+    /// `loop { ... }`
+    Loop(Block, Span),
 
-    #[grammar(break ;)]
-    Break,
+    /// `break;`
+    Break(Span),
 
-    #[grammar(return $v0 ;)]
-    Return(Expr),
+    /// `return e;`
+    Return(Expr, Span),
 
-    #[grammar(print ( $v0 ) ;)]
-    Print(Expr),
+    /// `print(e);`
+    Print(Expr, Span),
 }
 
 #[term]
@@ -211,7 +463,11 @@ pub enum Ascription {
     Ty(Ty),
 }
 
+/// A binary operator on `Int` (or `Char` or `String`) operands.
+/// Operators are read by the tokenizer in `operator_impls`, which takes the longest
+/// operator at the start of the text, so `<` is never read from the start of `<=`.
 #[term]
+#[customize(parse)]
 pub enum BinaryOp {
     #[grammar(+)]
     Add,
@@ -219,35 +475,104 @@ pub enum BinaryOp {
     #[grammar(-)]
     Sub,
 
+    #[grammar(*)]
+    Mul,
+
+    #[grammar(/)]
+    Div,
+
+    #[grammar(%)]
+    Rem,
+
     #[grammar(>=)]
     Ge,
 
     #[grammar(<=)]
     Le,
 
+    #[grammar(>)]
+    Gt,
+
+    #[grammar(<)]
+    Lt,
+
     #[grammar(==)]
     Eq,
 
     #[grammar(!=)]
     Ne,
-
-    // Note: bare `>` and `<` are omitted because `>` is a prefix of `>=`
-    // and `<` is a prefix of `<=`, causing parser ambiguity in formality-core.
-    // Use `>=` / `<=` / `==` / `!=` instead. A proper tokenizer would resolve
-    // this, but for now the restricted set is sufficient.
 }
 
 impl BinaryOp {
     /// Returns true for operators that take Int operands and return Int.
     pub fn is_arithmetic(&self) -> bool {
-        matches!(self, BinaryOp::Add | BinaryOp::Sub)
+        matches!(
+            self,
+            BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div | BinaryOp::Rem
+        )
+    }
+
+    /// Returns true for the operator that also concatenates String operands.
+    pub fn is_concatenation(&self) -> bool {
+        matches!(self, BinaryOp::Add)
     }
 
-    /// Returns true for operators that take Int operands and return Bool.
+    /// Returns true for operators that take Int (or Char or String) operands and return Bool.
     pub fn is_comparison(&self) -> bool {
-        matches!(self, BinaryOp::Ge | BinaryOp::Le | BinaryOp::Eq | BinaryOp::Ne)
+        matches!(
+            self,
+            BinaryOp::Ge
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Lt
+                | BinaryOp::Eq
+                | BinaryOp::Ne
+        )
+    }
+}
+
+/// The operator of an [`Expr::Comparison`]: `==`, `!=`, `<`, `<=`, `>` or `>=`.
+#[term($op)]
+#[customize(parse)]
+pub struct ComparisonOp {
+    pub op: BinaryOp,
+}
+
+/// The operator of an [`Expr::Additive`]: `+` or `-`.
+#[term($op)]
+#[customize(parse)]
+pub struct AdditiveOp {
+    pub op: BinaryOp,
+}
+
+/// The operator of an [`Expr::Multiplicative`]: `*`, `/` or `%`.
+#[term($op)]
+#[customize(parse)]
+pub struct MultiplicativeOp {
+    pub op: BinaryOp,
+}
+
+/// A prefix operator: `-` negates an `Int` and `!` negates a `Bool`.
+#[term]
+#[customize(parse)]
+pub enum UnaryOp {
+    #[grammar(-)]
+    Neg,
+
+    #[grammar(!)]
+    Not,
+}
+
+impl UnaryOp {
+    /// The type of both the operand and the result.
+    pub fn operand_ty(&self) -> Ty {
+        match self {
+            UnaryOp::Neg => Ty::int(),
+            UnaryOp::Not => Ty::bool(),
+        }
     }
 }
+mod operator_impls;
 
 #[term]
 pub enum Expr {
@@ -258,15 +583,49 @@ pub enum Expr {
     #[grammar($v0)]
     Integer(usize),
     // ANCHOR_END: Expr_Integer
+    /// A string literal `"..."`, whose value is a `String`.
+    #[grammar($v0)]
+    String(StringLiteral),
+
+    /// A char literal `'c'`, whose value is a `Char`.
+    #[grammar($v0)]
+    Char(CharLiteral),
+
     #[grammar(true)]
     True,
 
     #[grammar(false)]
     False,
 
-    #[grammar($v0 $v1 $v2)]
+    // The binary operators, from the loosest to the tightest binding. All of them
+    // associate to the left, so `1 - 2 - 3` is `-4` and `1 + 2 * 3` is `7`;
+    // use a block to group, as in `{ 1 + 2; } * 3`.
+    /// `lhs || rhs`, where `rhs` is only evaluated if `lhs` is `false`.
+    #[grammar($v0 || $v1)]
     #[precedence(0)]
-    BinaryOp(Arc<Expr>, BinaryOp, Arc<Expr>),
+    Or(Arc<Expr>, Arc<Expr>),
+
+    /// `lhs && rhs`, where `rhs` is only evaluated if `lhs` is `true`.
+    #[grammar($v0 && $v1)]
+    #[precedence(1)]
+    And(Arc<Expr>, Arc<Expr>),
+
+    #[grammar($v0 $v1 $v2)]
+    #[precedence(2)]
+    Comparison(Arc<Expr>, ComparisonOp, Arc<Expr>),
+
+    #[grammar($v0 $v1 $v2)]
+    #[precedence(3)]
+    Additive(Arc<Expr>, AdditiveOp, Arc<Expr>),
+
+    #[grammar($v0 $v1 $v2)]
+    #[precedence(4)]
+    Multiplicative(Arc<Expr>, MultiplicativeOp, Arc<Expr>),
+
+    /// `-e` or `!e`, which binds more tightly than any binary operator,
+    /// so that negative literals are written `-1`.
+    #[grammar($v0 $v1)]
+    Unary(UnaryOp, Arc<Expr>),
 
     #[cast]
     Place(PlaceExpr),
@@ -274,16 +633,41 @@ pub enum Expr {
     #[grammar($v0.share)]
     Share(Arc<Expr>),
 
+    /// Runs the future produced by a call to an `async fn` (see [`Async`])
+    /// to completion, consuming it, and produces its result.
+    #[grammar($v0.await)]
+    Await(Arc<Expr>),
+
+    /// Runs the future produced by a call to an `async fn` as a new task,
+    /// concurrently with the rest of the program.
+    #[grammar(spawn ( $v0 ))]
+    Spawn(Arc<Expr>),
+
+    /// A closure `|x: A, ...| -> R { ... }`, whose value captures the places
+    /// that its body uses (see [`CallKind`]). It is called as `f.ref.call(...)`.
+    #[grammar(| $,v0 | -> $v1 $v2)]
+    Closure(Vec<LocalVariableDecl>, Ty, Block),
+
     #[grammar(($*v0))]
     Tuple(Vec<Expr>),
 
-    #[grammar($v0 . $v1 $[?v2] $(v3))]
-    Call(Arc<Expr>, MethodId, Vec<Parameter>, Vec<Expr>),
+    #[grammar($v0 . $v1 $[?v2] $v3)]
+    Call(Arc<Expr>, MethodId, Vec<Parameter>, Arguments),
+
+    /// Call of the function (see [`FnDecl`]) with the given name.
+    #[grammar($v0 $[?v1] $v2)]
+    CallFn(ValueId, Vec<Parameter>, Arguments),
 
     // ANCHOR: Expr_New
-    #[grammar(new $v0 $[?v1] $(v2))]
-    New(ValueId, Vec<Parameter>, Vec<Expr>),
+    #[grammar(new $v0 $[?v1] $v2)]
+    New(ValueId, Vec<Parameter>, Arguments),
     // ANCHOR_END: Expr_New
+    #[grammar(new $v0 $[?v1] :: $v2 $v3)]
+    NewVariant(ValueId, Vec<Parameter>, ValueId, Arguments),
+
+    #[grammar(match $v0 { $*v1 })]
+    Match(Arc<Expr>, Vec<MatchArm>),
+
     #[grammar($$clear($v0))]
     Clear(ValueId),
 
@@ -317,6 +701,75 @@ pub enum Expr {
     Panic,
 }
 
+/// The argument list `(a, b, ...)` of a call or `new`, the uses of a generic.
+/// It records where it was written, so that two uses that are written alike are
+/// still told apart: the parameters inferred for each are kept separately
+/// (see [`Elaborations`](crate::type_system::inference::Elaborations)).
+#[term]
+#[customize(parse, debug)]
+pub struct Arguments {
+    pub exprs: Vec<Expr>,
+    pub span: Span,
+}
+
+/// Where a term was written in the source text. The parser sees only the text
+/// that remains to be parsed, so both ends are counted back from the end of
+/// the input: `start` and `end` are the number of bytes remaining at each end.
+/// The default span is that of a term not written in any text.
+#[term]
+#[customize(parse, debug)]
+#[derive(Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+mod span_impls;
+pub use span_impls::with_spans_shown;
+
+/// The text of a string literal, as the code points of its characters.
+/// The escapes `\"`, `\\`, `\n` and `\t` are supported.
+#[term]
+#[customize(parse, debug)]
+pub struct StringLiteral {
+    pub chars: Vec<usize>,
+}
+
+/// The character of a char literal, as its code point (see [`StringLiteral`]).
+#[term]
+#[customize(parse, debug)]
+pub struct CharLiteral {
+    pub code: usize,
+}
+mod literal_impls;
+
+/// One arm `Variant(x, y) => { ... }` of a `match`, binding the fields
+/// of the variant (in declaration order) to `x`, `y`.
+#[term($variant $(bindings) => $body)]
+pub struct MatchArm {
+    pub variant: ValueId,
+    pub bindings: Vec<ValueId>,
+    pub body: Block,
+}
+
+impl Expr {
+    /// True if this is a block that never completes normally (see [`Block::diverges`]).
+    pub fn diverges(&self) -> bool {
+        matches!(self, Expr::Block(block) if block.diverges())
+    }
+
+    /// The place leased by this expression, if it is of the form `place.mut`.
+    pub fn mutated_place(&self) -> Option<Place> {
+        match self {
+            Expr::Place(PlaceExpr {
+                place,
+                access: Access::Mt,
+                ..
+            }) => Some(place.clone()),
+            _ => None,
+        }
+    }
+}
+
 // ANCHOR: Access
 #[term]
 #[derive(Copy, Default)]
@@ -346,10 +799,14 @@ impl Access {
 }
 
 // ANCHOR: PlaceExpr
-#[term($place . $access)]
+/// `place.access`, together with where it was written (see [`Span`]).
+/// Place expressions are parsed and printed in `span_impls`.
+#[term]
+#[customize(parse, debug)]
 pub struct PlaceExpr {
     pub place: Place,
     pub access: Access,
+    pub span: Span,
 }
 // ANCHOR_END: PlaceExpr
 
@@ -427,6 +884,22 @@ impl Ty {
         .upcast()
     }
 
+    pub fn char() -> Ty {
+        NamedTy {
+            name: TypeName::Char,
+            parameters: vec![],
+        }
+        .upcast()
+    }
+
+    pub fn string() -> Ty {
+        NamedTy {
+            name: TypeName::String,
+            parameters: vec![],
+        }
+        .upcast()
+    }
+
     pub fn tuple(parameters: impl Upcast<Vec<Ty>>) -> Ty {
         let parameters: Vec<Ty> = parameters.upcast();
         NamedTy {
@@ -503,13 +976,36 @@ pub enum TypeName {
     #[grammar(Bool)]
     Bool,
 
+    /// A character, copied like an `Int`.
+    #[grammar(Char)]
+    Char,
+
+    /// An immutable string, stored in a refcounted buffer like an `Array[Char]`,
+    /// so that sharing it is cheap.
+    #[grammar(String)]
+    String,
+
     #[grammar(Array)]
     Array,
 
+    /// `Future[R, C]`, the result of calling an `async fn` (see [`Async`]).
+    #[grammar(Future)]
+    Future,
+
+    /// `Closure[K, (A...), R, (C...)]`, the type of a closure (see [`CallKind`]).
+    #[grammar(Closure $v0)]
+    Closure(CallKind),
+
     #[cast]
     Id(ValueId),
 }
 
+impl TypeName {
+    pub fn is_closure(&self) -> bool {
+        matches!(self, TypeName::Closure(_))
+    }
+}
+
 pub type Parameters = Vec<Parameter>;
 
 // ANCHOR: Place
@@ -680,6 +1176,10 @@ pub enum Predicate {
 
     #[grammar($v1 is $v0)]
     Variance(VarianceKind, Parameter),
+
+    /// `T is Trait` holds when there is an `impl Trait` for `T`.
+    #[grammar($v1 is $v0)]
+    Implements(ValueId, Parameter),
 }
 
 impl Predicate {
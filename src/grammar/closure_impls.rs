@@ -0,0 +1,178 @@
+use formality_core::{Map, Set, Upcast};
+
+use super::{
+    Access, Arguments, Block, CallKind, Expr, LocalVariableDecl, MatchArm, MethodId, Perm, Place,
+    Statement, Ty, ValueId, Var,
+};
+
+impl CallKind {
+    /// The permission of the receiver through which a closure of this kind is called,
+    /// written as it would be in a [`ThisDecl`](super::ThisDecl).
+    pub fn this_perm(self) -> Perm {
+        match self {
+            CallKind::Shared => Perm::rf(Set::<Place>::new()),
+            CallKind::Mut => {
+                let this: Place = Var::This.upcast();
+                Perm::mt((this,))
+            }
+            CallKind::Once => Perm::Given,
+        }
+    }
+}
+
+impl MethodId {
+    /// True for `call`, the method through which a closure is called.
+    pub fn is_call(&self) -> bool {
+        *self == crate::dada_lang::term::<MethodId>("call")
+    }
+}
+
+impl LocalVariableDecl {
+    /// Declarations of the inputs of a closure whose type only records the input types `tys`,
+    /// named `_0`, `_1`, ... in order.
+    pub fn closure_inputs(tys: Vec<Ty>) -> Vec<LocalVariableDecl> {
+        tys.into_iter()
+            .zip(0..)
+            .map(|(ty, i)| LocalVariableDecl {
+                name: crate::dada_lang::term::<ValueId>(&format!("_{i}")),
+                ty,
+            })
+            .collect()
+    }
+}
+
+impl Access {
+    /// The access with which a closure captures a place whose uses in its body
+    /// include both `self` and `other`: giving a place away needs its value,
+    /// mutating it needs a `mut` capture, and anything else only needs a `ref`.
+    fn capture_join(self, other: Access) -> Access {
+        match (self, other) {
+            (Access::Gv | Access::Drop, _) | (_, Access::Gv | Access::Drop) => Access::Gv,
+            (Access::Mt, _) | (_, Access::Mt) => Access::Mt,
+            (Access::Rf, Access::Rf) => Access::Rf,
+        }
+    }
+}
+
+impl Expr {
+    /// The variables of the enclosing scope used by the body of the closure
+    /// `|inputs| -> _ body`, each with the strongest access it is used with
+    /// (`ref`, `mut`, or `give`). Assigning to a place counts as mutating it.
+    pub fn closure_captures(inputs: &[LocalVariableDecl], body: &Block) -> Vec<(Var, Access)> {
+        let mut captures = Captures::default();
+        captures.bind(inputs.iter().map(|input| Var::Id(input.name.clone())));
+        captures.block(body);
+        let Captures { bound, used } = captures;
+        used.into_iter()
+            .filter(|(var, _)| !bound.contains(var))
+            .collect()
+    }
+}
+
+/// Variables are never shadowed, so a name bound anywhere in the closure body is
+/// never a variable of the enclosing scope.
+#[derive(Default)]
+struct Captures {
+    bound: Set<Var>,
+    used: Map<Var, Access>,
+}
+
+impl Captures {
+    fn bind(&mut self, vars: impl IntoIterator<Item = Var>) {
+        self.bound.extend(vars);
+    }
+
+    fn use_place(&mut self, place: &Place, access: Access) {
+        let access = match self.used.get(&place.var) {
+            Some(previous) => previous.capture_join(access),
+            None => access.capture_join(Access::Rf),
+        };
+        self.used.insert(place.var.clone(), access);
+    }
+
+    fn block(&mut self, block: &Block) {
+        for statement in &block.statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        match statement {
+            Statement::Expr(expr, _) | Statement::Return(expr, _) | Statement::Print(expr, _) => {
+                self.expr(expr)
+            }
+            Statement::Let(name, _ascription, expr, _) => {
+                self.bind([Var::Id(name.clone())]);
+                self.expr(expr);
+            }
+            Statement::Reassign(place, expr, _) => {
+                self.use_place(place, Access::Mt);
+                self.expr(expr);
+            }
+            Statement::Loop(block, _) => self.block(block),
+            Statement::Break(_) => {}
+        }
+    }
+
+    fn exprs<'e>(&mut self, exprs: impl IntoIterator<Item = &'e Expr>) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Block(block) => self.block(block),
+            Expr::Integer(_)
+            | Expr::String(_)
+            | Expr::Char(_)
+            | Expr::True
+            | Expr::False
+            | Expr::SizeOf(_)
+            | Expr::Panic => {}
+            Expr::Or(lhs, rhs)
+            | Expr::And(lhs, rhs)
+            | Expr::Comparison(lhs, _, rhs)
+            | Expr::Additive(lhs, _, rhs)
+            | Expr::Multiplicative(lhs, _, rhs) => self.exprs([&**lhs, &**rhs]),
+            Expr::Unary(_op, expr) => self.expr(expr),
+            Expr::Place(place_expr) => self.use_place(&place_expr.place, place_expr.access),
+            Expr::Share(expr) | Expr::Await(expr) | Expr::Spawn(expr) => self.expr(expr),
+            Expr::Closure(inputs, _output, body) => {
+                self.bind(inputs.iter().map(|input| Var::Id(input.name.clone())));
+                self.block(body);
+            }
+            Expr::Tuple(exprs)
+            | Expr::CallFn(_, _, Arguments { exprs, .. })
+            | Expr::New(_, _, Arguments { exprs, .. })
+            | Expr::NewVariant(_, _, _, Arguments { exprs, .. }) => self.exprs(exprs),
+            Expr::Call(receiver, _method_name, _parameters, Arguments { exprs, .. }) => {
+                self.expr(receiver);
+                self.exprs(exprs);
+            }
+            Expr::Match(scrutinee, arms) => {
+                self.expr(scrutinee);
+                for MatchArm {
+                    variant: _,
+                    bindings,
+                    body,
+                } in arms
+                {
+                    self.bind(bindings.iter().map(|binding| Var::Id(binding.clone())));
+                    self.block(body);
+                }
+            }
+            Expr::Clear(name) => {
+                let place: Place = Var::Id(name.clone()).upcast();
+                self.use_place(&place, Access::Gv)
+            }
+            Expr::If(cond, if_true, if_false) => self.exprs([&**cond, &**if_true, &**if_false]),
+            Expr::ArrayNew(_, expr) | Expr::ArrayCapacity(_, expr) | Expr::IsLastRef(_, expr) => {
+                self.expr(expr)
+            }
+            Expr::ArrayGive(_, array, index) => self.exprs([&**array, &**index]),
+            Expr::ArrayDrop(_, array, from, to) => self.exprs([&**array, &**from, &**to]),
+            Expr::ArrayWrite(_, array, index, value) => self.exprs([&**array, &**index, &**value]),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use formality_core::parse::{CoreParse, ParseError, ParseResult, Parser, Scope};
+use std::fmt::Debug;
+
+use crate::dada_lang::FormalityLang;
+
+use super::{CharLiteral, MethodId, StringLiteral};
+
+impl StringLiteral {
+    pub fn text(&self) -> String {
+        self.chars.iter().map(|&code| code_to_char(code)).collect()
+    }
+}
+
+impl CharLiteral {
+    pub fn value(&self) -> char {
+        code_to_char(self.code)
+    }
+}
+
+impl MethodId {
+    /// True for `len`, the method built into `String` that counts its characters.
+    pub fn is_len(&self) -> bool {
+        *self == crate::dada_lang::term::<MethodId>("len")
+    }
+}
+
+fn code_to_char(code: usize) -> char {
+    u32::try_from(code)
+        .ok()
+        .and_then(char::from_u32)
+        .unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+// The parser works in terms of tokens and skips whitespace between them, so we scan
+// the literal from the text ourselves and then have the parser consume its characters.
+impl CoreParse<FormalityLang> for StringLiteral {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "StringLiteral", |parser| {
+            let (source, chars) = scan_quoted(text, '"', usize::MAX);
+            parser.expect_char('"')?;
+            for c in source {
+                if !c.is_whitespace() {
+                    parser.expect_char(c)?;
+                }
+            }
+            parser.expect_char('"')?;
+            Ok(StringLiteral {
+                chars: chars.into_iter().map(|c| c as usize).collect(),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for CharLiteral {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "CharLiteral", |parser| {
+            let (source, chars) = scan_quoted(text, '\'', 1);
+            parser.expect_char('\'')?;
+            for c in source {
+                if !c.is_whitespace() {
+                    parser.expect_char(c)?;
+                }
+            }
+            // Fails if there is more than one character, or none (an empty literal
+            // or an unknown escape).
+            let Some(&c) = chars.first() else {
+                return Err(ParseError::at(
+                    text.trim_start(),
+                    "expected a single character or escape in a char literal".to_string(),
+                ));
+            };
+            parser.expect_char('\'')?;
+            Ok(CharLiteral { code: c as usize })
+        })
+    }
+}
+
+/// Scan the body of the literal delimited by `quote` at the start of `text`,
+/// up to `max` characters. Returns the source characters of the body
+/// (including the backslashes of escapes) and the characters they denote.
+/// Both are empty if `text` does not start with `quote`.
+fn scan_quoted(text: &str, quote: char, max: usize) -> (Vec<char>, Vec<char>) {
+    let mut source = vec![];
+    let mut chars = vec![];
+    let mut input = text.trim_start().chars();
+    if input.next() != Some(quote) {
+        return (source, chars);
+    }
+    while chars.len() < max {
+        let Some(c) = input.next() else { break };
+        if c == quote {
+            break;
+        }
+        if c != '\\' {
+            source.push(c);
+            chars.push(c);
+            continue;
+        }
+        let escaped = match input.next() {
+            Some('n') => ('n', '\n'),
+            Some('t') => ('t', '\t'),
+            Some(c @ ('\\' | '"' | '\'')) => (c, c),
+            // An unknown escape: stop here, so the parser fails on it.
+            _ => break,
+        };
+        source.extend(['\\', escaped.0]);
+        chars.push(escaped.1);
+    }
+    (source, chars)
+}
+
+impl Debug for StringLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.text())
+    }
+}
+
+impl Debug for CharLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.value())
+    }
+}
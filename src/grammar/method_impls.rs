@@ -2,7 +2,7 @@ use formality_core::parse::{CoreParse, ParseResult, Parser, Scope};
 
 use crate::dada_lang::FormalityLang;
 
-use super::{LocalVariableDecl, MethodDeclBoundData, Predicate, ThisDecl, Ty};
+use super::{FnDeclBoundData, LocalVariableDecl, MethodDeclBoundData, Predicate, ThisDecl, Ty};
 
 impl CoreParse<FormalityLang> for MethodDeclBoundData {
     fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
@@ -41,3 +41,35 @@ impl CoreParse<FormalityLang> for MethodDeclBoundData {
         })
     }
 }
+
+impl CoreParse<FormalityLang> for FnDeclBoundData {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "FnDeclBoundData", |parser| {
+            parser.expect_char('(')?;
+            let inputs: Vec<LocalVariableDecl> = parser.comma_nonterminal()?;
+            parser.expect_char(')')?;
+
+            let output: Ty = if parser.expect_char('-').is_ok() {
+                parser.expect_char('>')?;
+                parser.nonterminal()?
+            } else {
+                Ty::unit()
+            };
+
+            let predicates: Vec<Predicate> = if let Ok(()) = parser.expect_keyword("where") {
+                parser.comma_nonterminal()?
+            } else {
+                Default::default()
+            };
+
+            let body = parser.nonterminal()?;
+
+            Ok(FnDeclBoundData {
+                inputs,
+                output,
+                predicates,
+                body,
+            })
+        })
+    }
+}
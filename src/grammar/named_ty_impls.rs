@@ -1,10 +1,11 @@
 use anyhow::bail;
 use formality_core::parse::{CoreParse, Parser, Precedence};
+use formality_core::Upcast;
 use std::fmt::Debug;
 
 use crate::dada_lang::FormalityLang;
 
-use super::{NamedTy, Parameter, Perm, Ty, TypeName, ValueId};
+use super::{CallKind, NamedTy, Parameter, Perm, Ty, TypeName, ValueId};
 
 impl NamedTy {
     /// Build an `Array[T]` named type from the parameters list, validating that
@@ -20,6 +21,61 @@ impl NamedTy {
         }
     }
 
+    /// Build the `Future[R, (C...)]` type of a call to an `async fn` with output type `R`
+    /// whose arguments (including `self`) have the types `captured`.
+    pub fn future(output: impl Upcast<Ty>, captured: Vec<Ty>) -> NamedTy {
+        let captured = NamedTy::new(TypeName::Tuple(captured.len()), captured);
+        let parameters = vec![
+            Parameter::Ty(output.upcast()),
+            Parameter::Ty(Ty::NamedTy(captured)),
+        ];
+        NamedTy::new(TypeName::Future, parameters)
+    }
+
+    /// Extract the output type `R` and the captured type `C` of a `Future[R, C]`.
+    pub fn future_parameters(parameters: &[Parameter]) -> anyhow::Result<(Ty, Ty)> {
+        match parameters {
+            [Parameter::Ty(output), Parameter::Ty(captured)] => {
+                Ok((output.clone(), captured.clone()))
+            }
+            _ => bail!("Future requires exactly two type parameters, got {:?}", parameters),
+        }
+    }
+
+    /// Build the `Closure[K, (A...), R, (C...)]` type of a closure of kind `kind`
+    /// taking arguments of types `inputs`, returning `output`, and capturing values of types `captured`.
+    pub fn closure(
+        kind: CallKind,
+        inputs: Vec<Ty>,
+        output: impl Upcast<Ty>,
+        captured: Vec<Ty>,
+    ) -> NamedTy {
+        let parameters = vec![
+            Parameter::Ty(Ty::tuple(inputs)),
+            Parameter::Ty(output.upcast()),
+            Parameter::Ty(Ty::tuple(captured)),
+        ];
+        NamedTy::new(TypeName::Closure(kind), parameters)
+    }
+
+    /// Extract the input types `A...`, the output type `R`, and the captured type `C`
+    /// of a `Closure[K, (A...), R, C]`.
+    pub fn closure_parameters(parameters: &[Parameter]) -> anyhow::Result<(Vec<Ty>, Ty, Ty)> {
+        match parameters {
+            [Parameter::Ty(Ty::NamedTy(NamedTy {
+                name: TypeName::Tuple(_),
+                parameters: inputs,
+            })), Parameter::Ty(output), Parameter::Ty(captured)] => {
+                let inputs = inputs.iter().filter_map(|p| p.as_ty()).cloned().collect();
+                Ok((inputs, output.clone(), captured.clone()))
+            }
+            _ => bail!(
+                "Closure requires a tuple of input types, an output type, and a captured type, got {:?}",
+                parameters
+            ),
+        }
+    }
+
     /// Extract parameters for `array_give[T, P, A]` and `array_drop[T, P, A]`.
     /// Returns (Array[T] named type, element type T, permission P, permission A).
     pub fn array_with_pa(parameters: &[Parameter]) -> anyhow::Result<(NamedTy, Ty, Perm, Perm)> {
@@ -75,12 +131,37 @@ impl CoreParse<FormalityLang> for NamedTy {
                 Ok(NamedTy::new(name, parameters))
             });
 
+            p.parse_variant("char", Precedence::default(), |p| {
+                p.expect_keyword("Char")?;
+                let parameters: Vec<Parameter> = vec![];
+                Ok(NamedTy::new(TypeName::Char, parameters))
+            });
+
+            p.parse_variant("string", Precedence::default(), |p| {
+                p.expect_keyword("String")?;
+                let parameters: Vec<Parameter> = vec![];
+                Ok(NamedTy::new(TypeName::String, parameters))
+            });
+
             p.parse_variant("array", Precedence::default(), |p| {
                 p.expect_keyword("Array")?;
                 let parameters: Vec<Parameter> = p.delimited_nonterminal('[', false, ']')?;
                 Ok(NamedTy::new(TypeName::Array, parameters))
             });
 
+            p.parse_variant("future", Precedence::default(), |p| {
+                p.expect_keyword("Future")?;
+                let parameters: Vec<Parameter> = p.delimited_nonterminal('[', false, ']')?;
+                Ok(NamedTy::new(TypeName::Future, parameters))
+            });
+
+            p.parse_variant("closure", Precedence::default(), |p| {
+                p.expect_keyword("Closure")?;
+                let kind: CallKind = p.nonterminal()?;
+                let parameters: Vec<Parameter> = p.delimited_nonterminal('[', false, ']')?;
+                Ok(NamedTy::new(TypeName::Closure(kind), parameters))
+            });
+
             p.parse_variant("class", Precedence::default(), |p| {
                 p.mark_as_cast_variant();
                 let id: ValueId = p.nonterminal()?;
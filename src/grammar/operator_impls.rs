@@ -0,0 +1,121 @@
+use formality_core::parse::{CoreParse, ParseError, ParseResult, Parser, Scope};
+use formality_core::Set;
+
+use crate::dada_lang::FormalityLang;
+
+use super::{AdditiveOp, BinaryOp, ComparisonOp, MultiplicativeOp, UnaryOp};
+
+/// The operator tokens, longest first. `->` and `=>` are not operators, but are
+/// tokens so that `-` is never read from the start of `->` (nor `=` from `=>`).
+const TOKENS: &[&str] = &[
+    "&&", "||", "==", "!=", ">=", "<=", "->", "=>", ">", "<", "+", "-", "*", "/", "%", "!",
+];
+
+const COMPARISON_OPS: &[(&str, BinaryOp)] = &[
+    ("==", BinaryOp::Eq),
+    ("!=", BinaryOp::Ne),
+    (">=", BinaryOp::Ge),
+    ("<=", BinaryOp::Le),
+    (">", BinaryOp::Gt),
+    ("<", BinaryOp::Lt),
+];
+
+const ADDITIVE_OPS: &[(&str, BinaryOp)] = &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)];
+
+const MULTIPLICATIVE_OPS: &[(&str, BinaryOp)] = &[
+    ("*", BinaryOp::Mul),
+    ("/", BinaryOp::Div),
+    ("%", BinaryOp::Rem),
+];
+
+const UNARY_OPS: &[(&str, UnaryOp)] = &[("-", UnaryOp::Neg), ("!", UnaryOp::Not)];
+
+/// The operator token at the start of `text` (after whitespace), if any.
+/// This is the longest token that `text` starts with, so `<=` is read as one token.
+fn next_token(text: &str) -> Option<&'static str> {
+    let text = text.trim_start();
+    TOKENS.iter().copied().find(|token| text.starts_with(token))
+}
+
+/// The operator among `ops` whose token is at the start of `text`, with that token,
+/// or an error expecting one of them.
+fn next_op<'t, Op: Clone>(
+    text: &'t str,
+    ops: &[(&'static str, Op)],
+) -> Result<(&'static str, Op), Set<ParseError<'t>>> {
+    let token = next_token(text);
+    match ops.iter().find(|(op_token, _)| token == Some(*op_token)) {
+        Some((op_token, op)) => Ok((*op_token, op.clone())),
+        None => {
+            let expected: Vec<&str> = ops.iter().map(|(op_token, _)| *op_token).collect();
+            Err(ParseError::at(
+                text.trim_start(),
+                format!("expected one of `{}`", expected.join("`, `")),
+            ))
+        }
+    }
+}
+
+// The formality-core parser matches each operator character by character, so
+// `<` and `<=` would both match the start of `a <= b`. Instead, we read the next
+// token ourselves and then have the parser consume its characters.
+impl CoreParse<FormalityLang> for BinaryOp {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "BinaryOp", |parser| {
+            let ops = [COMPARISON_OPS, ADDITIVE_OPS, MULTIPLICATIVE_OPS].concat();
+            let (token, op) = next_op(text, &ops)?;
+            for c in token.chars() {
+                parser.expect_char(c)?;
+            }
+            Ok(op)
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for ComparisonOp {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "ComparisonOp", |parser| {
+            let (token, op) = next_op(text, COMPARISON_OPS)?;
+            for c in token.chars() {
+                parser.expect_char(c)?;
+            }
+            Ok(ComparisonOp { op })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for AdditiveOp {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "AdditiveOp", |parser| {
+            let (token, op) = next_op(text, ADDITIVE_OPS)?;
+            for c in token.chars() {
+                parser.expect_char(c)?;
+            }
+            Ok(AdditiveOp { op })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for MultiplicativeOp {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "MultiplicativeOp", |parser| {
+            let (token, op) = next_op(text, MULTIPLICATIVE_OPS)?;
+            for c in token.chars() {
+                parser.expect_char(c)?;
+            }
+            Ok(MultiplicativeOp { op })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for UnaryOp {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "UnaryOp", |parser| {
+            let (token, op) = next_op(text, UNARY_OPS)?;
+            for c in token.chars() {
+                parser.expect_char(c)?;
+            }
+            Ok(op)
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use super::{Parameter, Perm, Ty};
-use formality_core::{cast_impl, Cons, DowncastFrom, Upcast, UpcastFrom};
+use formality_core::{cast_impl, Cons, DowncastFrom, Set, Upcast, UpcastFrom};
 use std::sync::Arc;
 
 impl Perm {
@@ -15,6 +15,26 @@ impl Perm {
     pub fn apply_to(&self, perm: impl Upcast<Arc<Perm>>) -> Perm {
         Perm::apply(self, perm)
     }
+
+    /// Returns `or(perms)`, pulling the branches of any nested `or` into the result
+    /// (nested `or` is not well-formed). A single permission is returned unwrapped.
+    pub fn flattened_or(perms: impl IntoIterator<Item = Perm>) -> Perm {
+        let mut branches: Set<Perm> = Set::new();
+        for perm in perms {
+            match perm {
+                Perm::Or(inner) => branches.extend(inner),
+                perm => {
+                    branches.insert(perm);
+                }
+            }
+        }
+
+        if branches.len() == 1 {
+            branches.pop_first().unwrap()
+        } else {
+            Perm::Or(branches)
+        }
+    }
 }
 
 /// "LeafPerms"
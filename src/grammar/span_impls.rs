@@ -0,0 +1,422 @@
+use formality_core::parse::{CoreParse, ParseResult, Parser, Precedence, Scope};
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::dada_lang::FormalityLang;
+
+use super::{
+    Access, Arguments, Ascription, Async, Binder, Block, Expr, FnDecl, FnDeclBoundData, MethodDecl,
+    MethodDeclBoundData, MethodId, Place, PlaceExpr, Span, Statement, ValueId,
+};
+
+impl Span {
+    /// The span from the start of `self` to the end of `other`, which comes after it.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            start: self.start,
+            end: other.end,
+        }
+    }
+
+    /// The byte range of this span within `text`, the text it was parsed from,
+    /// or `None` if it covers no text (e.g., it is the default span).
+    pub fn range_in(&self, text: &str) -> Option<Range<usize>> {
+        if self.start <= self.end {
+            return None;
+        }
+        let start = text.len().checked_sub(self.start)?;
+        let end = text.len() - self.end;
+        Some(start..start + text[start..end].trim_end().len())
+    }
+
+    /// The span printed at the start of `text`, the `Debug` text of a term
+    /// printed while spans are shown (see [`with_spans_shown`]).
+    pub fn shown_at_start_of(text: &str) -> Option<Span> {
+        let (start, rest) = text.strip_prefix('@')?.split_once("..")?;
+        let (end, _) = rest.split_once(' ')?;
+        Some(Span {
+            start: start.parse().ok()?,
+            end: end.parse().ok()?,
+        })
+    }
+
+    /// Print this span ahead of the term it belongs to, if spans are shown.
+    fn fmt_shown(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if SHOW_SPANS.with(|shown| shown.get()) && !f.alternate() {
+            write!(f, "{self:?} ")?;
+        }
+        Ok(())
+    }
+}
+
+impl Arguments {
+    /// The same argument list, written at the same place, with `exprs` as its arguments.
+    pub fn with_exprs(&self, exprs: Vec<Expr>) -> Arguments {
+        Arguments {
+            exprs,
+            span: self.span.clone(),
+        }
+    }
+}
+
+impl Statement {
+    /// Where this statement was written.
+    pub fn span(&self) -> &Span {
+        match self {
+            Statement::Expr(_, span)
+            | Statement::Let(_, _, _, span)
+            | Statement::Reassign(_, _, span)
+            | Statement::Loop(_, span)
+            | Statement::Break(span)
+            | Statement::Return(_, span)
+            | Statement::Print(_, span) => span,
+        }
+    }
+}
+
+thread_local! {
+    static SHOW_SPANS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `op` with the terms that record where they were written (blocks, statements,
+/// place expressions and the headers of methods and functions) printing their span ahead
+/// of themselves, as in `@120..105 let x = y . give ;`. A [`FailedJudgment`] records
+/// only the `Debug` text of its judgments, so this is how a failure found while type
+/// checking is traced back to the source (see [`crate::diagnostics`]).
+///
+/// [`FailedJudgment`]: formality_core::judgment::FailedJudgment
+pub fn with_spans_shown<R>(op: impl FnOnce() -> R) -> R {
+    /// Restores the previous setting when dropped, even if `op` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SHOW_SPANS.with(|shown| shown.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SHOW_SPANS.with(|shown| shown.replace(true)));
+    op()
+}
+
+/// `text` without the whitespace and `//` comments at its start,
+/// which the parser skips before each token.
+fn skip_trivia(mut text: &str) -> &str {
+    loop {
+        text = text.trim_start();
+        match text.strip_prefix("//") {
+            Some(comment) => text = comment.split_once('\n').map_or("", |(_, rest)| rest),
+            None => return text,
+        }
+    }
+}
+
+// A span consumes no text: it covers the whitespace and comments before the next token,
+// starting after them and ending before them. Terms that record where they were written
+// parse one before their first token and one after their last, and join them with
+// `Span::to`, so that their span covers just their own tokens.
+impl CoreParse<FormalityLang> for Span {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "Span", |_parser| {
+            Ok(Span {
+                start: skip_trivia(text).len(),
+                end: text.len(),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for Arguments {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "Arguments", |parser| {
+            let start: Span = parser.nonterminal()?;
+            let exprs: Vec<Expr> = parser.delimited_nonterminal('(', false, ')')?;
+            let end: Span = parser.nonterminal()?;
+            Ok(Arguments {
+                exprs,
+                span: start.to(&end),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for Statement {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::multi_variant(scope, text, "Statement", |p| {
+            p.parse_variant("expr", Precedence::default(), |p| {
+                p.mark_as_cast_variant();
+                let start: Span = p.nonterminal()?;
+                let expr: Expr = p.nonterminal()?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Expr(expr, start.to(&end)))
+            });
+
+            p.parse_variant("let", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                p.expect_keyword("let")?;
+                let name: ValueId = p.nonterminal()?;
+                let ascription = if p.expect_char(':').is_ok() {
+                    Ascription::Ty(p.nonterminal()?)
+                } else {
+                    Ascription::NoTy
+                };
+                p.expect_char('=')?;
+                let expr: Arc<Expr> = p.nonterminal()?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Let(name, ascription, expr, start.to(&end)))
+            });
+
+            p.parse_variant("reassign", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                let place: Place = p.nonterminal()?;
+                p.expect_char('=')?;
+                let expr: Expr = p.nonterminal()?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Reassign(place, expr, start.to(&end)))
+            });
+
+            p.parse_variant("loop", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                p.expect_keyword("loop")?;
+                let body: Block = p.nonterminal()?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Loop(body, start.to(&end)))
+            });
+
+            p.parse_variant("break", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                p.expect_keyword("break")?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Break(start.to(&end)))
+            });
+
+            p.parse_variant("return", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                p.expect_keyword("return")?;
+                let expr: Expr = p.nonterminal()?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Return(expr, start.to(&end)))
+            });
+
+            p.parse_variant("print", Precedence::default(), |p| {
+                let start: Span = p.nonterminal()?;
+                p.expect_keyword("print")?;
+                p.expect_char('(')?;
+                let expr: Expr = p.nonterminal()?;
+                p.expect_char(')')?;
+                p.expect_char(';')?;
+                let end: Span = p.nonterminal()?;
+                Ok(Statement::Print(expr, start.to(&end)))
+            });
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for Block {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "Block", |parser| {
+            let start: Span = parser.nonterminal()?;
+            parser.expect_char('{')?;
+            let statements: Vec<Statement> = parser.many_nonterminal()?;
+            parser.expect_char('}')?;
+            let end: Span = parser.nonterminal()?;
+            Ok(Block {
+                statements,
+                span: start.to(&end),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for PlaceExpr {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "PlaceExpr", |parser| {
+            let start: Span = parser.nonterminal()?;
+            let place: Place = parser.nonterminal()?;
+            parser.expect_char('.')?;
+            let access: Access = parser.nonterminal()?;
+            let end: Span = parser.nonterminal()?;
+            Ok(PlaceExpr {
+                place,
+                access,
+                span: start.to(&end),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for MethodDecl {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "MethodDecl", |parser| {
+            let start: Span = parser.nonterminal()?;
+            let is_async = if parser.expect_keyword("async").is_ok() {
+                Async::Yes
+            } else {
+                let _ = parser.expect_keyword("sync");
+                Async::No
+            };
+            parser.expect_keyword("fn")?;
+            let name: MethodId = parser.nonterminal()?;
+            let end: Span = parser.nonterminal()?;
+            let binder: Binder<MethodDeclBoundData> = parser.nonterminal()?;
+            Ok(MethodDecl {
+                name,
+                is_async,
+                binder,
+                span: start.to(&end),
+            })
+        })
+    }
+}
+
+impl CoreParse<FormalityLang> for FnDecl {
+    fn parse<'t>(scope: &Scope<FormalityLang>, text: &'t str) -> ParseResult<'t, Self> {
+        Parser::single_variant(scope, text, "FnDecl", |parser| {
+            let start: Span = parser.nonterminal()?;
+            parser.expect_keyword("fn")?;
+            let name: ValueId = parser.nonterminal()?;
+            let end: Span = parser.nonterminal()?;
+            let binder: Binder<FnDeclBoundData> = parser.nonterminal()?;
+            Ok(FnDecl {
+                name,
+                binder,
+                span: start.to(&end),
+            })
+        })
+    }
+}
+
+impl Debug for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "@{}..{}", self.start, self.end)
+    }
+}
+
+// Spans are left out below (unless shown, see `with_spans_shown`),
+// so that terms print the same wherever they were written.
+
+impl Debug for Arguments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_list().entries(&self.exprs).finish()
+        } else {
+            write!(f, "(")?;
+            for (expr, i) in self.exprs.iter().zip(0..) {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{:?}", expr)?;
+            }
+            write!(f, ")")
+        }
+    }
+}
+
+impl Debug for Statement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            return match self {
+                Statement::Expr(expr, _) => f.debug_tuple("Expr").field(expr).finish(),
+                Statement::Let(name, ascription, expr, _) => f
+                    .debug_tuple("Let")
+                    .field(name)
+                    .field(ascription)
+                    .field(expr)
+                    .finish(),
+                Statement::Reassign(place, expr, _) => {
+                    f.debug_tuple("Reassign").field(place).field(expr).finish()
+                }
+                Statement::Loop(body, _) => f.debug_tuple("Loop").field(body).finish(),
+                Statement::Break(_) => write!(f, "Break"),
+                Statement::Return(expr, _) => f.debug_tuple("Return").field(expr).finish(),
+                Statement::Print(expr, _) => f.debug_tuple("Print").field(expr).finish(),
+            };
+        }
+
+        self.span().fmt_shown(f)?;
+        match self {
+            Statement::Expr(expr, _) => write!(f, "{expr:?} ;"),
+            Statement::Let(name, Ascription::NoTy, expr, _) => {
+                write!(f, "let {name:?} = {expr:?} ;")
+            }
+            Statement::Let(name, Ascription::Ty(ty), expr, _) => {
+                write!(f, "let {name:?} : {ty:?} = {expr:?} ;")
+            }
+            Statement::Reassign(place, expr, _) => write!(f, "{place:?} = {expr:?} ;"),
+            Statement::Loop(body, _) => write!(f, "loop {body:?}"),
+            Statement::Break(_) => write!(f, "break ;"),
+            Statement::Return(expr, _) => write!(f, "return {expr:?} ;"),
+            Statement::Print(expr, _) => write!(f, "print({expr:?}) ;"),
+        }
+    }
+}
+
+impl Debug for Block {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("Block")
+                .field("statements", &self.statements)
+                .finish()
+        } else {
+            self.span.fmt_shown(f)?;
+            write!(f, "{{")?;
+            for statement in &self.statements {
+                write!(f, " {statement:?}")?;
+            }
+            write!(f, " }}")
+        }
+    }
+}
+
+impl Debug for PlaceExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("PlaceExpr")
+                .field("place", &self.place)
+                .field("access", &self.access)
+                .finish()
+        } else {
+            self.span.fmt_shown(f)?;
+            write!(f, "{:?} . {:?}", self.place, self.access)
+        }
+    }
+}
+
+impl Debug for MethodDecl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("MethodDecl")
+                .field("name", &self.name)
+                .field("is_async", &self.is_async)
+                .field("binder", &self.binder)
+                .finish()
+        } else {
+            self.span.fmt_shown(f)?;
+            if let Async::Yes = self.is_async {
+                write!(f, "async ")?;
+            }
+            write!(f, "fn {:?} {:?}", self.name, self.binder)
+        }
+    }
+}
+
+impl Debug for FnDecl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.debug_struct("FnDecl")
+                .field("name", &self.name)
+                .field("binder", &self.binder)
+                .finish()
+        } else {
+            self.span.fmt_shown(f)?;
+            write!(f, "fn {:?} {:?}", self.name, self.binder)
+        }
+    }
+}
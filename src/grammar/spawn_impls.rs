@@ -0,0 +1,103 @@
+use super::{
+    Arguments, Block, Decl, DropBody, Expr, MatchArm, MethodBody, MethodDecl, Program, Statement,
+};
+
+impl Program {
+    /// True if `spawn(..)` appears anywhere in the program, so that running it
+    /// may involve more than one task.
+    pub fn spawns(&self) -> bool {
+        self.decls.iter().any(|decl| match decl {
+            Decl::ClassDecl(class_decl) => {
+                let (_, data) = class_decl.binder.open();
+                let DropBody { block } = &data.drop_body;
+                block.spawns() || data.methods.iter().any(method_spawns)
+            }
+            Decl::EnumDecl(_) => false,
+            Decl::FnDecl(fn_decl) => body_spawns(&fn_decl.binder.open().1.body),
+            Decl::TraitDecl(trait_decl) => trait_decl.methods.iter().any(method_spawns),
+            Decl::ImplDecl(impl_decl) => {
+                impl_decl.binder.open().1.methods.iter().any(method_spawns)
+            }
+        })
+    }
+}
+
+fn method_spawns(method: &MethodDecl) -> bool {
+    body_spawns(&method.binder.open().1.body)
+}
+
+fn body_spawns(body: &MethodBody) -> bool {
+    match body {
+        MethodBody::Trusted => false,
+        MethodBody::Block(block) => block.spawns(),
+    }
+}
+
+impl Block {
+    /// True if `spawn(..)` appears anywhere in this block.
+    pub fn spawns(&self) -> bool {
+        self.statements.iter().any(Statement::spawns)
+    }
+}
+
+impl Statement {
+    /// True if `spawn(..)` appears anywhere in this statement.
+    pub fn spawns(&self) -> bool {
+        match self {
+            Statement::Expr(expr, _) | Statement::Return(expr, _) | Statement::Print(expr, _) => {
+                expr.spawns()
+            }
+            Statement::Let(_, _, expr, _) => expr.spawns(),
+            Statement::Reassign(_, expr, _) => expr.spawns(),
+            Statement::Loop(block, _) => block.spawns(),
+            Statement::Break(_) => false,
+        }
+    }
+}
+
+impl Expr {
+    /// True if `spawn(..)` appears anywhere in this expression.
+    pub fn spawns(&self) -> bool {
+        match self {
+            Expr::Spawn(_) => true,
+            Expr::Block(block) => block.spawns(),
+            Expr::Integer(_)
+            | Expr::String(_)
+            | Expr::Char(_)
+            | Expr::True
+            | Expr::False
+            | Expr::SizeOf(_)
+            | Expr::Panic
+            | Expr::Place(_)
+            | Expr::Clear(_) => false,
+            Expr::Or(lhs, rhs)
+            | Expr::And(lhs, rhs)
+            | Expr::Comparison(lhs, _, rhs)
+            | Expr::Additive(lhs, _, rhs)
+            | Expr::Multiplicative(lhs, _, rhs) => lhs.spawns() || rhs.spawns(),
+            Expr::Unary(_, expr) | Expr::Share(expr) | Expr::Await(expr) => expr.spawns(),
+            Expr::Closure(_, _, body) => body.spawns(),
+            Expr::Tuple(exprs)
+            | Expr::CallFn(_, _, Arguments { exprs, .. })
+            | Expr::New(_, _, Arguments { exprs, .. })
+            | Expr::NewVariant(_, _, _, Arguments { exprs, .. }) => exprs.iter().any(Expr::spawns),
+            Expr::Call(receiver, _, _, Arguments { exprs, .. }) => {
+                receiver.spawns() || exprs.iter().any(Expr::spawns)
+            }
+            Expr::Match(scrutinee, arms) => {
+                scrutinee.spawns() || arms.iter().any(|MatchArm { body, .. }| body.spawns())
+            }
+            Expr::If(cond, if_true, if_false) => {
+                cond.spawns() || if_true.spawns() || if_false.spawns()
+            }
+            Expr::ArrayNew(_, expr) | Expr::ArrayCapacity(_, expr) | Expr::IsLastRef(_, expr) => {
+                expr.spawns()
+            }
+            Expr::ArrayGive(_, array, index) => array.spawns() || index.spawns(),
+            Expr::ArrayDrop(_, array, from, to) => array.spawns() || from.spawns() || to.spawns(),
+            Expr::ArrayWrite(_, array, index, value) => {
+                array.spawns() || index.spawns() || value.spawns()
+            }
+        }
+    }
+}
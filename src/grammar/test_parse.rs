@@ -21,6 +21,7 @@ fn test_parse_program() {
                 ClassDecl(
                     ClassDecl {
                         name: Point,
+                        boxed: No,
                         class_predicate: Share,
                         binder: Binder {
                             kinds: [],
@@ -51,6 +52,7 @@ fn test_parse_program() {
                                 methods: [
                                     MethodDecl {
                                         name: identity,
+                                        is_async: No,
                                         binder: Binder {
                                             kinds: [],
                                             term: MethodDeclBoundData {
@@ -229,9 +231,7 @@ fn test_parse_String_ty() {
             ),
             NamedTy(
                 NamedTy {
-                    name: Id(
-                        String,
-                    ),
+                    name: String,
                     parameters: [],
                 },
             ),
@@ -351,6 +351,216 @@ fn test_parse_expr() {
     .assert_debug_eq(&p);
 }
 
+#[test]
+fn test_parse_string_and_char_literals() {
+    let p: Expr = crate::dada_lang::term(
+        r#"
+        {
+            let s = "hello, \"world\"\n";
+            let c = ' ';
+        }
+    "#,
+    );
+    expect_test::expect![[r#"
+        Block(
+            Block {
+                statements: [
+                    Let(
+                        s,
+                        NoTy,
+                        String(
+                            "hello, \"world\"\n",
+                        ),
+                    ),
+                    Let(
+                        c,
+                        NoTy,
+                        Char(
+                            ' ',
+                        ),
+                    ),
+                ],
+            },
+        )
+    "#]]
+    .assert_debug_eq(&p);
+}
+
+#[test]
+fn test_parse_operators() {
+    let p: Expr = crate::dada_lang::term(
+        r#"
+        {
+            let x = 1 < 2 && -3 <= 4;
+            let y = !x.give || 6 % 4 > 1;
+        }
+    "#,
+    );
+    expect_test::expect![[r#"
+        Block(
+            Block {
+                statements: [
+                    Let(
+                        x,
+                        NoTy,
+                        And(
+                            Comparison(
+                                Integer(
+                                    1,
+                                ),
+                                ComparisonOp {
+                                    op: Lt,
+                                },
+                                Integer(
+                                    2,
+                                ),
+                            ),
+                            Comparison(
+                                Unary(
+                                    Neg,
+                                    Integer(
+                                        3,
+                                    ),
+                                ),
+                                ComparisonOp {
+                                    op: Le,
+                                },
+                                Integer(
+                                    4,
+                                ),
+                            ),
+                        ),
+                    ),
+                    Let(
+                        y,
+                        NoTy,
+                        Or(
+                            Unary(
+                                Not,
+                                Place(
+                                    PlaceExpr {
+                                        place: Place {
+                                            var: Id(
+                                                x,
+                                            ),
+                                            projections: [],
+                                        },
+                                        access: Gv,
+                                    },
+                                ),
+                            ),
+                            Comparison(
+                                Multiplicative(
+                                    Integer(
+                                        6,
+                                    ),
+                                    MultiplicativeOp {
+                                        op: Rem,
+                                    },
+                                    Integer(
+                                        4,
+                                    ),
+                                ),
+                                ComparisonOp {
+                                    op: Gt,
+                                },
+                                Integer(
+                                    1,
+                                ),
+                            ),
+                        ),
+                    ),
+                ],
+            },
+        )
+    "#]]
+    .assert_debug_eq(&p);
+}
+
+/// Each level of binary operators binds more tightly than the one before:
+/// `||`, `&&`, the comparisons, `+` and `-`, and then `*`, `/` and `%`.
+/// Operators of the same level associate to the left.
+#[test]
+fn test_parse_operator_precedence() {
+    let p: Expr = crate::dada_lang::term(
+        r#"
+        {
+            let x = 1 + 2 * 3 - 4;
+            let y = true || false && 1 < 2 + 3;
+        }
+    "#,
+    );
+    expect_test::expect![[r#"
+        Block(
+            Block {
+                statements: [
+                    Let(
+                        x,
+                        NoTy,
+                        Additive(
+                            Additive(
+                                Integer(
+                                    1,
+                                ),
+                                AdditiveOp {
+                                    op: Add,
+                                },
+                                Multiplicative(
+                                    Integer(
+                                        2,
+                                    ),
+                                    MultiplicativeOp {
+                                        op: Mul,
+                                    },
+                                    Integer(
+                                        3,
+                                    ),
+                                ),
+                            ),
+                            AdditiveOp {
+                                op: Sub,
+                            },
+                            Integer(
+                                4,
+                            ),
+                        ),
+                    ),
+                    Let(
+                        y,
+                        NoTy,
+                        Or(
+                            True,
+                            And(
+                                False,
+                                Comparison(
+                                    Integer(
+                                        1,
+                                    ),
+                                    ComparisonOp {
+                                        op: Lt,
+                                    },
+                                    Additive(
+                                        Integer(
+                                            2,
+                                        ),
+                                        AdditiveOp {
+                                            op: Add,
+                                        },
+                                        Integer(
+                                            3,
+                                        ),
+                                    ),
+                                ),
+                            ),
+                        ),
+                    ),
+                ],
+            },
+        )
+    "#]]
+    .assert_debug_eq(&p);
+}
+
 #[test]
 fn test_parse_class_with_drop_body() {
     let p: Program = crate::dada_lang::term(
@@ -428,3 +638,37 @@ fn test_parse_class_with_methods_and_drop() {
     assert_eq!(bound_data.methods.len(), 1);
     assert!(!bound_data.drop_body.block.statements.is_empty());
 }
+
+#[test]
+fn test_program_spawns() {
+    let p: Program = crate::dada_lang::term(
+        "
+        class Foo {
+            async fn work(given self) -> () {
+                ();
+            }
+        }
+
+        fn start(foo: given Foo) -> () {
+            if true { spawn(foo.give.work()); } else { (); };
+        }
+    ",
+    );
+    assert!(p.spawns());
+
+    let p: Program = crate::dada_lang::term(
+        "
+        class Foo {
+            async fn work(given self) -> () {
+                ();
+            }
+        }
+
+        fn start(foo: given Foo) -> () {
+            let f = foo.give.work();
+            ();
+        }
+    ",
+    );
+    assert!(!p.spawns());
+}
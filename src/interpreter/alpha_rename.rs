@@ -9,27 +9,36 @@
 use formality_core::Map;
 
 use crate::grammar::{
-    Block, Expr, MethodBody, MethodDeclBoundData, Statement, ValueId, Var,
+    Block, Expr, FnDeclBoundData, LocalVariableDecl, MethodBody, MethodDeclBoundData, Statement,
+    ValueId, Var,
 };
 use crate::type_system::in_flight::{InFlight, Transform};
 
-/// Collect all locally-declared variable names from a method body.
+/// Collect all locally-declared variable names from a method (or function) body.
 /// Returns the list of `Var`s that need renaming:
-/// - `Var::This` (always present)
+/// - `Var::This` (for methods)
 /// - `Var::Id(name)` for each input parameter
 /// - `Var::Id(name)` for each `let`-bound variable in the body
-fn collect_bound_vars(method: &MethodDeclBoundData) -> Vec<Var> {
-    let mut vars = vec![Var::This];
-    for input in &method.inputs {
+/// - `Var::Id(name)` for each variable bound by a `match` arm in the body
+fn collect_bound_vars(
+    has_this: bool,
+    inputs: &[LocalVariableDecl],
+    body: &MethodBody,
+) -> Vec<Var> {
+    let mut vars = vec![];
+    if has_this {
+        vars.push(Var::This);
+    }
+    for input in inputs {
         vars.push(Var::Id(input.name.clone()));
     }
-    if let MethodBody::Block(block) = &method.body {
+    if let MethodBody::Block(block) = body {
         collect_let_bound_vars_in_block(block, &mut vars);
     }
     vars
 }
 
-/// Recursively collect `Var::Id(name)` for all `let`-bound variables in a block.
+/// Recursively collect `Var::Id(name)` for all `let`-bound (and `match`-bound) variables in a block.
 fn collect_let_bound_vars_in_block(block: &Block, vars: &mut Vec<Var>) {
     for statement in &block.statements {
         collect_let_bound_vars_in_statement(statement, vars);
@@ -38,15 +47,15 @@ fn collect_let_bound_vars_in_block(block: &Block, vars: &mut Vec<Var>) {
 
 fn collect_let_bound_vars_in_statement(statement: &Statement, vars: &mut Vec<Var>) {
     match statement {
-        Statement::Let(name, _, _) => {
+        Statement::Let(name, _, _, _) => {
             vars.push(Var::Id(name.clone()));
         }
-        Statement::Expr(expr) => collect_let_bound_vars_in_expr(expr, vars),
-        Statement::Reassign(_, expr) => collect_let_bound_vars_in_expr(expr, vars),
-        Statement::Loop(block) => collect_let_bound_vars_in_block(block, vars),
-        Statement::Break => {}
-        Statement::Return(expr) => collect_let_bound_vars_in_expr(expr, vars),
-        Statement::Print(expr) => collect_let_bound_vars_in_expr(expr, vars),
+        Statement::Expr(expr, _) => collect_let_bound_vars_in_expr(expr, vars),
+        Statement::Reassign(_, expr, _) => collect_let_bound_vars_in_expr(expr, vars),
+        Statement::Loop(block, _) => collect_let_bound_vars_in_block(block, vars),
+        Statement::Break(_) => {}
+        Statement::Return(expr, _) => collect_let_bound_vars_in_expr(expr, vars),
+        Statement::Print(expr, _) => collect_let_bound_vars_in_expr(expr, vars),
     }
 }
 
@@ -58,11 +67,18 @@ fn collect_let_bound_vars_in_expr(expr: &Expr, vars: &mut Vec<Var>) {
             collect_let_bound_vars_in_expr(then_branch, vars);
             collect_let_bound_vars_in_expr(else_branch, vars);
         }
-        Expr::BinaryOp(lhs, _, rhs) => {
+        Expr::Or(lhs, rhs)
+        | Expr::And(lhs, rhs)
+        | Expr::Comparison(lhs, _, rhs)
+        | Expr::Additive(lhs, _, rhs)
+        | Expr::Multiplicative(lhs, _, rhs) => {
             collect_let_bound_vars_in_expr(lhs, vars);
             collect_let_bound_vars_in_expr(rhs, vars);
         }
         Expr::Share(e)
+        | Expr::Unary(_, e)
+        | Expr::Await(e)
+        | Expr::Spawn(e)
         | Expr::ArrayNew(_, e)
         | Expr::ArrayCapacity(_, e)
         | Expr::IsLastRef(_, e) => {
@@ -79,7 +95,12 @@ fn collect_let_bound_vars_in_expr(expr: &Expr, vars: &mut Vec<Var>) {
         }
         Expr::Call(receiver, _, _, args) => {
             collect_let_bound_vars_in_expr(receiver, vars);
-            for arg in args {
+            for arg in &args.exprs {
+                collect_let_bound_vars_in_expr(arg, vars);
+            }
+        }
+        Expr::CallFn(_, _, args) => {
+            for arg in &args.exprs {
                 collect_let_bound_vars_in_expr(arg, vars);
             }
         }
@@ -88,13 +109,26 @@ fn collect_let_bound_vars_in_expr(expr: &Expr, vars: &mut Vec<Var>) {
                 collect_let_bound_vars_in_expr(e, vars);
             }
         }
-        Expr::New(_, _, args) => {
-            for arg in args {
+        Expr::New(_, _, args) | Expr::NewVariant(_, _, _, args) => {
+            for arg in &args.exprs {
                 collect_let_bound_vars_in_expr(arg, vars);
             }
         }
+        Expr::Match(scrutinee, arms) => {
+            collect_let_bound_vars_in_expr(scrutinee, vars);
+            for arm in arms {
+                for binding in &arm.bindings {
+                    vars.push(Var::Id(binding.clone()));
+                }
+                collect_let_bound_vars_in_block(&arm.body, vars);
+            }
+        }
+        // The variables of a closure are renamed each time it is called (see `alpha_rename_closure`).
+        Expr::Closure(..) => {}
         // Leaf expressions — no nested blocks
         Expr::Integer(_)
+        | Expr::String(_)
+        | Expr::Char(_)
         | Expr::True
         | Expr::False
         | Expr::Place(_)
@@ -114,9 +148,51 @@ pub fn alpha_rename_method(
     method: &MethodDeclBoundData,
     depth: usize,
 ) -> (MethodDeclBoundData, Map<Var, Var>) {
-    let bound_vars = collect_bound_vars(method);
+    let bound_vars = collect_bound_vars(true, &method.inputs, &method.body);
+    let renamed_vars = renamed_vars(&bound_vars, depth);
+    let renamed = method.with_places_transformed(Transform::Rename(&bound_vars, &renamed_vars));
+    let rename_map: Map<Var, Var> = bound_vars.into_iter().zip(renamed_vars).collect();
+    (renamed, rename_map)
+}
 
-    let renamed_vars: Vec<Var> = bound_vars
+/// Alpha-rename all locally-declared variables in a function body,
+/// as [`alpha_rename_method`] does for methods.
+pub fn alpha_rename_fn(
+    function: &FnDeclBoundData,
+    depth: usize,
+) -> (FnDeclBoundData, Map<Var, Var>) {
+    let bound_vars = collect_bound_vars(false, &function.inputs, &function.body);
+    let renamed_vars = renamed_vars(&bound_vars, depth);
+    let renamed = function.with_places_transformed(Transform::Rename(&bound_vars, &renamed_vars));
+    let rename_map: Map<Var, Var> = bound_vars.into_iter().zip(renamed_vars).collect();
+    (renamed, rename_map)
+}
+
+/// Alpha-rename the variables of a closure for one of its calls: those it
+/// `captured`, its inputs, and those bound in its body, as
+/// [`alpha_rename_method`] does for methods.
+pub fn alpha_rename_closure(
+    captured: &[Var],
+    inputs: &[LocalVariableDecl],
+    body: &Block,
+    depth: usize,
+) -> (Vec<LocalVariableDecl>, Block, Map<Var, Var>) {
+    let mut bound_vars = captured.to_vec();
+    bound_vars.extend(collect_bound_vars(
+        false,
+        inputs,
+        &MethodBody::Block(body.clone()),
+    ));
+    let renamed_vars = renamed_vars(&bound_vars, depth);
+    let transform = Transform::Rename(&bound_vars, &renamed_vars);
+    let inputs = inputs.to_vec().with_places_transformed(transform);
+    let body = body.with_places_transformed(transform);
+    let rename_map: Map<Var, Var> = bound_vars.into_iter().zip(renamed_vars).collect();
+    (inputs, body, rename_map)
+}
+
+fn renamed_vars(bound_vars: &[Var], depth: usize) -> Vec<Var> {
+    bound_vars
         .iter()
         .map(|var| {
             let new_name = match var {
@@ -127,9 +203,5 @@ pub fn alpha_rename_method(
             let new_id: ValueId = crate::dada_lang::term(&new_name);
             Var::Id(new_id)
         })
-        .collect();
-
-    let renamed = method.with_places_transformed(Transform::Rename(&bound_vars, &renamed_vars));
-    let rename_map: Map<Var, Var> = bound_vars.into_iter().zip(renamed_vars).collect();
-    (renamed, rename_map)
+        .collect()
 }
@@ -1,16 +1,22 @@
 pub mod alpha_rename;
+mod tasks;
 
 use std::sync::Arc;
 
+use tasks::FieldAccess;
+
 use formality_core::{set, Upcast};
 
 use crate::grammar::ty_impls::PermTy;
 use crate::grammar::{
-    ClassDecl, ClassDeclBoundData, FieldId, MethodDeclBoundData, MethodId, NamedTy, Parameter,
-    Perm, Place, Program, Projection, Ty, TypeName, ValueId, Var,
+    AdditiveOp, Async, Block, Boxed, CallKind, ClassDecl, ClassDeclBoundData, ClassPredicate,
+    ComparisonOp, EnumDecl, FieldDecl, FieldId, FnDeclBoundData, LocalVariableDecl, MethodBody,
+    MethodDecl, MethodDeclBoundData, MethodId, MultiplicativeOp, NamedTy, Parameter, Perm, Place,
+    Program, Projection, Ty, TypeName, UnaryOp, ValueId, Var,
 };
 
 use crate::type_system::env::Env;
+use crate::type_system::inference::Generic;
 use crate::type_system::predicates::{
     prove_is_boxed, prove_is_copy, prove_is_copy_owned, prove_is_given, prove_is_move,
     prove_is_mut, prove_is_owned,
@@ -21,6 +27,10 @@ const ARRAY_REF_COUNT_OFFSET: usize = 0;
 const ARRAY_CAPACITY_OFFSET: usize = 1;
 const ARRAY_ELEMENTS_OFFSET: usize = 2;
 
+/// A boxed class allocation is laid out like an array's, `[RefCount, fields...]`,
+/// so the refcount is also at `ARRAY_REF_COUNT_OFFSET`.
+const BOXED_FIELDS_OFFSET: usize = 1;
+
 const POINTER_FLAGS_OFFSET: usize = 0;
 const POINTER_DATA_OFFSET: usize = 1;
 
@@ -34,6 +44,54 @@ enum Outcome {
     Return(ObjectValue),
 }
 
+/// The faults that the interpreter checks for by design. Unlike the others, these
+/// do not mean that the type checker accepted a program that it should have rejected.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FaultKind {
+    /// The program evaluated `panic!`.
+    Panic,
+
+    /// The program indexed an array beyond its capacity.
+    ArrayBounds,
+
+    /// The program ran for too long (see [`Interpreter::limit_steps`]).
+    StepLimit,
+}
+
+/// A fault of one of the kinds checked by design (see [`FaultKind`]).
+#[derive(Debug)]
+pub struct Fault {
+    pub kind: FaultKind,
+    message: String,
+}
+
+impl Fault {
+    fn new(kind: FaultKind, message: impl Into<String>) -> anyhow::Error {
+        Fault {
+            kind,
+            message: message.into(),
+        }
+        .into()
+    }
+
+    /// The kind of the fault `error` if it is one checked by design, or `None` for any
+    /// other fault, including a panic of the interpreter itself.
+    pub fn kind_of(error: &anyhow::Error) -> Option<FaultKind> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<Fault>())
+            .map(|fault| fault.kind)
+    }
+}
+
+impl std::fmt::Display for Fault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Fault {}
+
 #[cfg(test)]
 mod tests;
 
@@ -55,6 +113,12 @@ enum Word {
     MutRef(Pointer),
     RefCount(i64),
     Capacity(usize),
+    /// A `Char` value, or a character of a `String`.
+    Char(char),
+    /// A future, as an index into [`Interpreter::futures`].
+    Future(usize),
+    /// A closure, as an index into [`Interpreter::closures`].
+    Closure(usize),
     Uninitialized,
 }
 // ANCHOR_END: Word
@@ -159,7 +223,7 @@ enum FieldPointer<'a> {
 
 // ANCHOR: Pointer
 /// Identifies a position within an allocation.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 struct Pointer {
     index: usize,
     offset: usize,
@@ -223,9 +287,39 @@ pub struct Interpreter<'a> {
     /// (e.g., `_1_self`, `_2_self`) never collide, even across sequential
     /// calls at the same stack depth.
     next_call_id: usize,
+    /// Number of statements left to execute, if limited (see [`Interpreter::limit_steps`]).
+    remaining_steps: Option<usize>,
+    /// The calls to `async fn` methods that produced each future (see [`Word::Future`]).
+    /// An entry is taken once its future has been awaited or dropped.
+    futures: Vec<Option<PendingCall>>,
+    /// The code and captured environment of each closure (see [`Word::Closure`]).
+    /// An entry is taken once its closure has been dropped.
+    closures: Vec<Option<ClosureData>>,
+    /// The tasks started with `spawn` and how they are interleaved (see [`tasks`]).
+    scheduler: tasks::Scheduler<'a>,
 }
 // ANCHOR_END: Interpreter
 
+/// A call to an `async fn` method whose body runs when its future is awaited.
+/// The receiver and arguments have already been evaluated and are held here until then.
+struct PendingCall {
+    class_name: ValueId,
+    class_parameters: Vec<Parameter>,
+    method_id: MethodId,
+    method_parameters: Vec<Parameter>,
+    this: ObjectValue,
+    input_values: Vec<ObjectValue>,
+}
+
+/// A closure value: its inputs and body, and the values it captured, which are
+/// stored together in one allocation (its environment) when the closure is created.
+#[derive(Clone)]
+struct ClosureData {
+    inputs: Vec<LocalVariableDecl>,
+    body: Block,
+    captures: Vec<(Var, ObjectValue)>,
+}
+
 impl<'a> Interpreter<'a> {
     pub fn new(program: &'a Program) -> Self {
         Self {
@@ -234,12 +328,30 @@ impl<'a> Interpreter<'a> {
             output: String::new(),
             indent: 0,
             next_call_id: 0,
+            remaining_steps: None,
+            futures: Vec::new(),
+            closures: Vec::new(),
+            scheduler: tasks::Scheduler::new(),
         }
     }
 
+    /// Seed the scheduler that picks which task runs at each switch point.
+    /// The same seed always produces the same interleaving of tasks.
+    pub fn seed_scheduler(&mut self, seed: u64) {
+        self.scheduler.seed(seed);
+    }
+
+    /// Fault once more than `steps` statements have been executed.
+    /// Used when running programs that may not terminate (e.g., by the minimizer).
+    pub fn limit_steps(&mut self, steps: usize) {
+        self.remaining_steps = Some(steps);
+    }
+
     fn trace(&mut self, msg: impl std::fmt::Display) {
+        let task = self.scheduler.trace_label();
         let indent = "  ".repeat(self.indent);
-        self.output.push_str(&format!("Trace: {indent}{msg}\n"));
+        self.output
+            .push_str(&format!("Trace: {task}{indent}{msg}\n"));
     }
 
     /// Create a minimal Env for layout/predicate queries on concrete types.
@@ -267,6 +379,14 @@ impl<'a> Interpreter<'a> {
         })
     }
 
+    /// Allocate a Bool value, represented as the integer 1 or 0.
+    fn alloc_bool(&mut self, b: bool) -> ObjectValue {
+        ObjectValue {
+            pointer: self.alloc_int(if b { 1 } else { 0 }),
+            ty: Ty::bool(),
+        }
+    }
+
     /// Allocate a zero-sized allocation (used for unit values).
     fn alloc_unit(&mut self) -> Pointer {
         self.alloc_raw(Alloc { data: vec![] })
@@ -323,6 +443,70 @@ impl<'a> Interpreter<'a> {
         Ok(v != 0)
     }
 
+    /// Read the character of a Char value.
+    fn read_char(&self, env: &Env, value: &ObjectValue) -> anyhow::Result<char> {
+        let pointer = if self.is_mut_ref_type(env, &value.ty) {
+            self.read_mut_ref(value.pointer)?
+        } else {
+            value.pointer
+        };
+        match self.read_word(pointer)? {
+            Word::Char(c) => Ok(c),
+            other => anyhow::bail!("expected Char word, got {other:?}"),
+        }
+    }
+
+    /// Consume a Char value: read its character, drop, return it.
+    fn into_char_value(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<char> {
+        let c = self.read_char(env, value)?;
+        self.drop_value(env, value)?;
+        Ok(c)
+    }
+
+    /// Read the text of a String value, through any permission.
+    fn read_string(&self, env: &Env, value: &ObjectValue) -> anyhow::Result<String> {
+        let pointer = if self.is_mut_ref_type(env, &value.ty) {
+            self.read_mut_ref(value.pointer)?
+        } else {
+            value.pointer
+        };
+        let (_flags, buffer) = self.expect_object_pointer(pointer)?;
+        self.read_string_buffer(buffer)
+    }
+
+    /// Read the text stored in the string buffer at `buffer`, which is laid out
+    /// like an array: `[RefCount, Capacity, Char...]`.
+    fn read_string_buffer(&self, buffer: Pointer) -> anyhow::Result<String> {
+        let len = self.read_capacity(buffer + ARRAY_CAPACITY_OFFSET)?;
+        self.read_words(buffer + ARRAY_ELEMENTS_OFFSET, len)?
+            .into_iter()
+            .map(|word| match word {
+                Word::Char(c) => Ok(c),
+                other => anyhow::bail!("expected Char word, got {other:?}"),
+            })
+            .collect()
+    }
+
+    /// Consume a String value: read its text, drop, return it.
+    fn into_string_value(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<String> {
+        let text = self.read_string(env, value)?;
+        self.drop_value(env, value)?;
+        Ok(text)
+    }
+
+    /// Allocate a new, given String holding `text`.
+    fn alloc_string(&mut self, text: &str) -> ObjectValue {
+        let mut data = vec![Word::RefCount(1), Word::Capacity(text.chars().count())];
+        data.extend(text.chars().map(Word::Char));
+        let buffer = self.alloc_raw(Alloc { data });
+        ObjectValue {
+            pointer: self.alloc_raw(Alloc {
+                data: vec![Word::Flags(Flags::Given), Word::Pointer(buffer)],
+            }),
+            ty: Ty::string(),
+        }
+    }
+
     /// Assert that the value at `pointer` is a capacity word and return the capacity.
     fn read_capacity(&self, pointer: Pointer) -> anyhow::Result<usize> {
         match self.read_word(pointer)? {
@@ -411,6 +595,36 @@ impl<'a> Interpreter<'a> {
         anyhow::bail!("no field `{field_id:?}` in class `{class_name:?}`")
     }
 
+    /// Offset of the first field from the start of the object data of a class:
+    /// the object data of a boxed class starts with its refcount.
+    fn class_fields_offset(&self, class_decl: &ClassDecl) -> usize {
+        match class_decl.boxed {
+            Boxed::Yes => BOXED_FIELDS_OFFSET,
+            Boxed::No => 0,
+        }
+    }
+
+    /// True if the values of `class_decl`, whose fields are `fields`, end with a
+    /// marker word. A `tracked class` whose fields take no words would otherwise
+    /// leave no trace of being moved, and [`Self::drop_value`] could not tell a
+    /// consumed value from one that is still there.
+    fn has_tracked_marker(
+        &self,
+        env: &Env,
+        class_decl: &ClassDecl,
+        fields: &[FieldDecl],
+    ) -> anyhow::Result<bool> {
+        if class_decl.class_predicate != ClassPredicate::Tracked || class_decl.boxed == Boxed::Yes {
+            return Ok(false);
+        }
+        for field in fields {
+            if self.size_of(env, &field.ty)? > 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     /// Check if a type is owned (delegates to the type system).
     fn is_owned_type(&self, env: &Env, ty: impl Upcast<Ty>) -> bool {
         let ty = ty.upcast();
@@ -459,8 +673,12 @@ impl<'a> Interpreter<'a> {
     fn size_of_named_ty(&self, env: &Env, named_ty: &NamedTy) -> anyhow::Result<usize> {
         let NamedTy { name, parameters } = named_ty;
         match name {
-            TypeName::Int | TypeName::Bool => Ok(1),
-            TypeName::Array => Ok(2), // Word::Flags + Word::Pointer
+            TypeName::Int
+            | TypeName::Bool
+            | TypeName::Char
+            | TypeName::Future
+            | TypeName::Closure(_) => Ok(1),
+            TypeName::Array | TypeName::String => Ok(2), // Word::Flags + Word::Pointer
             TypeName::Tuple(_) => {
                 let mut total = 0;
                 for param in parameters {
@@ -472,7 +690,24 @@ impl<'a> Interpreter<'a> {
                 Ok(total)
             }
             TypeName::Id(class_name) => {
+                if let Ok(enum_decl) = self.program.enum_named(class_name) {
+                    // A discriminant word followed by the fields of the largest variant.
+                    let enum_data = enum_decl.binder.instantiate_with(parameters)?;
+                    let mut max_variant_size = 0;
+                    for variant in &enum_data.variants {
+                        let mut variant_size = 0;
+                        for field in &variant.fields {
+                            variant_size += self.size_of(env, &field.ty)?;
+                        }
+                        max_variant_size = max_variant_size.max(variant_size);
+                    }
+                    return Ok(1 + max_variant_size);
+                }
+
                 let class_decl = self.program.class_named(class_name)?;
+                if class_decl.boxed == Boxed::Yes {
+                    return Ok(2); // Word::Flags + Word::Pointer
+                }
 
                 let ClassDeclBoundData {
                     predicates: _,
@@ -485,12 +720,49 @@ impl<'a> Interpreter<'a> {
                 for field in &fields {
                     total += self.size_of(env, &field.ty)?;
                 }
+                if self.has_tracked_marker(env, class_decl, &fields)? {
+                    total += 1;
+                }
 
                 Ok(total)
             }
         }
     }
 
+    /// The types of the words of a value of the enum `enum_decl` whose discriminant
+    /// is `discriminant`: the discriminant itself, the fields of that variant, and
+    /// `Int` padding up to the size of the largest variant.
+    fn enum_layout(
+        &self,
+        env: &Env,
+        enum_decl: &EnumDecl,
+        parameters: &[Parameter],
+        discriminant: i64,
+    ) -> anyhow::Result<Vec<Ty>> {
+        let enum_data = enum_decl.binder.instantiate_with(parameters)?;
+        let Some(variant) = usize::try_from(discriminant)
+            .ok()
+            .and_then(|d| enum_data.variants.get(d))
+        else {
+            anyhow::bail!(
+                "invalid discriminant {discriminant} for enum `{:?}`",
+                enum_decl.name
+            );
+        };
+
+        let mut tys = vec![Ty::int()];
+        let mut variant_size = 0;
+        for field in &variant.fields {
+            variant_size += self.size_of(env, &field.ty)?;
+            tys.push(field.ty.clone());
+        }
+
+        let enum_ty = NamedTy::new(&enum_decl.name, parameters);
+        let padding = self.size_of_named_ty(env, &enum_ty)? - 1 - variant_size;
+        tys.extend(std::iter::repeat(Ty::int()).take(padding));
+        Ok(tys)
+    }
+
     // ---------------------------------------------------------------
     // Core value operations
     // ---------------------------------------------------------------
@@ -603,10 +875,12 @@ impl<'a> Interpreter<'a> {
     ) -> anyhow::Result<()> {
         assert!(matches!(array_data.named_ty.name, TypeName::Array));
         let capacity = self.read_capacity(array_data.pointer + ARRAY_CAPACITY_OFFSET)?;
-        anyhow::ensure!(
-            index < capacity,
-            "{op}: index {index} out of bounds (capacity {capacity})"
-        );
+        if index >= capacity {
+            return Err(Fault::new(
+                FaultKind::ArrayBounds,
+                format!("{op}: index {index} out of bounds (capacity {capacity})"),
+            ));
+        }
         Ok(())
     }
 
@@ -643,7 +917,7 @@ impl<'a> Interpreter<'a> {
         object_ty: &NamedTy,
         op: &mut impl FnMut(&mut Self, &Env, FieldPointer<'_>) -> anyhow::Result<()>,
     ) -> anyhow::Result<()> {
-        match self.find_object_fields(object_data_pointer, object_ty)? {
+        match self.find_object_fields(env, object_data_pointer, object_ty)? {
             None => op(
                 self,
                 env,
@@ -673,6 +947,7 @@ impl<'a> Interpreter<'a> {
     /// If this returns `None`, then this is a scalar/leaf value without internal fields.
     fn find_object_fields(
         &mut self,
+        env: &Env,
         object_data_pointer: Pointer,
         object_ty: &NamedTy,
     ) -> Result<Option<(Pointer, Vec<Ty>)>, anyhow::Error> {
@@ -685,16 +960,38 @@ impl<'a> Interpreter<'a> {
                     .map(|p| p.as_ty().expect("tuple parameters to be types").clone())
                     .collect(),
             )),
-            TypeName::Int | TypeName::Bool => None,
-            TypeName::Array => {
+            TypeName::Int
+            | TypeName::Bool
+            | TypeName::Char
+            | TypeName::Future
+            | TypeName::Closure(_) => None,
+            TypeName::Array | TypeName::String => {
                 // Array elements are user-managed (unsafe); we don't traverse them.
+                // The characters of a string are copied like `Int`s.
                 Some((object_data_pointer + ARRAY_ELEMENTS_OFFSET, vec![]))
             }
             TypeName::Id(class_name) => {
+                if let Ok(enum_decl) = self.program.enum_named(class_name) {
+                    // The fields depend on the discriminant. Once that has been
+                    // uninitialized (i.e., the value was moved), treat it as a leaf.
+                    if !self.is_word_initialized(object_data_pointer) {
+                        return Ok(None);
+                    }
+                    let discriminant = self.read_int(object_data_pointer)?;
+                    return Ok(Some((
+                        object_data_pointer,
+                        self.enum_layout(env, enum_decl, parameters, discriminant)?,
+                    )));
+                }
+
                 let class_decl = self.program.class_named(&class_name)?;
                 let class_data = class_decl.binder.instantiate_with(&parameters)?;
+                if self.has_tracked_marker(env, class_decl, &class_data.fields)? {
+                    // Moving the value uninitializes its marker word, as for an `Int`.
+                    return Ok(None);
+                }
                 Some((
-                    object_data_pointer,
+                    object_data_pointer + self.class_fields_offset(class_decl),
                     class_data
                         .fields
                         .into_iter()
@@ -861,6 +1158,8 @@ impl<'a> Interpreter<'a> {
                         self.write_refcount(heap_pointer + ARRAY_REF_COUNT_OFFSET, new_refcount)?;
 
                         if new_refcount == 0 {
+                            let named_ty = self.named_ty(ty);
+                            self.drop_captures_in_fields(env, heap_pointer, &named_ty)?;
                             self.drop_object_data(
                                 env,
                                 &ObjectData {
@@ -944,11 +1243,38 @@ impl<'a> Interpreter<'a> {
         Ok(())
     }
 
+    /// Drop an owned value (see [`Self::consume_value`]), faulting if it is a whole
+    /// value of a `tracked class` or `tracked enum`: the former must be consumed by a
+    /// `given self` method, the latter by a `match`. (Values of a tracked class with
+    /// no fields carry a marker word, see [`Self::has_tracked_marker`].)
+    fn drop_value(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<()> {
+        if self.is_owned_type(env, &value.ty) && self.is_value_whole(env, value) {
+            let named_ty = self.named_ty(&value.ty);
+            if let TypeName::Id(name) = &named_ty.name {
+                if let Ok(enum_decl) = self.program.enum_named(name) {
+                    anyhow::ensure!(
+                        enum_decl.class_predicate != ClassPredicate::Tracked,
+                        "value of tracked enum `{name:?}` dropped without being consumed"
+                    );
+                } else if let Ok(class_decl) = self.program.class_named(name) {
+                    anyhow::ensure!(
+                        class_decl.class_predicate != ClassPredicate::Tracked,
+                        "value of tracked class `{name:?}` dropped without being consumed"
+                    );
+                }
+            }
+        }
+        self.consume_value(env, value)
+    }
+
     /// Drop an owned value (Given or Shared): run the drop body if present,
     /// then recursively drop owned fields, then uninitialize.
     /// Given and Shared converge at every leaf — a Given
     /// array with refcount 1 decrements the same way as a Shared array.
-    fn drop_value(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<()> {
+    ///
+    /// Unlike [`Self::drop_value`], this accepts values of a `tracked class`:
+    /// it is used for the `self` of a method, which consumes it.
+    fn consume_value(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<()> {
         // Check if this is an owned, initialized class with a drop body.
         // Only owned handles (given/shared) execute the drop body.
         // Only run the drop body if the value is "whole" (all fields initialized).
@@ -958,7 +1284,10 @@ impl<'a> Interpreter<'a> {
                 if let Ok(class_decl) = self.program.class_named(class_name) {
                     if let Ok(class_data) = class_decl.binder.instantiate_with(&named_ty.parameters)
                     {
-                        if !class_data.drop_body.block.statements.is_empty() {
+                        // The drop body of a boxed class runs once the last handle is dropped.
+                        if !class_data.drop_body.block.statements.is_empty()
+                            && (class_decl.boxed == Boxed::No || self.is_last_handle(value)?)
+                        {
                             // Execute the drop body before field cleanup.
                             self.execute_drop_body(
                                 value,
@@ -972,18 +1301,92 @@ impl<'a> Interpreter<'a> {
                 }
             }
         }
+
+        self.drop_captures(env, value)?;
         self.traverse_value(env, value, &mut Self::and_drop_fields)
     }
 
+    /// Drop the values captured by the futures and closures that `value` owns,
+    /// whether it is one itself or stores them in its (flat) fields. A future that
+    /// is dropped without being awaited never runs its call, so the values it
+    /// captured are dropped along with it. Likewise, an owned closure owns its
+    /// environment (the values its body gave away are already uninitialized).
+    ///
+    /// Those stored in the fields of a boxed value are dropped along with its heap
+    /// data, see [`Self::drop_captures_in_fields`].
+    fn drop_captures(&mut self, env: &Env, value: &ObjectValue) -> anyhow::Result<()> {
+        if !self.is_owned_type(env, &value.ty) {
+            return Ok(());
+        }
+        let ObjectValueLayout::Flat(pointer, ty) = self.value_layout(env, value)? else {
+            return Ok(());
+        };
+        let named_ty = self.named_ty(ty);
+        if named_ty.name == TypeName::Future {
+            if self.is_word_initialized(pointer) {
+                let call = self.take_pending_call(value)?;
+                self.drop_value(env, &call.this)?;
+                for input_value in &call.input_values {
+                    self.drop_value(env, input_value)?;
+                }
+            }
+            Ok(())
+        } else if named_ty.name.is_closure() {
+            if self.is_word_initialized(pointer) {
+                if let Word::Closure(index) = self.read_word(pointer)? {
+                    if let Some(closure) = self.closures[index].take() {
+                        for (_, captured_value) in &closure.captures {
+                            self.drop_value(env, captured_value)?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        } else {
+            self.drop_captures_in_fields(env, pointer, &named_ty)
+        }
+    }
+
+    /// Drop the values captured by the futures and closures stored in the fields
+    /// of the object data at `object_data_pointer` (see [`Self::drop_captures`]).
+    fn drop_captures_in_fields(
+        &mut self,
+        env: &Env,
+        object_data_pointer: Pointer,
+        object_ty: &NamedTy,
+    ) -> anyhow::Result<()> {
+        let Some((field_pointer, field_tys)) =
+            self.find_object_fields(env, object_data_pointer, object_ty)?
+        else {
+            return Ok(());
+        };
+        let mut offset = 0;
+        for field_ty in field_tys {
+            self.drop_captures(
+                env,
+                &ObjectValue {
+                    pointer: field_pointer + offset,
+                    ty: field_ty.clone(),
+                },
+            )?;
+            offset += self.size_of(env, &field_ty)?;
+        }
+        Ok(())
+    }
+
+    /// True if dropping the boxed value `value` releases its heap allocation.
+    fn is_last_handle(&self, value: &ObjectValue) -> anyhow::Result<bool> {
+        let (_flags, heap_pointer) = self.expect_object_pointer(value.pointer)?;
+        Ok(self.read_refcount(heap_pointer + ARRAY_REF_COUNT_OFFSET)? == 1)
+    }
+
     /// Check if a value is "whole" — all accessible places within it are initialized.
     /// Accessible places are fields of classes and tuples, recursively, but NOT
     /// array elements (those are user-managed). A whole value has every word of
     /// every accessible sub-place initialized.
     ///
-    /// Note: when boxed user classes are added, this will need to dereference
-    /// through the [Flags, Pointer] wrapper and check the fields on the heap.
-    /// Currently only Array is boxed, and its elements are not accessible places,
-    /// so we just check the wrapper words.
+    /// For boxed values we check the [Flags, Pointer] wrapper and, for boxed
+    /// classes, dereference it to check the fields on the heap.
     fn is_value_whole(&self, env: &Env, value: &ObjectValue) -> bool {
         if self.is_mut_ref_type(env, &value.ty) {
             return self.is_word_initialized(value.pointer);
@@ -991,8 +1394,21 @@ impl<'a> Interpreter<'a> {
         if self.is_boxed_type(env, &value.ty) {
             // Check the [Flags, Pointer] wrapper words.
             // Array elements are not accessible places, so we don't recurse into them.
-            return self.is_word_initialized(value.pointer)
-                && self.is_word_initialized(value.pointer + 1);
+            if !self.is_word_initialized(value.pointer)
+                || !self.is_word_initialized(value.pointer + 1)
+            {
+                return false;
+            }
+            let named_ty = self.named_ty(&value.ty);
+            if named_ty.name == TypeName::Array || named_ty.name == TypeName::String {
+                return true;
+            }
+            let Word::Pointer(heap_pointer) =
+                self.read_word_raw(value.pointer + POINTER_DATA_OFFSET)
+            else {
+                return false;
+            };
+            return self.is_named_ty_whole(env, heap_pointer + BOXED_FIELDS_OFFSET, &named_ty);
         }
         // Flat type: check fields recursively via find_object_fields.
         self.is_named_ty_whole(env, value.pointer, &self.named_ty(&value.ty))
@@ -1003,8 +1419,12 @@ impl<'a> Interpreter<'a> {
         // We need &mut self for find_object_fields (it looks up the program),
         // but we're only reading. Use the same logic inline.
         match &named_ty.name {
-            TypeName::Int | TypeName::Bool => self.is_word_initialized(pointer),
-            TypeName::Array => {
+            TypeName::Int
+            | TypeName::Bool
+            | TypeName::Char
+            | TypeName::Future
+            | TypeName::Closure(_) => self.is_word_initialized(pointer),
+            TypeName::Array | TypeName::String => {
                 // Boxed — just check wrapper (handled above, but be safe)
                 self.is_word_initialized(pointer) && self.is_word_initialized(pointer + 1)
             }
@@ -1024,6 +1444,29 @@ impl<'a> Interpreter<'a> {
                 true
             }
             TypeName::Id(class_name) => {
+                if let Ok(enum_decl) = self.program.enum_named(class_name) {
+                    let Ok(discriminant) = self.read_int(pointer) else {
+                        return false;
+                    };
+                    let Ok(tys) =
+                        self.enum_layout(env, enum_decl, &named_ty.parameters, discriminant)
+                    else {
+                        return false;
+                    };
+                    let mut offset = 0;
+                    for ty in &tys {
+                        let field_value = ObjectValue {
+                            pointer: pointer + offset,
+                            ty: ty.clone(),
+                        };
+                        if !self.is_value_whole(env, &field_value) {
+                            return false;
+                        }
+                        offset += self.size_of(env, ty).unwrap_or(0);
+                    }
+                    return true;
+                }
+
                 let Ok(class_decl) = self.program.class_named(class_name) else {
                     return false;
                 };
@@ -1031,6 +1474,9 @@ impl<'a> Interpreter<'a> {
                 else {
                     return false;
                 };
+                if let Ok(true) = self.has_tracked_marker(env, class_decl, &class_data.fields) {
+                    return self.is_word_initialized(pointer);
+                }
                 let mut offset = 0;
                 for field in &class_data.fields {
                     let field_value = ObjectValue {
@@ -1061,6 +1507,10 @@ impl<'a> Interpreter<'a> {
     /// not a copy. For given classes, `self: given Class[...]`. For share/shared
     /// classes, `self: ref[magic] Class[...]`.
     ///
+    /// For boxed classes, `This` must instead hold a `[Flags, Pointer]` wrapper:
+    /// for given classes that is the value itself, for share/shared classes a
+    /// temporary borrowed wrapper that is scrubbed once the body has run.
+    ///
     /// When the frame is popped, `This` and `Magic` are skipped (they refer to
     /// the value being dropped — field cleanup happens after this returns).
     fn execute_drop_body(
@@ -1095,7 +1545,7 @@ impl<'a> Interpreter<'a> {
         // Build the self type and point This at the resolved object data.
         // This is an alias into the same memory, not a copy.
         let self_ty = match class_predicate {
-            crate::grammar::ClassPredicate::Given => {
+            crate::grammar::ClassPredicate::Tracked | crate::grammar::ClassPredicate::Given => {
                 // given class: self has type `given Class[...]`
                 class_ty
             }
@@ -1105,8 +1555,19 @@ impl<'a> Interpreter<'a> {
             }
         };
 
+        let mut temporary_wrapper = None;
+        let this_pointer = match (self.program.class_named(class_name)?.boxed, class_predicate) {
+            (Boxed::No, _) => magic_data.pointer,
+            (Boxed::Yes, ClassPredicate::Tracked | ClassPredicate::Given) => value.pointer,
+            (Boxed::Yes, _) => *temporary_wrapper.insert(self.alloc_raw(Alloc {
+                data: vec![
+                    Word::Flags(Flags::Borrowed),
+                    Word::Pointer(magic_data.pointer),
+                ],
+            })),
+        };
         stack_frame.env = stack_frame.env.push_local_variable(Var::This, self_ty)?;
-        stack_frame.insert_variable(Var::This, magic_data.pointer);
+        stack_frame.insert_variable(Var::This, this_pointer);
 
         self.trace(format_args!("drop {class_name:?}"));
         self.indent += 1;
@@ -1136,6 +1597,10 @@ impl<'a> Interpreter<'a> {
             self.drop_value(env, &tv)?;
         }
 
+        if let Some(wrapper) = temporary_wrapper {
+            self.uninitialize_words(wrapper, 2);
+        }
+
         self.indent -= 1;
 
         Ok(())
@@ -1187,6 +1652,10 @@ impl<'a> Interpreter<'a> {
         value_ty: &Ty,
     ) -> anyhow::Result<ObjectValue> {
         match object_data.operms {
+            ObjectPerms::Given if self.is_copy_type(env, value_ty) => {
+                // Value types are copied, not moved: the source stays initialized.
+                self.share_place(env, object_data, value_ty)
+            }
             ObjectPerms::Given => {
                 assert!(self.is_owned_type(env, value_ty));
                 assert!(self.is_move_type(env, value_ty));
@@ -1228,13 +1697,7 @@ impl<'a> Interpreter<'a> {
                 // element has Shared flags due to subtyping (shared ≤ ref ≤ given).
                 // The operation is correct regardless: copy words, increment refcounts
                 // on boxed fields.
-                let copied = self.copy_object_data(env, &object_data)?;
-                let shared_value = ObjectValue {
-                    pointer: copied,
-                    ty: value_ty.clone(),
-                };
-                self.traverse_value(env, &shared_value, &mut Self::and_copy_shared_fields)?;
-                Ok(shared_value)
+                self.share_place(env, object_data, value_ty)
             }
             ObjectPerms::Borrowed => {
                 // Runtime flags say Borrowed: produce a borrow (copy words, set
@@ -1246,6 +1709,23 @@ impl<'a> Interpreter<'a> {
         }
     }
 
+    /// Produce a shared copy of a place: copy its words and increment
+    /// the refcounts of its boxed fields. The source stays initialized.
+    fn share_place(
+        &mut self,
+        env: &Env,
+        object_data: &ObjectData,
+        value_ty: &Ty,
+    ) -> anyhow::Result<ObjectValue> {
+        let copied = self.copy_object_data(env, &object_data)?;
+        let shared_value = ObjectValue {
+            pointer: copied,
+            ty: value_ty.clone(),
+        };
+        self.traverse_value(env, &shared_value, &mut Self::and_copy_shared_fields)?;
+        Ok(shared_value)
+    }
+
     /// `place.ref`: create a borrowed reference to a place.
     fn ref_place(
         &mut self,
@@ -1496,10 +1976,12 @@ impl<'a> Interpreter<'a> {
                 } => {
                     let (field_offset, field_ty) =
                         self.field_offset_by_name(env, class_name, parameters, field_id)?;
+                    let fields_offset =
+                        self.class_fields_offset(self.program.class_named(class_name)?);
                     Ok(ObjectValue {
                         pointer: Pointer {
                             index: owner_object.pointer.index,
-                            offset: owner_object.pointer.offset + field_offset,
+                            offset: owner_object.pointer.offset + fields_offset + field_offset,
                         },
                         ty: field_ty,
                     })
@@ -1534,10 +2016,12 @@ impl<'a> Interpreter<'a> {
                 } => {
                     let capacity =
                         self.read_capacity(owner_object.pointer + ARRAY_CAPACITY_OFFSET)?;
-                    anyhow::ensure!(
-                        *index < capacity,
-                        "index out of bounds {index} >= {capacity}"
-                    );
+                    if *index >= capacity {
+                        return Err(Fault::new(
+                            FaultKind::ArrayBounds,
+                            format!("index out of bounds {index} >= {capacity}"),
+                        ));
+                    }
                     let element_ty = extract_array_element_ty(parameters)?;
                     let element_size = self.size_of(env, &element_ty)?;
                     let offset = ARRAY_ELEMENTS_OFFSET + index * element_size;
@@ -1630,6 +2114,38 @@ impl<'a> Interpreter<'a> {
                 other => write!(buf, "<unexpected: {other:?}>")?,
             },
 
+            Ty::NamedTy(NamedTy {
+                name: TypeName::Char,
+                ..
+            }) => match self.read_word_raw(ptr) {
+                Word::Uninitialized => write!(buf, "\u{26a1}")?,
+                Word::Char(c) => write!(buf, "{c:?}")?,
+                other => write!(buf, "<unexpected: {other:?}>")?,
+            },
+
+            Ty::NamedTy(NamedTy {
+                name: TypeName::String,
+                ..
+            }) => match self.read_word_raw(ptr) {
+                Word::Uninitialized => write!(buf, "\u{26a1}")?,
+                Word::Flags(flags) => {
+                    write!(buf, "String {{ flag: {flags:?}")?;
+                    match self.read_word_raw(ptr + POINTER_DATA_OFFSET) {
+                        Word::Pointer(buffer) => {
+                            let refcount = self.read_refcount(buffer).unwrap_or(-1);
+                            write!(buf, ", rc: {refcount}")?;
+                            match self.read_string_buffer(buffer) {
+                                Ok(text) => write!(buf, ", {text:?} }}")?,
+                                Err(_) => write!(buf, ", \u{26a1} }}")?,
+                            }
+                        }
+                        Word::Uninitialized => write!(buf, ", \u{26a1} }}")?,
+                        other => write!(buf, ", <unexpected: {other:?}> }}")?,
+                    }
+                }
+                other => write!(buf, "<unexpected: {other:?}>")?,
+            },
+
             Ty::NamedTy(NamedTy {
                 name: TypeName::Bool,
                 ..
@@ -1648,6 +2164,43 @@ impl<'a> Interpreter<'a> {
                 write!(buf, "()")?;
             }
 
+            Ty::NamedTy(NamedTy {
+                name: TypeName::Id(enum_name),
+                parameters,
+            }) if self.program.enum_named(enum_name).is_ok() => {
+                let enum_decl = self.program.enum_named(enum_name)?;
+                let enum_data = enum_decl.binder.instantiate_with(parameters)?;
+                let variant = match self.read_word_raw(ptr) {
+                    Word::Uninitialized => {
+                        write!(buf, "\u{26a1}")?;
+                        return Ok(());
+                    }
+                    Word::Int(discriminant) => usize::try_from(discriminant)
+                        .ok()
+                        .and_then(|d| enum_data.variants.get(d)),
+                    _ => None,
+                };
+                let Some(variant) = variant else {
+                    write!(buf, "<unexpected: {:?}>", self.read_word_raw(ptr))?;
+                    return Ok(());
+                };
+
+                write!(buf, "{enum_name:?}::{:?}", variant.name)?;
+                write!(buf, " {{ ")?;
+
+                let mut offset = 1;
+                for (field, index) in variant.fields.iter().zip(0..) {
+                    if index > 0 {
+                        write!(buf, ", ")?;
+                    }
+                    write!(buf, "{:?}: ", field.name)?;
+                    self.fmt_value(env, buf, ptr + offset, &field.ty)?;
+                    offset += self.size_of(env, &field.ty)?;
+                }
+
+                write!(buf, " }}")?;
+            }
+
             Ty::NamedTy(NamedTy {
                 name: TypeName::Id(class_name),
                 parameters,
@@ -1655,14 +2208,44 @@ impl<'a> Interpreter<'a> {
                 let class_decl = self.program.class_named(class_name)?;
                 let class_data = class_decl.binder.instantiate_with(parameters)?;
 
+                if class_decl.boxed == Boxed::Yes && !self.is_word_initialized(ptr) {
+                    write!(buf, "\u{26a1}")?;
+                    return Ok(());
+                }
+
                 write!(buf, "{class_name:?}")?;
                 write!(buf, " {{ ")?;
 
+                // A boxed class is displayed like an array: its flag (when we are looking
+                // at the `[Flags, Pointer]` wrapper rather than through a `mut` reference)
+                // and refcount, followed by the fields stored on the heap.
+                let mut ptr = ptr;
+                let mut first = true;
+                if class_decl.boxed == Boxed::Yes {
+                    if let Word::Flags(flags) = self.read_word_raw(ptr) {
+                        write!(buf, "flag: {flags:?}, ")?;
+                        ptr = match self.read_word_raw(ptr + POINTER_DATA_OFFSET) {
+                            Word::Pointer(p) => p,
+                            other => {
+                                write!(buf, "<unexpected: {other:?}> }}")?;
+                                return Ok(());
+                            }
+                        };
+                    }
+                    match self.read_word_raw(ptr + ARRAY_REF_COUNT_OFFSET) {
+                        Word::RefCount(refcount) => write!(buf, "rc: {refcount}")?,
+                        other => write!(buf, "<unexpected: {other:?}>")?,
+                    }
+                    ptr = ptr + BOXED_FIELDS_OFFSET;
+                    first = false;
+                }
+
                 let mut offset = 0;
-                for (field, index) in class_data.fields.iter().zip(0..) {
-                    if index > 0 {
+                for field in class_data.fields.iter() {
+                    if !first {
                         write!(buf, ", ")?;
                     }
+                    first = false;
                     write!(buf, "{:?}: ", field.name)?;
                     let field_ptr = Pointer {
                         index: ptr.index,
@@ -1728,6 +2311,44 @@ impl<'a> Interpreter<'a> {
                 }
             }
 
+            Ty::NamedTy(NamedTy {
+                name: TypeName::Future,
+                ..
+            }) => match self.read_word_raw(ptr) {
+                Word::Uninitialized => write!(buf, "\u{26a1}")?,
+                Word::Future(index) => match &self.futures[index] {
+                    Some(call) => write!(
+                        buf,
+                        "Future {{ {:?}.{:?} }}",
+                        call.class_name, call.method_id
+                    )?,
+                    None => write!(buf, "Future {{ \u{26a1} }}")?,
+                },
+                other => write!(buf, "<unexpected: {other:?}>")?,
+            },
+
+            Ty::NamedTy(NamedTy {
+                name: TypeName::Closure(_),
+                ..
+            }) => match self.read_word_raw(ptr) {
+                Word::Uninitialized => write!(buf, "\u{26a1}")?,
+                Word::Closure(index) => match &self.closures[index] {
+                    Some(closure) => {
+                        write!(buf, "Closure {{ ")?;
+                        for (i, (var, value)) in closure.captures.iter().enumerate() {
+                            if i > 0 {
+                                write!(buf, ", ")?;
+                            }
+                            write!(buf, "{var:?}: ")?;
+                            self.fmt_value(env, buf, value.pointer, &value.ty)?;
+                        }
+                        write!(buf, " }}")?;
+                    }
+                    None => write!(buf, "Closure {{ \u{26a1} }}")?,
+                },
+                other => write!(buf, "<unexpected: {other:?}>")?,
+            },
+
             Ty::NamedTy(NamedTy {
                 name: TypeName::Tuple(_),
                 ..
@@ -1770,8 +2391,11 @@ impl<'a> Interpreter<'a> {
             );
         }
 
-        // Build flat allocation
+        // Build flat allocation; a boxed class starts with its refcount.
         let mut data = Vec::new();
+        if class_decl.boxed == Boxed::Yes {
+            data.push(Word::RefCount(1));
+        }
 
         // Flags word for non-copy class instantiations
         // Copy field words into the allocation
@@ -1780,8 +2404,17 @@ impl<'a> Interpreter<'a> {
             let words = self.read_words(field_tv.pointer, field_size)?;
             data.extend_from_slice(&words);
         }
+        if self.has_tracked_marker(env, class_decl, &fields)? {
+            data.push(Word::Int(0));
+        }
 
-        let ptr = self.alloc_raw(Alloc { data });
+        let mut ptr = self.alloc_raw(Alloc { data });
+        if class_decl.boxed == Boxed::Yes {
+            // The value itself is the `[Flags, Pointer]` wrapper, as for `array_new`.
+            ptr = self.alloc_raw(Alloc {
+                data: vec![Word::Flags(Flags::Given), Word::Pointer(ptr)],
+            });
+        }
         let ty = Ty::NamedTy(NamedTy {
             name: class_name.upcast(),
             parameters: parameters.to_vec(),
@@ -1789,6 +2422,65 @@ impl<'a> Interpreter<'a> {
         Ok(ObjectValue { pointer: ptr, ty })
     }
 
+    /// The generic parameters written for `generic`. The interpreter does not infer
+    /// omitted parameters: the type checker does, and `elaborate_program` writes them
+    /// out, so they can only be missing from a program that was not type-checked.
+    fn explicit_parameters(
+        &self,
+        env: &Env,
+        generic: &Generic,
+        parameters: &[Parameter],
+    ) -> anyhow::Result<Vec<Parameter>> {
+        if env.needs_inference(generic, parameters) {
+            anyhow::bail!(
+                "the parameters of {} must be given explicitly \
+                 in a program that is not type-checked",
+                generic.describe()
+            );
+        }
+        Ok(parameters.to_vec())
+    }
+
+    fn instantiate_variant(
+        &mut self,
+        env: &Env,
+        enum_name: &ValueId,
+        parameters: &[Parameter],
+        variant_name: &ValueId,
+        field_values: &[ObjectValue],
+    ) -> anyhow::Result<ObjectValue> {
+        let enum_decl = self.program.enum_named(enum_name)?;
+        let enum_data = enum_decl.binder.instantiate_with(parameters)?;
+        let (discriminant, variant) = enum_data.variant_named(variant_name)?;
+
+        if variant.fields.len() != field_values.len() {
+            anyhow::bail!(
+                "variant `{enum_name:?}::{variant_name:?}` has {} fields but {} were provided",
+                variant.fields.len(),
+                field_values.len()
+            );
+        }
+
+        let ty = Ty::NamedTy(NamedTy {
+            name: enum_name.upcast(),
+            parameters: parameters.to_vec(),
+        });
+        let size = self.size_of(env, &ty)?;
+
+        // Build flat allocation: the discriminant, the variant's fields,
+        // and padding up to the size of the largest variant.
+        let mut data = vec![Word::Int(discriminant as i64)];
+        for (field_decl, field_tv) in variant.fields.iter().zip(field_values) {
+            let field_size = self.size_of(env, &field_decl.ty)?;
+            let words = self.read_words(field_tv.pointer, field_size)?;
+            data.extend_from_slice(&words);
+        }
+        data.resize(size, Word::Int(0));
+
+        let ptr = self.alloc_raw(Alloc { data });
+        Ok(ObjectValue { pointer: ptr, ty })
+    }
+
     // ---------------------------------------------------------------
     // Method finding and calling
     // ---------------------------------------------------------------
@@ -1800,8 +2492,22 @@ impl<'a> Interpreter<'a> {
         method_id: &MethodId,
         method_parameters: &[Parameter],
     ) -> anyhow::Result<MethodDeclBoundData> {
+        let method_decl = self.find_method_decl(class_name, class_parameters, method_id)?;
+        let method_data = method_decl.binder.instantiate_with(method_parameters)?;
+        Ok(method_data)
+    }
+
+    /// Find the declaration of the method `method_id` of the class `class_name`,
+    /// either among its own methods or those of the impls that apply to it.
+    fn find_method_decl(
+        &self,
+        class_name: &ValueId,
+        class_parameters: &[Parameter],
+        method_id: &MethodId,
+    ) -> anyhow::Result<MethodDecl> {
         let ClassDecl {
             name: _,
+            boxed: _,
             class_predicate: _,
             binder,
         } = self.program.class_named(class_name)?;
@@ -1813,15 +2519,27 @@ impl<'a> Interpreter<'a> {
             drop_body: _,
         } = binder.instantiate_with(class_parameters)?;
 
+        // Trait methods are dispatched statically: the receiver has a known class,
+        // so we look for the method among the impls that apply to it.
+        let class_ty = NamedTy {
+            name: class_name.upcast(),
+            parameters: class_parameters.to_vec(),
+        };
+        let impl_methods = self
+            .program
+            .impls_for(&class_ty)?
+            .into_iter()
+            .flat_map(|impl_data| impl_data.methods);
+
         let method_decl = methods
-            .iter()
+            .into_iter()
+            .chain(impl_methods)
             .find(|m| m.name == *method_id)
             .ok_or_else(|| {
                 anyhow::anyhow!("class `{class_name:?}` has no method `{method_id:?}`")
             })?;
 
-        let method_data = method_decl.binder.instantiate_with(method_parameters)?;
-        Ok(method_data)
+        Ok(method_decl)
     }
 
     fn call_method(
@@ -1861,61 +2579,240 @@ impl<'a> Interpreter<'a> {
             body,
         } = renamed;
 
-        // Extend the caller's env with the method's renamed bindings.
-        let mut env = caller_frame.env.clone();
-
         let self_var = rename_map[&Var::This].clone();
 
-        // Use the receiver's type directly as the type of the renamed self.
-        // The receiver already carries the correct permission from the
-        // access mode used at the call site (e.g., `v.mut` produces
-        // `mut[v] Vec[T]`). Applying `this_decl.perm` on top would
-        // double-wrap (e.g., `mut[v] mut[v] Vec[T]`).
-        let this_ty = this.ty;
-        env = env.push_local_variable(self_var.clone(), this_ty.clone())?;
+        self.call_body(
+            caller_frame,
+            &format!("{class_name:?}.{method_id:?}"),
+            Some((self_var, this)),
+            vec![],
+            &inputs,
+            &body,
+            input_values,
+        )
+    }
+
+    /// Allocate a future for the call `call`, which runs when the future is awaited.
+    fn alloc_future(&mut self, call: PendingCall) -> ObjectValue {
+        let index = self.futures.len();
+        self.futures.push(Some(call));
+        ObjectValue {
+            pointer: self.alloc_raw(Alloc {
+                data: vec![Word::Future(index)],
+            }),
+            // The type system tracks the output and captured values of a future;
+            // at runtime it is just a handle to its pending call.
+            ty: NamedTy::future(Ty::unit(), vec![]).upcast(),
+        }
+    }
+
+    /// Take the pending call of the owned future `future`, consuming the future.
+    /// Futures are awaited one at a time, so each runs to completion before the
+    /// program continues, in the order they are awaited.
+    fn take_pending_call(&mut self, future: &ObjectValue) -> anyhow::Result<PendingCall> {
+        let Word::Future(index) = self.read_word(future.pointer)? else {
+            anyhow::bail!("cannot await a value of type `{:?}`", future.ty);
+        };
+        self.uninitialize_word(future.pointer);
+        self.futures[index]
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("future awaited more than once"))
+    }
+
+    fn call_fn(
+        &mut self,
+        caller_frame: &mut StackFrame,
+        fn_name: &ValueId,
+        fn_parameters: &[Parameter],
+        input_values: Vec<ObjectValue>,
+    ) -> anyhow::Result<ObjectValue> {
+        let fn_decl = self.program.fn_named(fn_name)?;
+        let fn_data = fn_decl.binder.instantiate_with(fn_parameters)?;
+
+        if fn_data.inputs.len() != input_values.len() {
+            anyhow::bail!(
+                "function `{fn_name:?}` has {} parameters but {} were provided",
+                fn_data.inputs.len(),
+                input_values.len()
+            );
+        }
+
+        // Alpha-rename as for methods (see `call_method`).
+        self.next_call_id += 1;
+        let call_id = self.next_call_id;
+        let (renamed, _rename_map) = alpha_rename::alpha_rename_fn(&fn_data, call_id);
+
+        let FnDeclBoundData {
+            inputs,
+            output: _,
+            predicates: _,
+            body,
+        } = renamed;
+
+        self.call_body(
+            caller_frame,
+            &format!("{fn_name:?}"),
+            None,
+            vec![],
+            &inputs,
+            &body,
+            input_values,
+        )
+    }
+
+    /// Call the closure `closure` with `input_values`. Its body runs with the variables
+    /// it captured bound to the values in its environment. Calling an owned closure
+    /// consumes it, dropping whatever captured values its body did not give away.
+    fn call_closure(
+        &mut self,
+        caller_frame: &mut StackFrame,
+        closure: ObjectValue,
+        input_values: Vec<ObjectValue>,
+    ) -> anyhow::Result<ObjectValue> {
+        let pointer = if self.is_mut_ref_type(&caller_frame.env, &closure.ty) {
+            self.read_mut_ref(closure.pointer)?
+        } else {
+            closure.pointer
+        };
+        let Word::Closure(index) = self.read_word(pointer)? else {
+            anyhow::bail!("cannot call a value of type `{:?}`", closure.ty);
+        };
+        let ClosureData {
+            inputs,
+            body,
+            captures,
+        } = self.closures[index]
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("closure called after it was dropped"))?;
+
+        if inputs.len() != input_values.len() {
+            anyhow::bail!(
+                "closure has {} parameters but {} were provided",
+                inputs.len(),
+                input_values.len()
+            );
+        }
+
+        // Alpha-rename as for methods (see `call_method`), including the captured
+        // variables, so that each call binds them afresh.
+        self.next_call_id += 1;
+        let call_id = self.next_call_id;
+        let captured_vars: Vec<Var> = captures.iter().map(|(var, _)| var.clone()).collect();
+        let (inputs, body, rename_map) =
+            alpha_rename::alpha_rename_closure(&captured_vars, &inputs, &body, call_id);
+        let captures = captures
+            .into_iter()
+            .map(|(var, value)| (rename_map[&var].clone(), value))
+            .collect();
+
+        let result = self.call_body(
+            caller_frame,
+            "closure",
+            None,
+            captures,
+            &inputs,
+            &MethodBody::Block(body),
+            input_values,
+        )?;
+
+        // An owned closure is consumed by the call (see `consume_value`); otherwise this
+        // only scrubs the temporary holding the receiver.
+        self.drop_value(&caller_frame.env.clone(), &closure)?;
+
+        Ok(result)
+    }
+
+    /// Execute the `body` of a method, function, or closure called from `caller_frame`,
+    /// alpha-renamed for the current call id, with `this` bound to the renamed `self` variable
+    /// (for methods), the `captures` of a closure bound to the values in its environment,
+    /// and the `inputs` bound to `input_values`. `label` names the callee in the trace.
+    fn call_body(
+        &mut self,
+        caller_frame: &mut StackFrame,
+        label: &str,
+        this: Option<(Var, ObjectValue)>,
+        captures: Vec<(Var, ObjectValue)>,
+        inputs: &[LocalVariableDecl],
+        body: &MethodBody,
+        input_values: Vec<ObjectValue>,
+    ) -> anyhow::Result<ObjectValue> {
+        let call_id = self.next_call_id;
+
+        // Extend the caller's env with the callee's renamed bindings.
+        let mut env = caller_frame.env.clone();
 
-        // Collect the method's type bindings (renamed var → type) so we can
-        // inject them into the caller's env after the method returns.
+        // Collect the callee's type bindings (renamed var → type) so we can
+        // inject them into the caller's env after the callee returns.
         // The return type may reference these variables (e.g., `given_from[_N_self]`),
         // and the caller needs them for type proofs.
-        let mut method_type_bindings: Vec<(Var, Ty)> = vec![(self_var.clone(), this_ty)];
+        let mut callee_type_bindings: Vec<(Var, Ty)> = vec![];
+
+        let mut callee_variables = vec![];
+        if let Some((self_var, this)) = this {
+            // Use the receiver's type directly as the type of the renamed self.
+            // The receiver already carries the correct permission from the
+            // access mode used at the call site (e.g., `v.mut` produces
+            // `mut[v] Vec[T]`). Applying `this_decl.perm` on top would
+            // double-wrap (e.g., `mut[v] mut[v] Vec[T]`).
+            let this_ty = this.ty;
+            env = env.push_local_variable(self_var.clone(), this_ty.clone())?;
+            callee_type_bindings.push((self_var.clone(), this_ty));
+            callee_variables.push((self_var, this.pointer));
+        }
+        let mut captured_vars = vec![];
+        for (var, value) in captures {
+            env = env.push_local_variable(var.clone(), value.ty.clone())?;
+            callee_type_bindings.push((var.clone(), value.ty));
+            callee_variables.push((var.clone(), value.pointer));
+            captured_vars.push(var);
+        }
 
-        let mut method_frame = StackFrame {
+        let mut callee_frame = StackFrame {
             env,
             variables: Vec::new(),
         };
-        method_frame.insert_variable(self_var, this.pointer);
+        for (var, pointer) in callee_variables {
+            callee_frame.insert_variable(var, pointer);
+        }
         for (input, input_value) in inputs.iter().zip(input_values) {
             let var = Var::Id(input.name.clone());
             let input_ty = input_value.ty.clone();
-            method_frame.env = method_frame
+            callee_frame.env = callee_frame
                 .env
                 .push_local_variable(var.clone(), input_value.ty)?;
-            method_frame.insert_variable(var.clone(), input_value.pointer);
-            method_type_bindings.push((var, input_ty));
+            callee_frame.insert_variable(var.clone(), input_value.pointer);
+            callee_type_bindings.push((var, input_ty));
         }
 
-        self.trace(format_args!("enter {class_name:?}.{method_id:?}"));
+        self.trace(format_args!("enter {label}"));
         self.indent += 1;
 
-        let result: anyhow::Result<ObjectValue> = match &body {
-            crate::grammar::MethodBody::Trusted => anyhow::bail!(
-                "method `{method_id:?}` of class `{class_name:?}` is trusted and cannot be called by the interpreter",
-            ),
-            crate::grammar::MethodBody::Block(block) => {
-                let result_tv = match self.eval_block(&mut method_frame, block)? {
+        let result: anyhow::Result<ObjectValue> = match body {
+            MethodBody::Trusted => {
+                anyhow::bail!("`{label}` is trusted and cannot be called by the interpreter")
+            }
+            MethodBody::Block(block) => {
+                let result_tv = match self.eval_block(&mut callee_frame, block)? {
                     Outcome::Value(tv) => tv,
                     Outcome::Return(tv) => tv,
                     Outcome::Break => anyhow::bail!("break outside of loop"),
                 };
-                // Free any variables remaining in the method's stack frame
+                // Free any variables remaining in the callee's stack frame
                 // (end-of-scope cleanup). With block-scoped drops, only
-                // method parameters remain here.
-                let env = &method_frame.env;
-                for (var, ptr) in &method_frame.variables {
+                // the parameters remain here. A method consumes its `self`.
+                let env = &callee_frame.env;
+                for (var, ptr) in &callee_frame.variables {
+                    // The captured values stay in the closure's environment.
+                    if captured_vars.contains(var) {
+                        continue;
+                    }
                     let ty = env.var_ty(var)?.clone();
                     let tv = ObjectValue { pointer: *ptr, ty };
-                    self.drop_value(env, &tv)?;
+                    if *var == Var::This {
+                        self.consume_value(env, &tv)?;
+                    } else {
+                        self.drop_value(env, &tv)?;
+                    }
                 }
 
                 Ok(result_tv)
@@ -1926,12 +2823,12 @@ impl<'a> Interpreter<'a> {
 
         let result_tv = result?;
 
-        // Inject the method's type bindings into the caller's env.
-        // The return type may reference method-scope variables
+        // Inject the callee's type bindings into the caller's env.
+        // The return type may reference callee-scope variables
         // (e.g., `given_from[_N_self]`), and the caller needs these
         // bindings for type proofs (is_owned, is_copy, etc.).
         // Names are globally unique (monotonic call_id), so no collisions.
-        for (var, ty) in method_type_bindings {
+        for (var, ty) in callee_type_bindings {
             caller_frame.env = caller_frame
                 .env
                 .push_local_variable(var.clone(), ty)
@@ -1941,9 +2838,7 @@ impl<'a> Interpreter<'a> {
         let result_display = self
             .display_value(&caller_frame.env, &result_tv)
             .unwrap_or_else(|e| format!("<error: {e}>"));
-        self.trace(format_args!(
-            "exit {class_name:?}.{method_id:?} => {result_display}"
-        ));
+        self.trace(format_args!("exit {label} => {result_display}"));
 
         Ok(result_tv)
     }
@@ -1952,16 +2847,27 @@ impl<'a> Interpreter<'a> {
     // Evaluation
     // ---------------------------------------------------------------
 
-    /// Run a program by instantiating `Main()` and calling `main`.
+    /// Run a program by calling the top-level function `main()` if there is one,
+    /// and otherwise by instantiating `Main()` and calling `main`.
+    /// Tasks spawned by the program run until they finish as well.
     pub fn interpret(&mut self) -> anyhow::Result<ObjectValue> {
-        let main_class: ValueId = crate::dada_lang::try_term("Main")?;
-        let main_method: MethodId = crate::dada_lang::try_term("main")?;
+        self.run_tasks(Self::run_main)
+    }
+
+    fn run_main(&mut self) -> anyhow::Result<ObjectValue> {
+        let main_fn: ValueId = crate::dada_lang::try_term("main")?;
         let env = self.base_env();
-        let object = self.instantiate_class(&env, &main_class, &[], &[])?;
         let mut root_frame = StackFrame {
             env,
             variables: Vec::new(),
         };
+        if self.program.fn_named(&main_fn).is_ok() {
+            return self.call_fn(&mut root_frame, &main_fn, &[], vec![]);
+        }
+
+        let main_class: ValueId = crate::dada_lang::try_term("Main")?;
+        let main_method: MethodId = crate::dada_lang::try_term("main")?;
+        let object = self.instantiate_class(&root_frame.env, &main_class, &[], &[])?;
         self.call_method(
             &mut root_frame,
             &main_class,
@@ -1978,7 +2884,7 @@ impl<'a> Interpreter<'a> {
         stack_frame: &mut StackFrame,
         block: &crate::grammar::Block,
     ) -> anyhow::Result<Outcome> {
-        let crate::grammar::Block { statements } = block;
+        let crate::grammar::Block { statements, .. } = block;
 
         // Snapshot the current variable count so we can drop block-scoped vars on exit.
         let vars_before = stack_frame.variables.len();
@@ -2024,12 +2930,19 @@ impl<'a> Interpreter<'a> {
         stack_frame: &mut StackFrame,
         statement: &crate::grammar::Statement,
     ) -> anyhow::Result<Outcome> {
+        if let Some(remaining_steps) = &mut self.remaining_steps {
+            if *remaining_steps == 0 {
+                return Err(Fault::new(FaultKind::StepLimit, "step limit exceeded"));
+            }
+            *remaining_steps -= 1;
+        }
+
         self.trace(format_args!("{statement:?}"));
 
         match statement {
-            crate::grammar::Statement::Expr(expr) => self.eval_expr(stack_frame, expr),
+            crate::grammar::Statement::Expr(expr, _) => self.eval_expr(stack_frame, expr),
 
-            crate::grammar::Statement::Let(name, _ascription, expr) => {
+            crate::grammar::Statement::Let(name, _ascription, expr, _) => {
                 let tv = self.eval_expr_value(stack_frame, expr)?;
                 let var = Var::Id(name.clone());
                 let ty = tv.ty.clone();
@@ -2048,8 +2961,9 @@ impl<'a> Interpreter<'a> {
                 Ok(Outcome::Value(self.unit_value()))
             }
 
-            crate::grammar::Statement::Reassign(place, expr) => {
+            crate::grammar::Statement::Reassign(place, expr, _) => {
                 let tv = self.eval_expr_value(stack_frame, expr)?;
+                self.access_field(stack_frame, place, FieldAccess::Write)?;
                 let env = &stack_frame.env;
 
                 if let Some((owner_place, last_projection)) = place.owner_field() {
@@ -2107,7 +3021,7 @@ impl<'a> Interpreter<'a> {
                 Ok(Outcome::Value(self.unit_value()))
             }
 
-            crate::grammar::Statement::Loop(body) => loop {
+            crate::grammar::Statement::Loop(body, _) => loop {
                 match self.eval_block(stack_frame, body)? {
                     Outcome::Value(tv) => {
                         self.drop_value(&stack_frame.env, &tv)?;
@@ -2119,19 +3033,26 @@ impl<'a> Interpreter<'a> {
                 }
             },
 
-            crate::grammar::Statement::Break => Ok(Outcome::Break),
+            crate::grammar::Statement::Break(_) => Ok(Outcome::Break),
 
-            crate::grammar::Statement::Return(expr) => {
+            crate::grammar::Statement::Return(expr, _) => {
                 let tv = self.eval_expr_value(stack_frame, expr)?;
                 Ok(Outcome::Return(tv))
             }
 
-            crate::grammar::Statement::Print(expr) => {
+            crate::grammar::Statement::Print(expr, _) => {
                 let tv = self.eval_expr_value(stack_frame, expr)?;
-                let text = self.display_value(&stack_frame.env, &tv)?;
+                // Strings and chars print as their text; other values as they are displayed.
+                let text = match self.named_ty(&tv.ty).name {
+                    TypeName::String => self.read_string(&stack_frame.env, &tv)?,
+                    TypeName::Char => self.read_char(&stack_frame.env, &tv)?.to_string(),
+                    _ => self.display_value(&stack_frame.env, &tv)?,
+                };
                 self.drop_value(&stack_frame.env, &tv)?;
+                let task = self.scheduler.trace_label();
                 let indent = "  ".repeat(self.indent);
                 self.output.push_str("-----> ");
+                self.output.push_str(&task);
                 self.output.push_str(&indent);
                 self.output.push_str(&text);
                 self.output.push('\n');
@@ -2151,6 +3072,17 @@ impl<'a> Interpreter<'a> {
                 ty: Ty::int(),
             })),
 
+            crate::grammar::Expr::String(literal) => {
+                Ok(Outcome::Value(self.alloc_string(&literal.text())))
+            }
+
+            crate::grammar::Expr::Char(literal) => Ok(Outcome::Value(ObjectValue {
+                pointer: self.alloc_raw(Alloc {
+                    data: vec![Word::Char(literal.value())],
+                }),
+                ty: Ty::char(),
+            })),
+
             crate::grammar::Expr::True => Ok(Outcome::Value(ObjectValue {
                 pointer: self.alloc_int(1),
                 ty: Ty::bool(),
@@ -2161,34 +3093,96 @@ impl<'a> Interpreter<'a> {
                 ty: Ty::bool(),
             })),
 
-            crate::grammar::Expr::BinaryOp(lhs, op, rhs) => {
+            crate::grammar::Expr::Comparison(lhs, ComparisonOp { op }, rhs)
+            | crate::grammar::Expr::Additive(lhs, AdditiveOp { op }, rhs)
+            | crate::grammar::Expr::Multiplicative(lhs, MultiplicativeOp { op }, rhs) => {
                 let l = self.eval_expr_value(stack_frame, lhs)?;
                 let r = self.eval_expr_value(stack_frame, rhs)?;
-                let a = self.into_int_value(&stack_frame.env, &l)?;
-                let b = self.into_int_value(&stack_frame.env, &r)?;
                 use crate::grammar::BinaryOp::*;
-                match op {
-                    Add | Sub => Ok(Outcome::Value(ObjectValue {
-                        pointer: self.alloc_int(match op {
-                            Add => a + b,
-                            Sub => a - b,
-                            _ => unreachable!(),
-                        }),
-                        ty: Ty::int(),
-                    })),
-                    Ge | Le | Eq | Ne => {
+                let env = &stack_frame.env;
+                let (a, b) = match self.named_ty(&l.ty).name {
+                    TypeName::String => {
+                        let a = self.into_string_value(env, &l)?;
+                        let b = self.into_string_value(env, &r)?;
+                        let result = match op {
+                            Add => return Ok(Outcome::Value(self.alloc_string(&(a + &b)))),
+                            Ge => a >= b,
+                            Le => a <= b,
+                            Gt => a > b,
+                            Lt => a < b,
+                            Eq => a == b,
+                            Ne => a != b,
+                            Sub | Mul | Div | Rem => anyhow::bail!("no `{op:?}` on strings"),
+                        };
+                        return Ok(Outcome::Value(self.alloc_bool(result)));
+                    }
+                    // Chars compare by their code points.
+                    TypeName::Char => (
+                        i64::from(u32::from(self.into_char_value(env, &l)?)),
+                        i64::from(u32::from(self.into_char_value(env, &r)?)),
+                    ),
+                    _ => (
+                        self.into_int_value(env, &l)?,
+                        self.into_int_value(env, &r)?,
+                    ),
+                };
+                let result = match op {
+                    Add => a.checked_add(b),
+                    Sub => a.checked_sub(b),
+                    Mul => a.checked_mul(b),
+                    Div | Rem if b == 0 => anyhow::bail!("division by zero: {a} {op:?} {b}"),
+                    Div => a.checked_div(b),
+                    Rem => a.checked_rem(b),
+                    Ge | Le | Gt | Lt | Eq | Ne => {
                         let result = match op {
                             Ge => a >= b,
                             Le => a <= b,
+                            Gt => a > b,
+                            Lt => a < b,
                             Eq => a == b,
                             Ne => a != b,
                             _ => unreachable!(),
                         };
+                        return Ok(Outcome::Value(self.alloc_bool(result)));
+                    }
+                };
+                let Some(result) = result else {
+                    anyhow::bail!("integer overflow: {a} {op:?} {b}")
+                };
+                Ok(Outcome::Value(ObjectValue {
+                    pointer: self.alloc_int(result),
+                    ty: Ty::int(),
+                }))
+            }
+
+            crate::grammar::Expr::Or(lhs, rhs) | crate::grammar::Expr::And(lhs, rhs) => {
+                let l = self.eval_expr_value(stack_frame, lhs)?;
+                let l = self.into_bool_value(&stack_frame.env, &l)?;
+                // `||` is decided by a `true` left operand, `&&` by a `false` one.
+                if l == matches!(expr, crate::grammar::Expr::Or(..)) {
+                    return Ok(Outcome::Value(self.alloc_bool(l)));
+                }
+                self.eval_expr(stack_frame, rhs)
+            }
+
+            crate::grammar::Expr::Unary(op, expr) => {
+                let v = self.eval_expr_value(stack_frame, expr)?;
+                let env = &stack_frame.env;
+                match op {
+                    UnaryOp::Neg => {
+                        let v = self.into_int_value(env, &v)?;
+                        let Some(result) = v.checked_neg() else {
+                            anyhow::bail!("integer overflow: -{v}")
+                        };
                         Ok(Outcome::Value(ObjectValue {
-                            pointer: self.alloc_int(if result { 1 } else { 0 }),
-                            ty: Ty::bool(),
+                            pointer: self.alloc_int(result),
+                            ty: Ty::int(),
                         }))
                     }
+                    UnaryOp::Not => {
+                        let v = self.into_bool_value(env, &v)?;
+                        Ok(Outcome::Value(self.alloc_bool(!v)))
+                    }
                 }
             }
 
@@ -2205,13 +3199,16 @@ impl<'a> Interpreter<'a> {
                 }))
             }
 
-            crate::grammar::Expr::New(class_name, params, field_exprs) => {
-                let field_values: Vec<ObjectValue> = field_exprs
+            crate::grammar::Expr::New(class_name, params, arguments) => {
+                let field_values: Vec<ObjectValue> = arguments
+                    .exprs
                     .iter()
                     .map(|e| self.eval_expr_value(stack_frame, e))
                     .collect::<Result<_, _>>()?;
                 let env = &stack_frame.env;
-                let result = self.instantiate_class(env, class_name, params, &field_values)?;
+                let params =
+                    self.explicit_parameters(env, &Generic::Class(class_name.clone()), params)?;
+                let result = self.instantiate_class(env, class_name, &params, &field_values)?;
                 for fv in &field_values {
                     // Scrub the temp without dropping — ownership moved into the class.
                     self.uninitialize(env, fv)?;
@@ -2219,7 +3216,99 @@ impl<'a> Interpreter<'a> {
                 Ok(Outcome::Value(result))
             }
 
-            crate::grammar::Expr::Place(crate::grammar::PlaceExpr { place, access }) => {
+            crate::grammar::Expr::NewVariant(enum_name, params, variant_name, arguments) => {
+                let field_values: Vec<ObjectValue> = arguments
+                    .exprs
+                    .iter()
+                    .map(|e| self.eval_expr_value(stack_frame, e))
+                    .collect::<Result<_, _>>()?;
+                let env = &stack_frame.env;
+                let params = self.explicit_parameters(
+                    env,
+                    &Generic::Variant(enum_name.clone(), variant_name.clone()),
+                    params,
+                )?;
+                let result =
+                    self.instantiate_variant(env, enum_name, &params, variant_name, &field_values)?;
+                for fv in &field_values {
+                    // Scrub the temp without dropping — ownership moved into the enum.
+                    self.uninitialize(env, fv)?;
+                }
+                Ok(Outcome::Value(result))
+            }
+
+            crate::grammar::Expr::Match(scrutinee, arms) => {
+                let scrutinee_tv = self.eval_expr_value(stack_frame, scrutinee)?;
+                let env = &stack_frame.env;
+                let scrutinee_data =
+                    self.object_value_to_data(env, &scrutinee_tv, ObjectPerms::Given)?;
+                let NamedTy {
+                    name: TypeName::Id(enum_name),
+                    parameters,
+                } = &scrutinee_data.named_ty
+                else {
+                    anyhow::bail!("match on non-enum type: {:?}", scrutinee_data.named_ty);
+                };
+                let enum_decl = self.program.enum_named(enum_name)?;
+                let enum_data = enum_decl.binder.instantiate_with(parameters)?;
+
+                // Select the arm for the variant named by the discriminant.
+                let discriminant = self.read_int(scrutinee_data.pointer)?;
+                let Some(variant) = usize::try_from(discriminant)
+                    .ok()
+                    .and_then(|d| enum_data.variants.get(d))
+                else {
+                    anyhow::bail!("invalid discriminant {discriminant} for enum `{enum_name:?}`");
+                };
+                let arm = arms
+                    .iter()
+                    .find(|arm| arm.variant == variant.name)
+                    .ok_or_else(|| anyhow::anyhow!("no arm for variant `{:?}`", variant.name))?;
+
+                // Give each field of the variant, with the permissions of the scrutinee
+                // applied: a given scrutinee moves its fields out, a ref or mut scrutinee
+                // produces refs or mut-refs to them (cf. `array_give_element`).
+                let PermTy(perm, _) = scrutinee_tv.ty.upcast();
+                let mut binding_values = vec![];
+                let mut offset = 1;
+                for field in &variant.fields {
+                    let field_value = ObjectValue {
+                        pointer: scrutinee_data.pointer + offset,
+                        ty: field.ty.clone(),
+                    };
+                    let field_data =
+                        self.object_value_to_data(env, &field_value, scrutinee_data.operms)?;
+                    let binding_ty: Ty = PermTy(perm.clone(), field.ty.clone()).upcast();
+                    binding_values.push(self.give_place(env, &field_data, &binding_ty)?);
+                    offset += self.size_of(env, &field.ty)?;
+                }
+
+                // The bindings are scoped to the arm.
+                let vars_before = stack_frame.variables.len();
+                for (name, value) in arm.bindings.iter().zip(binding_values) {
+                    let var = Var::Id(name.clone());
+                    let display = self
+                        .display_value(&stack_frame.env, &value)
+                        .unwrap_or_else(|e| format!("<error: {e}>"));
+                    self.trace(format_args!("{var:?} = {display}"));
+                    stack_frame.env = stack_frame.env.push_local_variable(var.clone(), value.ty)?;
+                    stack_frame.insert_variable(var, value.pointer);
+                }
+
+                let outcome = self.eval_block(stack_frame, &arm.body)?;
+                self.drop_block_scoped_vars(stack_frame, vars_before)?;
+                // Matching consumes the scrutinee, even a `tracked enum` whose
+                // variant has no fields to move out.
+                self.consume_value(&stack_frame.env, &scrutinee_tv)?;
+                Ok(outcome)
+            }
+
+            crate::grammar::Expr::Place(crate::grammar::PlaceExpr { place, access, .. }) => {
+                let field_access = match access {
+                    crate::grammar::Access::Mt | crate::grammar::Access::Drop => FieldAccess::Write,
+                    crate::grammar::Access::Gv | crate::grammar::Access::Rf => FieldAccess::Read,
+                };
+                self.access_field(stack_frame, place, field_access)?;
                 let resolved = self.resolve_place_to_object_data(stack_frame, place)?;
                 let env = &stack_frame.env;
                 let place_ty = stack_frame.env.place_ty(place)?;
@@ -2254,7 +3343,38 @@ impl<'a> Interpreter<'a> {
                 }))
             }
 
-            crate::grammar::Expr::Call(receiver, method_name, method_params, args) => {
+            crate::grammar::Expr::Await(future) => {
+                let future_tv = self.eval_expr_value(stack_frame, future)?;
+                let call = self.take_pending_call(&future_tv)?;
+                let PendingCall {
+                    class_name,
+                    class_parameters,
+                    method_id,
+                    method_parameters,
+                    this,
+                    input_values,
+                } = call;
+                Ok(Outcome::Value(self.call_method(
+                    stack_frame,
+                    &class_name,
+                    &class_parameters,
+                    &method_id,
+                    &method_parameters,
+                    this,
+                    input_values,
+                )?))
+            }
+
+            crate::grammar::Expr::Spawn(future) => {
+                let future_tv = self.eval_expr_value(stack_frame, future)?;
+                let call = self.take_pending_call(&future_tv)?;
+                let task = self.scheduler.spawn(call);
+                self.trace(format_args!("spawn task {task}"));
+                Ok(Outcome::Value(self.unit_value()))
+            }
+
+            crate::grammar::Expr::Call(receiver, method_name, method_params, arguments) => {
+                let args = &arguments.exprs;
                 let receiver_tv = self.eval_expr_value(stack_frame, receiver)?;
                 let inner_ty = receiver_tv.ty.strip_perm();
                 let (class_name, class_parameters) = match &inner_ty {
@@ -2262,23 +3382,136 @@ impl<'a> Interpreter<'a> {
                         name: TypeName::Id(id),
                         parameters,
                     }) => (id.clone(), parameters.clone()),
+                    Ty::NamedTy(NamedTy {
+                        name: TypeName::String,
+                        ..
+                    }) => {
+                        anyhow::ensure!(
+                            method_name.is_len() && args.is_empty(),
+                            "strings have no method `{method_name:?}`"
+                        );
+                        let text = self.into_string_value(&stack_frame.env, &receiver_tv)?;
+                        let len = text.chars().count() as i64;
+                        return Ok(Outcome::Value(ObjectValue {
+                            pointer: self.alloc_int(len),
+                            ty: Ty::int(),
+                        }));
+                    }
+                    Ty::NamedTy(NamedTy {
+                        name: TypeName::Closure(_),
+                        ..
+                    }) => {
+                        anyhow::ensure!(
+                            method_name.is_call(),
+                            "closures have no method `{method_name:?}`"
+                        );
+                        let arg_vals: Vec<ObjectValue> = args
+                            .iter()
+                            .map(|a| self.eval_expr_value(stack_frame, a))
+                            .collect::<Result<_, _>>()?;
+                        return Ok(Outcome::Value(self.call_closure(
+                            stack_frame,
+                            receiver_tv,
+                            arg_vals,
+                        )?));
+                    }
                     _ => anyhow::bail!("cannot call method on non-class type: {inner_ty:?}"),
                 };
                 let arg_vals: Vec<ObjectValue> = args
                     .iter()
                     .map(|a| self.eval_expr_value(stack_frame, a))
                     .collect::<Result<_, _>>()?;
+                let method_params = self.explicit_parameters(
+                    &stack_frame.env,
+                    &Generic::Method(receiver_tv.ty.clone(), method_name.clone()),
+                    method_params,
+                )?;
+                let method_decl =
+                    self.find_method_decl(&class_name, &class_parameters, method_name)?;
+                if method_decl.is_async == Async::Yes {
+                    // The body runs once the future is awaited (see `Expr::Await`).
+                    return Ok(Outcome::Value(self.alloc_future(PendingCall {
+                        class_name,
+                        class_parameters,
+                        method_id: method_name.clone(),
+                        method_parameters: method_params,
+                        this: receiver_tv,
+                        input_values: arg_vals,
+                    })));
+                }
                 Ok(Outcome::Value(self.call_method(
                     stack_frame,
                     &class_name,
                     &class_parameters,
                     method_name,
-                    method_params,
+                    &method_params,
                     receiver_tv,
                     arg_vals,
                 )?))
             }
 
+            crate::grammar::Expr::Closure(inputs, _output, body) => {
+                // Evaluate each capture as the place expression it is used with, and move
+                // the resulting values into one allocation: the closure's environment.
+                let mut data = vec![];
+                let mut captured_values = vec![];
+                for (var, access) in crate::grammar::Expr::closure_captures(inputs, body) {
+                    let place_expr = crate::grammar::Expr::Place(crate::grammar::PlaceExpr::new(
+                        var.clone(),
+                        access,
+                        crate::grammar::Span::default(),
+                    ));
+                    let tv = self.eval_expr_value(stack_frame, &place_expr)?;
+                    let size = self.size_of(&stack_frame.env, &tv.ty)?;
+                    captured_values.push((var, data.len(), tv.ty.clone()));
+                    data.extend(self.read_words(tv.pointer, size)?);
+                    // Scrub the temp without dropping — ownership moved into the environment.
+                    self.uninitialize(&stack_frame.env, &tv)?;
+                }
+                let env_pointer = self.alloc_raw(Alloc { data });
+                let captures = captured_values
+                    .into_iter()
+                    .map(|(var, offset, ty)| {
+                        let pointer = env_pointer + offset;
+                        (var, ObjectValue { pointer, ty })
+                    })
+                    .collect();
+
+                let index = self.closures.len();
+                self.closures.push(Some(ClosureData {
+                    inputs: inputs.clone(),
+                    body: body.clone(),
+                    captures,
+                }));
+                Ok(Outcome::Value(ObjectValue {
+                    pointer: self.alloc_raw(Alloc {
+                        data: vec![Word::Closure(index)],
+                    }),
+                    // The type system tracks the inputs, output, and captures of a closure;
+                    // at runtime it is just a handle to its code and environment.
+                    ty: NamedTy::closure(CallKind::Once, vec![], Ty::unit(), vec![]).upcast(),
+                }))
+            }
+
+            crate::grammar::Expr::CallFn(fn_name, fn_params, arguments) => {
+                let args = &arguments.exprs;
+                let arg_vals: Vec<ObjectValue> = args
+                    .iter()
+                    .map(|a| self.eval_expr_value(stack_frame, a))
+                    .collect::<Result<_, _>>()?;
+                let fn_params = self.explicit_parameters(
+                    &stack_frame.env,
+                    &Generic::Fn(fn_name.clone()),
+                    fn_params,
+                )?;
+                Ok(Outcome::Value(self.call_fn(
+                    stack_frame,
+                    fn_name,
+                    &fn_params,
+                    arg_vals,
+                )?))
+            }
+
             crate::grammar::Expr::If(cond, if_true, if_false) => {
                 let cond_tv = self.eval_expr_value(stack_frame, cond)?;
                 let b = self.into_bool_value(&stack_frame.env, &cond_tv)?;
@@ -2462,7 +3695,7 @@ impl<'a> Interpreter<'a> {
                 }))
             }
 
-            crate::grammar::Expr::Panic => anyhow::bail!("panic!"),
+            crate::grammar::Expr::Panic => Err(Fault::new(FaultKind::Panic, "panic!")),
 
             crate::grammar::Expr::Clear(var) => {
                 let var_key = Var::Id(var.clone());
@@ -2571,6 +3804,9 @@ fn fmt_word(word: &Word, hex_width: usize) -> String {
         Word::Flags(f) => format!("Flags({f:?})"),
         Word::RefCount(n) => format!("RefCount({n})"),
         Word::Capacity(n) => format!("Capacity({n})"),
+        Word::Char(c) => format!("Char({c:?})"),
+        Word::Future(n) => format!("Future({n})"),
+        Word::Closure(n) => format!("Closure({n})"),
         Word::Pointer(p) => {
             if p.offset == 0 {
                 format!("Pointer(0x{:0>width$x})", p.index, width = hex_width)
@@ -0,0 +1,489 @@
+//! Tasks started with `spawn(..)` and the scheduler that interleaves them.
+//!
+//! A program that never spawns runs `main` on the calling thread alone. Otherwise,
+//! each task runs on a thread of its own, but only one task runs at a time:
+//! the interpreter is handed from task to task at *switch points*, which are the
+//! accesses to the fields of objects. At each switch point, the scheduler picks the
+//! task that runs next with a pseudo-random generator, so a given seed (see
+//! [`Interpreter::seed_scheduler`]) always produces the same interleaving.
+//!
+//! A spawned task can only capture shared data, and the type system only permits
+//! writes to shared data for `atomic` fields. To check this, the scheduler records
+//! the accesses of each task to the fields of objects, and faults on a *data race*:
+//! a write to a non-atomic field that another task may access concurrently, i.e.,
+//! without one of the accesses happening before the other. The only ordering between
+//! tasks is that everything a task does before spawning another happens before
+//! everything the new task does, which we track with vector clocks.
+
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::Scope;
+
+use crate::grammar::Place;
+use crate::rng::Rng;
+
+use super::{Interpreter, ObjectValue, PendingCall, Pointer, StackFrame};
+
+/// The tasks of a running program: task 0 runs `main`, the others were spawned.
+pub(super) struct Scheduler<'a> {
+    tasks: Vec<Task>,
+
+    /// The task that is running.
+    current: usize,
+
+    /// Picks the task to run at each switch point.
+    rng: Rng,
+
+    /// Hands the interpreter between the threads running the tasks, while the program runs.
+    baton: Option<Arc<Baton<'a>>>,
+
+    /// The first fault of any task, which cancels the others.
+    fault: Option<anyhow::Error>,
+
+    /// The accesses to each word of the fields accessed by the tasks.
+    accesses: HashMap<Pointer, WordAccesses>,
+}
+
+struct Task {
+    /// The call that the task runs, until it starts.
+    call: Option<PendingCall>,
+
+    status: TaskStatus,
+
+    /// For each task, the last of its epochs that happens before the current epoch
+    /// of this task (including, for this task itself, its current epoch).
+    /// A task starts a new epoch whenever it spawns a task.
+    clock: Vec<usize>,
+
+    /// The indentation of the trace of this task, saved while other tasks run.
+    indent: usize,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum TaskStatus {
+    Pending,
+    Running,
+    Finished,
+}
+
+/// An access by `task` during its epoch `epoch` (see [`Task::clock`]).
+#[derive(Copy, Clone)]
+struct Access {
+    task: usize,
+    epoch: usize,
+}
+
+/// The accesses to a word that are relevant to detect data races.
+#[derive(Default)]
+struct WordAccesses {
+    /// The last write.
+    write: Option<Access>,
+
+    /// The last read of each task since the last write.
+    reads: Vec<Access>,
+}
+
+/// How a field is accessed (see [`Interpreter::access_field`]).
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub(super) enum FieldAccess {
+    Read,
+    Write,
+}
+
+impl Scheduler<'_> {
+    pub(super) fn new() -> Self {
+        Self {
+            tasks: vec![],
+            current: 0,
+            rng: Rng::new(0),
+            baton: None,
+            fault: None,
+            accesses: HashMap::new(),
+        }
+    }
+
+    pub(super) fn seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// Add a task that runs `call`, returning its index.
+    pub(super) fn spawn(&mut self, call: PendingCall) -> usize {
+        let task = self.tasks.len();
+        let parent = self.current;
+
+        // Everything the parent did so far happens before the new task runs,
+        // but what it does from now on does not.
+        let mut clock = self.tasks[parent].clock.clone();
+        clock.resize(task + 1, 0);
+        clock[task] = 1;
+        self.tasks[parent].clock[parent] += 1;
+
+        self.tasks.push(Task {
+            call: Some(call),
+            status: TaskStatus::Pending,
+            clock,
+            indent: 0,
+        });
+        task
+    }
+
+    /// The prefix of the trace of the current task: none for `main`.
+    pub(super) fn trace_label(&self) -> String {
+        if self.current == 0 {
+            String::new()
+        } else {
+            format!("[task {}] ", self.current)
+        }
+    }
+
+    /// Pick the task to run next among those that are not finished,
+    /// except that no new task starts once a task has faulted.
+    fn pick_task(&mut self) -> Option<usize> {
+        let candidates: Vec<usize> = (0..self.tasks.len())
+            .filter(|&task| match self.tasks[task].status {
+                TaskStatus::Running => true,
+                TaskStatus::Pending => self.fault.is_none(),
+                TaskStatus::Finished => false,
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(self.rng.pick(&candidates))
+    }
+
+    /// Record the fault of the current task, unless another task faulted first.
+    fn record_fault(&mut self, fault: anyhow::Error) {
+        if self.fault.is_none() {
+            self.fault = Some(fault);
+        }
+    }
+
+    /// Record that the current task accesses the `size` words at `pointer`, which hold
+    /// the field `place`, faulting if that races with the access of another task.
+    fn record_access(
+        &mut self,
+        place: &Place,
+        pointer: Pointer,
+        size: usize,
+        access: FieldAccess,
+    ) -> anyhow::Result<()> {
+        let task = self.current;
+        let clock = &self.tasks[task].clock;
+        let now = Access {
+            task,
+            epoch: clock[task],
+        };
+        let happens_before = |a: &Access| clock.get(a.task).is_some_and(|&e| e >= a.epoch);
+        let verb = match access {
+            FieldAccess::Read => "reads",
+            FieldAccess::Write => "writes",
+        };
+
+        for offset in 0..size {
+            let word = self.accesses.entry(pointer + offset).or_default();
+            if let Some(write) = word.write.filter(|write| !happens_before(write)) {
+                anyhow::bail!(
+                    "data race on `{place:?}`: task {task} {verb} it while task {} may write it concurrently",
+                    write.task
+                );
+            }
+            match access {
+                FieldAccess::Read => {
+                    word.reads.retain(|read| read.task != task);
+                    word.reads.push(now);
+                }
+                FieldAccess::Write => {
+                    if let Some(read) = word.reads.iter().find(|read| !happens_before(read)) {
+                        anyhow::bail!(
+                            "data race on `{place:?}`: task {task} {verb} it while task {} may read it concurrently",
+                            read.task
+                        );
+                    }
+                    word.write = Some(now);
+                    word.reads.clear();
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hands the interpreter to the task (or the thread that starts tasks) whose turn it is.
+struct Baton<'a> {
+    state: Mutex<(Turn, Option<Interpreter<'a>>)>,
+    turn_changed: Condvar,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Turn {
+    /// The task runs.
+    Task(usize),
+
+    /// The task must be started on a new thread, after which it runs.
+    Start(usize),
+
+    /// All tasks are finished.
+    Finished,
+}
+
+impl<'a> Baton<'a> {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new((Turn::Task(0), None)),
+            turn_changed: Condvar::new(),
+        }
+    }
+
+    /// Lock the state. Tasks catch their panics (see [`catch_panic`]), so a poisoned
+    /// lock still holds a consistent state.
+    fn lock(&self) -> MutexGuard<'_, (Turn, Option<Interpreter<'a>>)> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Make it the turn `turn`, handing over `interpreter`.
+    fn hand_over(&self, turn: Turn, interpreter: Interpreter<'a>) {
+        let mut state = self.lock();
+        *state = (turn, Some(interpreter));
+        self.turn_changed.notify_all();
+    }
+
+    /// Wait for the turn `turn` and take the interpreter.
+    fn wait_for_turn(&self, turn: Turn) -> Interpreter<'a> {
+        let mut state = self
+            .turn_changed
+            .wait_while(self.lock(), |(t, _)| *t != turn)
+            .unwrap_or_else(PoisonError::into_inner);
+        state
+            .1
+            .take()
+            .expect("the interpreter is handed over with the turn")
+    }
+
+    /// Wait until a task is to be started (returning it) or all tasks are finished.
+    fn wait_for_start(&self) -> Option<usize> {
+        let state = self
+            .turn_changed
+            .wait_while(self.lock(), |(t, _)| matches!(t, Turn::Task(_)))
+            .unwrap_or_else(PoisonError::into_inner);
+        match state.0 {
+            Turn::Start(task) => Some(task),
+            Turn::Finished => None,
+            Turn::Task(_) => unreachable!(),
+        }
+    }
+
+    /// Make it the turn of `task`, whose thread has been started.
+    fn started(&self, task: usize) {
+        let mut state = self.lock();
+        state.0 = Turn::Task(task);
+        self.turn_changed.notify_all();
+    }
+
+    /// Stop waiting for tasks to start.
+    fn finish(&self) {
+        let mut state = self.lock();
+        state.0 = Turn::Finished;
+        self.turn_changed.notify_all();
+    }
+}
+
+/// Start a thread for each task that is to be started, until all tasks are finished.
+fn start_tasks<'scope, 'a: 'scope>(baton: &Arc<Baton<'a>>, scope: &'scope Scope<'scope, '_>) {
+    while let Some(task) = baton.wait_for_start() {
+        let task_baton = baton.clone();
+        scope.spawn(move || {
+            let mut interpreter = task_baton.wait_for_turn(Turn::Task(task));
+            let result = catch_panic(|| interpreter.run_task(task));
+            interpreter.finish_task(&task_baton, result);
+        });
+        baton.started(task);
+    }
+}
+
+/// Run `f`, turning a panic into a fault. The interpreter must still be handed on
+/// when a task panics, or the other tasks would wait for their turn forever.
+fn catch_panic<T>(f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        anyhow::bail!("interpreter panicked: {message}")
+    })
+}
+
+impl<'a> Interpreter<'a> {
+    /// Run `main` as task 0 on this thread, and the tasks that it spawns on threads
+    /// of their own, until all of them are finished. Returns the result of `main`,
+    /// or the first fault of any task.
+    ///
+    /// If the program never spawns, `main` is the only task: no thread is started
+    /// and there are no switch points.
+    pub(super) fn run_tasks(
+        &mut self,
+        main: impl FnOnce(&mut Self) -> anyhow::Result<ObjectValue>,
+    ) -> anyhow::Result<ObjectValue> {
+        self.scheduler.tasks = vec![Task {
+            call: None,
+            status: TaskStatus::Running,
+            clock: vec![1],
+            indent: 0,
+        }];
+        self.scheduler.current = 0;
+        if !self.program.spawns() {
+            return catch_panic(|| main(self));
+        }
+
+        let baton = Arc::new(Baton::new());
+        self.scheduler.baton = Some(baton.clone());
+
+        let result = std::thread::scope(|scope| {
+            scope.spawn(|| start_tasks(&baton, scope));
+            let result = catch_panic(|| main(self));
+            self.finish_main_task(&baton, result)
+        });
+
+        self.scheduler.baton = None;
+        result
+    }
+
+    /// Run the call of the spawned task `task`, dropping its result.
+    fn run_task(&mut self, task: usize) -> anyhow::Result<()> {
+        let PendingCall {
+            class_name,
+            class_parameters,
+            method_id,
+            method_parameters,
+            this,
+            input_values,
+        } = self.scheduler.tasks[task]
+            .call
+            .take()
+            .expect("a task is started once");
+        let mut root_frame = StackFrame {
+            env: self.base_env(),
+            variables: Vec::new(),
+        };
+        let result = self.call_method(
+            &mut root_frame,
+            &class_name,
+            &class_parameters,
+            &method_id,
+            &method_parameters,
+            this,
+            input_values,
+        )?;
+        self.drop_value(&root_frame.env, &result)
+    }
+
+    /// Finish the current (spawned) task, handing the interpreter to the next one.
+    fn finish_task(mut self, baton: &Baton<'a>, result: anyhow::Result<()>) {
+        if let Err(fault) = result {
+            self.scheduler.record_fault(fault);
+        }
+        let current = self.scheduler.current;
+        self.scheduler.tasks[current].status = TaskStatus::Finished;
+        match self.scheduler.pick_task() {
+            Some(next) => self.hand_off(baton, next),
+            None => baton.hand_over(Turn::Finished, self),
+        }
+    }
+
+    /// Finish `main`, waiting for the other tasks to finish as well.
+    fn finish_main_task(
+        &mut self,
+        baton: &Baton<'a>,
+        result: anyhow::Result<ObjectValue>,
+    ) -> anyhow::Result<ObjectValue> {
+        let value = match result {
+            Ok(value) => Some(value),
+            Err(fault) => {
+                self.scheduler.record_fault(fault);
+                None
+            }
+        };
+        self.scheduler.tasks[0].status = TaskStatus::Finished;
+        if let Some(next) = self.scheduler.pick_task() {
+            let placeholder = Interpreter::new(self.program);
+            std::mem::replace(self, placeholder).hand_off(baton, next);
+            *self = baton.wait_for_turn(Turn::Finished);
+        }
+        baton.finish();
+
+        match self.scheduler.fault.take() {
+            Some(fault) => Err(fault),
+            None => Ok(value.expect("main returned a value")),
+        }
+    }
+
+    /// Hand the interpreter to the task `next`, starting it if it is not running yet.
+    fn hand_off(mut self, baton: &Baton<'a>, next: usize) {
+        let current = self.scheduler.current;
+        self.scheduler.tasks[current].indent = self.indent;
+        self.scheduler.current = next;
+        self.indent = self.scheduler.tasks[next].indent;
+        let turn = match self.scheduler.tasks[next].status {
+            TaskStatus::Pending => {
+                self.scheduler.tasks[next].status = TaskStatus::Running;
+                Turn::Start(next)
+            }
+            TaskStatus::Running => Turn::Task(next),
+            TaskStatus::Finished => unreachable!("finished tasks are not picked"),
+        };
+        baton.hand_over(turn, self);
+    }
+
+    /// A switch point, where the scheduler may let another task run for a while.
+    /// Fails if another task has faulted in the meantime, cancelling this one.
+    fn switch_tasks(&mut self) -> anyhow::Result<()> {
+        let Some(baton) = self.scheduler.baton.clone() else {
+            return Ok(());
+        };
+        let current = self.scheduler.current;
+        if self.scheduler.tasks.len() > 1 {
+            let next = self
+                .scheduler
+                .pick_task()
+                .expect("the current task is running");
+            if next != current {
+                let placeholder = Interpreter::new(self.program);
+                std::mem::replace(self, placeholder).hand_off(&baton, next);
+                *self = baton.wait_for_turn(Turn::Task(current));
+            }
+        }
+        anyhow::ensure!(
+            self.scheduler.fault.is_none(),
+            "task {current} cancelled because another task faulted"
+        );
+        Ok(())
+    }
+
+    /// Called before the current task accesses `place`. If `place` is a field, this
+    /// is a switch point, and the access is checked for a data race (unless the field
+    /// is atomic).
+    pub(super) fn access_field(
+        &mut self,
+        stack_frame: &StackFrame,
+        place: &Place,
+        access: FieldAccess,
+    ) -> anyhow::Result<()> {
+        let Some((owner_place, last_projection)) = place.owner_field() else {
+            return Ok(());
+        };
+        self.switch_tasks()?;
+
+        // Until a task is spawned, there is nothing to race with.
+        let env = &stack_frame.env;
+        if self.scheduler.tasks.len() <= 1 || env.is_atomic_field(place)? {
+            return Ok(());
+        }
+        let owner_object_data = self.resolve_place_to_object_data(stack_frame, &owner_place)?;
+        let field_value = self.resolve_projection(env, &owner_object_data, &last_projection)?;
+        let size = self.size_of(env, &field_value.ty)?;
+        self.scheduler
+            .record_access(place, field_value.pointer, size, access)
+    }
+}
@@ -1,12 +1,22 @@
 mod array;
+mod async_fns;
 mod basics;
 mod block_scoped_drops;
+mod boxed_class;
+mod closures;
 mod copy_move;
 mod drop_body;
+mod enums;
+mod free_fns;
 mod generics;
 mod mdbook;
 mod method_calls;
+mod operators;
 mod place_ops;
 mod share;
 mod size_of;
+mod strings;
+mod tasks;
+mod traits;
+mod tracked;
 mod vector;
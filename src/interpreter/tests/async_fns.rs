@@ -0,0 +1,158 @@
+// Tests for `async fn` methods, whose calls run when their futures are awaited.
+
+/// The body of an `async fn` runs when its future is awaited, not when it is called.
+#[test]
+fn await_runs_call() {
+    crate::assert_interpret!(
+        {
+            class Counter {
+                count: Int;
+
+                async fn get(ref self) -> Int {
+                    self.count.give;
+                }
+            }
+            class Main {
+                async fn main(given self) -> Int {
+                    let c = new Counter(22);
+                    let f = c.ref.get();
+                    f.give.await;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (22) ;
+            Output: Trace:   _1_c = Counter { count: 22 }
+            Output: Trace:   let _1_f = _1_c . ref . get () ;
+            Output: Trace:   _1_f = Future { Counter.get }
+            Output: Trace:   _1_f . give . await ;
+            Output: Trace:   enter Counter.get
+            Output: Trace:     _2_self . count . give ;
+            Output: Trace:   exit Counter.get => 22
+            Output: Trace: exit Main.main => 22
+            Result: Ok: 22
+            Alloc 0x09: [Int(22)]"#]]
+    );
+}
+
+/// Futures run to completion one at a time, in the order they are awaited.
+#[test]
+fn futures_run_in_await_order() {
+    crate::assert_interpret!(
+        {
+            class Counter {
+                count: Int;
+
+                async fn get(ref self) -> Int {
+                    self.count.give;
+                }
+            }
+            class Main {
+                async fn main(given self) -> Int {
+                    let a = new Counter(1);
+                    let b = new Counter(2);
+                    let fa = a.ref.get();
+                    let fb = b.ref.get();
+                    fb.give.await;
+                    fa.give.await;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_a = new Counter (1) ;
+            Output: Trace:   _1_a = Counter { count: 1 }
+            Output: Trace:   let _1_b = new Counter (2) ;
+            Output: Trace:   _1_b = Counter { count: 2 }
+            Output: Trace:   let _1_fa = _1_a . ref . get () ;
+            Output: Trace:   _1_fa = Future { Counter.get }
+            Output: Trace:   let _1_fb = _1_b . ref . get () ;
+            Output: Trace:   _1_fb = Future { Counter.get }
+            Output: Trace:   _1_fb . give . await ;
+            Output: Trace:   enter Counter.get
+            Output: Trace:     _2_self . count . give ;
+            Output: Trace:   exit Counter.get => 2
+            Output: Trace:   _1_fa . give . await ;
+            Output: Trace:   enter Counter.get
+            Output: Trace:     _3_self . count . give ;
+            Output: Trace:   exit Counter.get => 1
+            Output: Trace: exit Main.main => 1
+            Result: Ok: 1
+            Alloc 0x13: [Int(1)]"#]]
+    );
+}
+
+/// Dropping a future that was never awaited drops the values it captured,
+/// so a tracked value captured by it is dropped without being consumed.
+#[test]
+fn dropped_future_drops_captured_values() {
+    crate::assert_interpret_fault!(
+        {
+            tracked class Tx {
+                id: Int;
+
+                async fn commit(given self) -> () {
+                    ();
+                }
+            }
+            class Main {
+                async fn main(given self) -> () {
+                    let t = new Tx(7);
+                    let f = t.give.commit();
+                    f.drop;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx (7) ;
+            Output: Trace:   _1_t = Tx { id: 7 }
+            Output: Trace:   let _1_f = _1_t . give . commit () ;
+            Output: Trace:   _1_f = Future { Tx.commit }
+            Output: Trace:   _1_f . drop ;
+            Result: Fault: value of tracked class `Tx` dropped without being consumed
+            Alloc 0x07: [Int(7)]"#]]
+    );
+}
+
+/// A future stored in a field is dropped along with the value that holds it,
+/// and with it the values it captured.
+#[test]
+fn future_in_dropped_field_drops_captured_values() {
+    crate::assert_interpret_fault!(
+        {
+            tracked class Tx {
+                id: Int;
+
+                async fn commit(given self) -> () {
+                    ();
+                }
+            }
+            class Holder {
+                f: Future[(), (Tx)];
+            }
+            class Main {
+                async fn main(given self) -> () {
+                    let t = new Tx(7);
+                    let f = t.give.commit();
+                    let h = new Holder(f.give);
+                    h.drop;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx (7) ;
+            Output: Trace:   _1_t = Tx { id: 7 }
+            Output: Trace:   let _1_f = _1_t . give . commit () ;
+            Output: Trace:   _1_f = Future { Tx.commit }
+            Output: Trace:   let _1_h = new Holder (_1_f . give) ;
+            Output: Trace:   _1_h = Holder { f: Future { Tx.commit } }
+            Output: Trace:   _1_h . drop ;
+            Result: Fault: value of tracked class `Tx` dropped without being consumed
+            Alloc 0x07: [Int(7)]"#]]
+    );
+}
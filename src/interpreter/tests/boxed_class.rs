@@ -0,0 +1,114 @@
+// Tests for user-declared `boxed class`es, which are stored like arrays:
+// the value is a `[Flags, Pointer]` wrapper and the fields live in a
+// refcounted heap allocation `[RefCount, fields...]`.
+
+#[test]
+fn boxed_class_new_and_drop() {
+    // Fields are read through the pointer and the drop body runs on scope exit.
+    crate::assert_interpret!(
+        {
+            boxed class Node {
+                value: Int;
+
+                drop {
+                    print(self.value.give);
+                }
+            }
+
+            class Main {
+                fn main(given self) -> () {
+                    let n = new Node(1);
+                    print(n.value.give);
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_n = new Node (1) ;
+            Output: Trace:   _1_n = Node { flag: Given, rc: 1, value: 1 }
+            Output: Trace:   print(_1_n . value . give) ;
+            Output: ----->   1
+            Output: Trace:   () ;
+            Output: Trace:   drop Node
+            Output: Trace:     print(self . value . give) ;
+            Output: ----->     1
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
+
+#[test]
+fn boxed_class_shared_handles_drop_once() {
+    // Copying a shared handle increments the refcount; the drop body
+    // only runs when the last handle is dropped.
+    crate::assert_interpret!(
+        {
+            boxed class Node {
+                value: Int;
+
+                drop {
+                    print(self.value.give);
+                }
+            }
+
+            class Main {
+                fn main(given self) -> () {
+                    let n = new Node(1);
+                    let s = n.give.share;
+                    let t = s.give;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_n = new Node (1) ;
+            Output: Trace:   _1_n = Node { flag: Given, rc: 1, value: 1 }
+            Output: Trace:   let _1_s = _1_n . give . share ;
+            Output: Trace:   _1_s = shared Node { flag: Shared, rc: 1, value: 1 }
+            Output: Trace:   let _1_t = _1_s . give ;
+            Output: Trace:   _1_t = shared Node { flag: Shared, rc: 2, value: 1 }
+            Output: Trace:   () ;
+            Output: Trace:   drop Node
+            Output: Trace:     print(self . value . give) ;
+            Output: ----->     1
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
+
+#[test]
+fn boxed_recursive_list() {
+    // A recursive list: each node holds the next one behind its own pointer.
+    crate::assert_interpret!(
+        {
+            enum Option[ty T] {
+                None { }
+                Some { value: T; }
+            }
+
+            boxed class List {
+                value: Int;
+                next: Option[List];
+            }
+
+            class Main {
+                fn main(given self) -> () {
+                    let l = new List(1, new Option[List]::None());
+                    let m = new List(2, new Option[List]::Some(l.give));
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_l = new List (1, new Option [List] :: None ()) ;
+            Output: Trace:   _1_l = List { flag: Given, rc: 1, value: 1, next: Option::None {  } }
+            Output: Trace:   let _1_m = new List (2, new Option [List] :: Some (_1_l . give)) ;
+            Output: Trace:   _1_m = List { flag: Given, rc: 1, value: 2, next: Option::Some { value: List { flag: Given, rc: 1, value: 1, next: Option::None {  } } } }
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
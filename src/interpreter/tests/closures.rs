@@ -0,0 +1,112 @@
+// Tests for closures, whose calls run with the values captured in their environment.
+
+/// A closure that captures a place by `mut` updates it each time it is called.
+#[test]
+fn mut_closure_updates_capture() {
+    crate::assert_interpret!(
+        {
+            class Counter {
+                count: Int;
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let c = new Counter(0);
+                    let f = || -> () { c.count = c.count.give + 1; (); };
+                    f.mut.call();
+                    f.mut.call();
+                    c.count.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (0) ;
+            Output: Trace:   _1_c = Counter { count: 0 }
+            Output: Trace:   let _1_f = | | -> () { _1_c . count = _1_c . count . give + 1 ; () ; } ;
+            Output: Trace:   _1_f = Closure { _1_c: mut [_1_c] Counter { count: 0 } }
+            Output: Trace:   _1_f . mut . call () ;
+            Output: Trace:   enter closure
+            Output: Trace:     _2__1_c . count = _2__1_c . count . give + 1 ;
+            Output: Trace:     _2__1_c . count = 1
+            Output: Trace:     () ;
+            Output: Trace:   exit closure => ()
+            Output: Trace:   _1_f . mut . call () ;
+            Output: Trace:   enter closure
+            Output: Trace:     _3__1_c . count = _3__1_c . count . give + 1 ;
+            Output: Trace:     _3__1_c . count = 2
+            Output: Trace:     () ;
+            Output: Trace:   exit closure => ()
+            Output: Trace:   _1_c . count . give ;
+            Output: Trace: exit Main.main => 2
+            Result: Ok: 2
+            Alloc 0x0f: [Int(2)]"#]]
+    );
+}
+
+/// A closure that captures a place by `ref` reads it through its inputs' values.
+#[test]
+fn shared_closure_reads_capture() {
+    crate::assert_interpret!(
+        {
+            class Counter {
+                count: Int;
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let c = new Counter(20);
+                    let f = |x: Int| -> Int { c.count.give + x.give; };
+                    f.ref.call(2);
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (20) ;
+            Output: Trace:   _1_c = Counter { count: 20 }
+            Output: Trace:   let _1_f = | x : Int | -> Int { _1_c . count . give + x . give ; } ;
+            Output: Trace:   _1_f = Closure { _1_c: ref [_1_c] Counter { count: 20 } }
+            Output: Trace:   _1_f . ref . call (2) ;
+            Output: Trace:   enter closure
+            Output: Trace:     _2__1_c . count . give + _2_x . give ;
+            Output: Trace:   exit closure => 22
+            Output: Trace: exit Main.main => 22
+            Result: Ok: 22
+            Alloc 0x0b: [Int(22)]"#]]
+    );
+}
+
+/// A closure that captures a value by `give` owns it: calling it through `given`
+/// consumes the closure and gives the value back.
+#[test]
+fn once_closure_gives_capture() {
+    crate::assert_interpret!(
+        {
+            class Counter {
+                count: Int;
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let c = new Counter(22);
+                    let f = || -> Counter { c.give; };
+                    let d = f.give.call();
+                    d.count.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (22) ;
+            Output: Trace:   _1_c = Counter { count: 22 }
+            Output: Trace:   let _1_f = | | -> Counter { _1_c . give ; } ;
+            Output: Trace:   _1_f = Closure { _1_c: Counter { count: 22 } }
+            Output: Trace:   let _1_d = _1_f . give . call () ;
+            Output: Trace:   enter closure
+            Output: Trace:     _2__1_c . give ;
+            Output: Trace:   exit closure => Counter { count: 22 }
+            Output: Trace:   _1_d = Counter { count: 22 }
+            Output: Trace:   _1_d . count . give ;
+            Output: Trace: exit Main.main => 22
+            Result: Ok: 22
+            Alloc 0x0b: [Int(22)]"#]]
+    );
+}
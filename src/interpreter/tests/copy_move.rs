@@ -103,3 +103,33 @@ fn ref_method_field_is_ref() {
             Alloc 0x0d: [Int(0)]"#]]
     );
 }
+
+#[test]
+fn int_field_give_copies() {
+    // Giving an `Int` field copies it: the object stays fully
+    // initialized and can itself be given afterwards.
+    crate::assert_interpret!(
+        {
+            class Data {
+                x: Int;
+            }
+            class Main {
+                fn main(given self) -> Data {
+                    let d = new Data(42);
+                    let a = d.x.give;
+                    d.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_d = new Data (42) ;
+            Output: Trace:   _1_d = Data { x: 42 }
+            Output: Trace:   let _1_a = _1_d . x . give ;
+            Output: Trace:   _1_a = 42
+            Output: Trace:   _1_d . give ;
+            Output: Trace: exit Main.main => Data { x: 42 }
+            Result: Ok: Data { x: 42 }
+            Alloc 0x07: [Int(42)]"#]]
+    );
+}
@@ -0,0 +1,73 @@
+// Tests for constructing and matching on values of an `enum`.
+
+/// A variant stores its discriminant and fields; `match` runs the arm for that
+/// variant with its fields given to the arm's bindings.
+#[test]
+fn match_selects_variant() {
+    crate::assert_interpret!(
+        {
+            enum Shape {
+                Circle { radius: Int; }
+                Square { side: Int; }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let s = new Shape::Square(3);
+                    match s.give {
+                        Circle(r) => { print(r.give); }
+                        Square(w) => { print(w.give + w.give); }
+                    };
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_s = new Shape :: Square (3) ;
+            Output: Trace:   _1_s = Shape::Square { side: 3 }
+            Output: Trace:   match _1_s . give { Circle (_1_r) => { print(_1_r . give) ; } Square (_1_w) => { print(_1_w . give + _1_w . give) ; } } ;
+            Output: Trace:   _1_w = 3
+            Output: Trace:   print(_1_w . give + _1_w . give) ;
+            Output: ----->   6
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
+
+/// Matching on a reference leaves the scrutinee initialized.
+#[test]
+fn match_on_ref_keeps_scrutinee() {
+    crate::assert_interpret!(
+        {
+            enum Option[ty T] {
+                None { }
+                Some { value: T; }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let o = new Option[Int]::Some(5);
+                    match o.ref {
+                        None() => { print(0); }
+                        Some(v) => { print(v.give); }
+                    };
+                    print(o.give);
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_o = new Option [Int] :: Some (5) ;
+            Output: Trace:   _1_o = Option::Some { value: 5 }
+            Output: Trace:   match _1_o . ref { None () => { print(0) ; } Some (_1_v) => { print(_1_v . give) ; } } ;
+            Output: Trace:   _1_v = 5
+            Output: Trace:   print(_1_v . give) ;
+            Output: ----->   5
+            Output: Trace:   print(_1_o . give) ;
+            Output: ----->   Option::Some { value: 5 }
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
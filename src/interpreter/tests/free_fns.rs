@@ -0,0 +1,24 @@
+#[test]
+fn main_fn_is_entry_point() {
+    // A top-level `fn main()` is run in preference to `Main.main`.
+    crate::assert_interpret!(
+        {
+            fn add(a: Int, b: Int) -> Int {
+                a.give + b.give;
+            }
+
+            fn main() -> Int {
+                add(1, 2);
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter main
+            Output: Trace:   add (1, 2) ;
+            Output: Trace:   enter add
+            Output: Trace:     _2_a . give + _2_b . give ;
+            Output: Trace:   exit add => 3
+            Output: Trace: exit main => 3
+            Result: Ok: 3
+            Alloc 0x06: [Int(3)]"#]]
+    );
+}
@@ -247,3 +247,84 @@ fn struct_move_param_ref_borrows() {
             Alloc 0x08: [Int(42)]"#]]
     );
 }
+
+#[test]
+fn inferred_parameters_are_elaborated() {
+    // The class parameter omitted from `new Box(42)` is the one inferred by the type
+    // checker, written out in the program that is run.
+    crate::assert_interpret!(
+        {
+            shared class Box[ty T] {
+                value: T;
+
+                fn get(given self) -> T {
+                    self.value.give;
+                }
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let b = new Box(42);
+                    b.give.get();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_b = new Box [Int] (42) ;
+            Output: Trace:   _1_b = Box { value: 42 }
+            Output: Trace:   _1_b . give . get () ;
+            Output: Trace:   enter Box.get
+            Output: Trace:     _2_self . value . give ;
+            Output: Trace:   exit Box.get => 42
+            Output: Trace: exit Main.main => 42
+            Result: Ok: 42
+            Alloc 0x07: [Int(42)]"#]]
+    );
+}
+
+#[test]
+fn inferred_parameters_are_elaborated_per_use() {
+    // The two `new Box(v.give)` are written alike, but `v` is an `Int` in one block
+    // and a `Bool` in the other: each use is elaborated with its own parameters.
+    crate::assert_interpret!(
+        {
+            class Box[ty T] {
+                value: T;
+            }
+            class Main {
+                fn main(given self) -> () {
+                    {
+                        let v = 22;
+                        let b = new Box(v.give);
+                        print(b.value.give);
+                    };
+                    {
+                        let v = true;
+                        let b = new Box(v.give);
+                        print(b.value.give);
+                    };
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   { let _1_v = 22 ; let _1_b = new Box [Int] (_1_v . give) ; print(_1_b . value . give) ; } ;
+            Output: Trace:   let _1_v = 22 ;
+            Output: Trace:   _1_v = 22
+            Output: Trace:   let _1_b = new Box [Int] (_1_v . give) ;
+            Output: Trace:   _1_b = Box { value: 22 }
+            Output: Trace:   print(_1_b . value . give) ;
+            Output: ----->   22
+            Output: Trace:   { let _1_v = true ; let _1_b = new Box [Bool] (_1_v . give) ; print(_1_b . value . give) ; } ;
+            Output: Trace:   let _1_v = true ;
+            Output: Trace:   _1_v = true
+            Output: Trace:   let _1_b = new Box [Bool] (_1_v . give) ;
+            Output: Trace:   _1_b = Box { value: true }
+            Output: Trace:   print(_1_b . value . give) ;
+            Output: ----->   true
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
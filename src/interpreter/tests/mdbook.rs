@@ -324,6 +324,31 @@ fn interp_conditional_false() {
     // ANCHOR_END: interp_conditional_false
 }
 
+#[test]
+fn interp_conditional_value() {
+    // ANCHOR: interp_conditional_value
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let result = if true { 42; } else { 0; };
+                    result.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_result = if true { 42 ; } else { 0 ; } ;
+            Output: Trace:   42 ;
+            Output: Trace:   _1_result = 42
+            Output: Trace:   _1_result . give ;
+            Output: Trace: exit Main.main => 42
+            Result: Ok: 42
+            Alloc 0x08: [Int(42)]"#]]
+    );
+    // ANCHOR_END: interp_conditional_value
+}
+
 // ---------------------------------------------------------------
 // Array[T] examples for the interpreter chapter
 // ---------------------------------------------------------------
@@ -570,3 +595,40 @@ fn interp_array_drop_frees() {
     );
     // ANCHOR_END: interp_array_drop_frees
 }
+
+// ---------------------------------------------------------------
+// String examples for the interpreter chapter
+// ---------------------------------------------------------------
+
+#[test]
+fn interp_string_concat() {
+    // ANCHOR: interp_string_concat
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let greeting = "hello".share;
+                    let name = "world";
+                    let message = greeting.give + ", " + name.ref;
+                    print(message.ref);
+                    message.ref.len();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_greeting = "hello" . share ;
+            Output: Trace:   _1_greeting = shared String { flag: Shared, rc: 1, "hello" }
+            Output: Trace:   let _1_name = "world" ;
+            Output: Trace:   _1_name = String { flag: Given, rc: 1, "world" }
+            Output: Trace:   let _1_message = _1_greeting . give + ", " + _1_name . ref ;
+            Output: Trace:   _1_message = String { flag: Given, rc: 1, "hello, world" }
+            Output: Trace:   print(_1_message . ref) ;
+            Output: ----->   hello, world
+            Output: Trace:   _1_message . ref . len () ;
+            Output: Trace: exit Main.main => 12
+            Result: Ok: 12
+            Alloc 0x1f: [Int(12)]"#]]
+    );
+    // ANCHOR_END: interp_string_concat
+}
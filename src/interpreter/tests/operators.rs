@@ -0,0 +1,87 @@
+// Tests for arithmetic, comparison, short-circuit and unary operators.
+
+/// Binary operators associate to the left; `-` also negates.
+#[test]
+fn arithmetic() {
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let x = 7 * 6 / 4 % 5;
+                    x.give - -1;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_x = 7 * 6 / 4 % 5 ;
+            Output: Trace:   _1_x = 0
+            Output: Trace:   _1_x . give - - 1 ;
+            Output: Trace: exit Main.main => 1
+            Result: Ok: 1
+            Alloc 0x0f: [Int(1)]"#]]
+    );
+}
+
+/// The right operand of `&&` is not evaluated if the left one is false.
+#[test]
+fn short_circuit() {
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Bool {
+                    let x = 1 > 2 && { print(1); true; };
+                    !x.give || { print(2); false; };
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_x = 1 > 2 && { print(1) ; true ; } ;
+            Output: Trace:   _1_x = false
+            Output: Trace:   ! _1_x . give || { print(2) ; false ; } ;
+            Output: Trace: exit Main.main => true
+            Result: Ok: true
+            Alloc 0x08: [Int(1)]"#]]
+    );
+}
+
+#[test]
+fn division_by_zero() {
+    crate::assert_interpret_fault!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let x = 0;
+                    22 % x.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_x = 0 ;
+            Output: Trace:   _1_x = 0
+            Output: Trace:   22 % _1_x . give ;
+            Result: Fault: division by zero: 22 % 0"#]]
+    );
+}
+
+#[test]
+fn overflow() {
+    crate::assert_interpret_fault!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let x = 9223372036854775807;
+                    x.give * 2;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_x = 9223372036854775807 ;
+            Output: Trace:   _1_x = 9223372036854775807
+            Output: Trace:   _1_x . give * 2 ;
+            Result: Fault: integer overflow: 9223372036854775807 * 2"#]]
+    );
+}
@@ -0,0 +1,85 @@
+// Tests for `String` and `Char` values.
+
+/// Concatenation allocates a new string; `print` shows its text.
+#[test]
+fn concatenate_and_print() {
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Int {
+                    let a = "hello, ";
+                    let b = a.ref + "world";
+                    print(b.ref);
+                    b.ref.len();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_a = "hello, " ;
+            Output: Trace:   _1_a = String { flag: Given, rc: 1, "hello, " }
+            Output: Trace:   let _1_b = _1_a . ref + "world" ;
+            Output: Trace:   _1_b = String { flag: Given, rc: 1, "hello, world" }
+            Output: Trace:   print(_1_b . ref) ;
+            Output: ----->   hello, world
+            Output: Trace:   _1_b . ref . len () ;
+            Output: Trace: exit Main.main => 12
+            Result: Ok: 12
+            Alloc 0x14: [Int(12)]"#]]
+    );
+}
+
+/// Sharing a string increments the refcount of its buffer instead of copying it.
+#[test]
+fn shared_string_refcount() {
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Bool {
+                    let a = "dada".share;
+                    let b = a.give;
+                    print(b.ref);
+                    a.give == b.give;
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_a = "dada" . share ;
+            Output: Trace:   _1_a = shared String { flag: Shared, rc: 1, "dada" }
+            Output: Trace:   let _1_b = _1_a . give ;
+            Output: Trace:   _1_b = shared String { flag: Shared, rc: 2, "dada" }
+            Output: Trace:   print(_1_b . ref) ;
+            Output: ----->   dada
+            Output: Trace:   _1_a . give == _1_b . give ;
+            Output: Trace: exit Main.main => true
+            Result: Ok: true
+            Alloc 0x0e: [Int(1)]"#]]
+    );
+}
+
+/// Chars are displayed as literals and printed as their text.
+#[test]
+fn chars_compare() {
+    crate::assert_interpret!(
+        {
+            class Main {
+                fn main(given self) -> Bool {
+                    let c = 'x';
+                    print(c.give);
+                    c.give >= 'a';
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = 'x' ;
+            Output: Trace:   _1_c = 'x'
+            Output: Trace:   print(_1_c . give) ;
+            Output: ----->   x
+            Output: Trace:   _1_c . give >= 'a' ;
+            Output: Trace: exit Main.main => true
+            Result: Ok: true
+            Alloc 0x07: [Int(1)]"#]]
+    );
+}
@@ -0,0 +1,100 @@
+// Tests for tasks started with `spawn`, which the scheduler interleaves at field accesses.
+
+/// Each field access is a switch point, so the two increments interleave and one
+/// of them is lost; writes to an atomic field are not data races, however.
+#[test]
+fn atomic_increments_interleave() {
+    crate::assert_interpret!(
+        {
+            boxed class Counter {
+                atomic count: Int;
+
+                async fn bump(shared self) -> () {
+                    self.count = self.count.give + 1;
+                    ();
+                }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let c = new Counter(0).share;
+                    spawn(c.give.bump());
+                    spawn(c.give.bump());
+                    print(c.count.give);
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (0) . share ;
+            Output: Trace:   _1_c = shared Counter { flag: Shared, rc: 1, count: 0 }
+            Output: Trace:   spawn(_1_c . give . bump ()) ;
+            Output: Trace:   spawn task 1
+            Output: Trace:   spawn(_1_c . give . bump ()) ;
+            Output: Trace:   spawn task 2
+            Output: Trace:   print(_1_c . count . give) ;
+            Output: Trace: [task 1] enter Counter.bump
+            Output: Trace: [task 1]   _2_self . count = _2_self . count . give + 1 ;
+            Output: ----->   0
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Output: Trace: [task 2] enter Counter.bump
+            Output: Trace: [task 2]   _3_self . count = _3_self . count . give + 1 ;
+            Output: Trace: [task 1]   _2_self . count = 1
+            Output: Trace: [task 1]   () ;
+            Output: Trace: [task 1] exit Counter.bump => ()
+            Output: Trace: [task 2]   _3_self . count = 1
+            Output: Trace: [task 2]   () ;
+            Output: Trace: [task 2] exit Counter.bump => ()
+            Result: Ok: ()"#]]
+    );
+}
+
+/// Writing a non-atomic field that `main` reads concurrently is a data race.
+/// (The type system rejects the write through `shared self`.)
+#[test]
+fn non_atomic_write_is_data_race() {
+    crate::assert_interpret_fault!(
+        {
+            boxed class Counter {
+                count: Int;
+
+                async fn bump(shared self) -> () {
+                    self.count = self.count.give + 1;
+                    ();
+                }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let c = new Counter(0).share;
+                    spawn(c.give.bump());
+                    spawn(c.give.bump());
+                    print(c.count.give);
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_c = new Counter (0) . share ;
+            Output: Trace:   _1_c = shared Counter { flag: Shared, rc: 1, count: 0 }
+            Output: Trace:   spawn(_1_c . give . bump ()) ;
+            Output: Trace:   spawn task 1
+            Output: Trace:   spawn(_1_c . give . bump ()) ;
+            Output: Trace:   spawn task 2
+            Output: Trace:   print(_1_c . count . give) ;
+            Output: Trace: [task 1] enter Counter.bump
+            Output: Trace: [task 1]   _2_self . count = _2_self . count . give + 1 ;
+            Output: ----->   0
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Output: Trace: [task 2] enter Counter.bump
+            Output: Trace: [task 2]   _3_self . count = _3_self . count . give + 1 ;
+            Result: Fault: data race on `_2_self . count`: task 1 writes it while task 0 may read it concurrently
+            Alloc 0x02: [RefCount(2), Int(0)]
+            Alloc 0x05: [Flags(Shared), Pointer(0x02)]
+            Alloc 0x08: [Flags(Shared), Pointer(0x02)]
+            Alloc 0x10: [Int(1)]
+            Alloc 0x13: [Int(1)]"#]]
+    );
+}
@@ -0,0 +1,187 @@
+// Tests for values of a `tracked class`, which the interpreter refuses to drop.
+
+/// A tracked value given to a `given self` method is consumed, not dropped,
+/// and the moved-out variable is not dropped again at the end of its scope.
+#[test]
+fn tracked_value_consumed() {
+    crate::assert_interpret!(
+        {
+            tracked class Tx {
+                id: Int;
+
+                fn commit(given self) -> Int {
+                    self.id.give;
+                }
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let t = new Tx(1);
+                    t.give.commit();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx (1) ;
+            Output: Trace:   _1_t = Tx { id: 1 }
+            Output: Trace:   _1_t . give . commit () ;
+            Output: Trace:   enter Tx.commit
+            Output: Trace:     _2_self . id . give ;
+            Output: Trace:   exit Tx.commit => 1
+            Output: Trace: exit Main.main => 1
+            Result: Ok: 1
+            Alloc 0x08: [Int(1)]"#]]
+    );
+}
+
+/// Dropping a tracked value faults, even when the type checker is bypassed.
+#[test]
+fn tracked_value_dropped_faults() {
+    crate::assert_interpret_fault!(
+        {
+            tracked class Tx {
+                id: Int;
+
+                fn commit(given self) -> () {
+                    ();
+                }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let t = new Tx(7);
+                    t.drop;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx (7) ;
+            Output: Trace:   _1_t = Tx { id: 7 }
+            Output: Trace:   _1_t . drop ;
+            Result: Fault: value of tracked class `Tx` dropped without being consumed
+            Alloc 0x05: [Int(7)]"#]]
+    );
+}
+
+/// A tracked value with no fields still carries a marker word, so dropping it
+/// faults rather than going unnoticed.
+#[test]
+fn zero_sized_tracked_value_dropped_faults() {
+    crate::assert_interpret_fault!(
+        {
+            tracked class Tx {
+                fn commit(given self) -> () {
+                    ();
+                }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let t = new Tx();
+                    t.drop;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx () ;
+            Output: Trace:   _1_t = Tx {  }
+            Output: Trace:   _1_t . drop ;
+            Result: Fault: value of tracked class `Tx` dropped without being consumed
+            Alloc 0x05: [Int(0)]"#]]
+    );
+}
+
+/// Once given to a `given self` method, a tracked value with no fields is
+/// consumed and not dropped again at the end of its scope.
+#[test]
+fn zero_sized_tracked_value_consumed() {
+    crate::assert_interpret!(
+        {
+            tracked class Tx {
+                fn commit(given self) -> () {
+                    ();
+                }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let t = new Tx();
+                    t.give.commit();
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Tx () ;
+            Output: Trace:   _1_t = Tx {  }
+            Output: Trace:   _1_t . give . commit () ;
+            Output: Trace:   enter Tx.commit
+            Output: Trace:     () ;
+            Output: Trace:   exit Tx.commit => ()
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
+
+/// Dropping a value of a tracked enum faults, as for a tracked class.
+#[test]
+fn tracked_enum_value_dropped_faults() {
+    crate::assert_interpret_fault!(
+        {
+            tracked enum Token {
+                Open { }
+                Closed { }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let t = new Token::Open();
+                    t.drop;
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Token :: Open () ;
+            Output: Trace:   _1_t = Token::Open {  }
+            Output: Trace:   _1_t . drop ;
+            Result: Fault: value of tracked enum `Token` dropped without being consumed
+            Alloc 0x05: [Int(0)]"#]]
+    );
+}
+
+/// Matching on a tracked enum consumes it, even for a variant without fields.
+#[test]
+fn tracked_enum_value_consumed_by_match() {
+    crate::assert_interpret!(
+        {
+            tracked enum Token {
+                Open { }
+                Closed { }
+            }
+            class Main {
+                fn main(given self) -> () {
+                    let t = new Token::Open();
+                    match t.give {
+                        Open() => { print(1); }
+                        Closed() => { print(2); }
+                    };
+                    ();
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_t = new Token :: Open () ;
+            Output: Trace:   _1_t = Token::Open {  }
+            Output: Trace:   match _1_t . give { Open () => { print(1) ; } Closed () => { print(2) ; } } ;
+            Output: Trace:   print(1) ;
+            Output: ----->   1
+            Output: Trace:   () ;
+            Output: Trace: exit Main.main => ()
+            Result: Ok: ()"#]]
+    );
+}
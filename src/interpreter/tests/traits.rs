@@ -0,0 +1,41 @@
+/// A trait method called on a type variable is dispatched to the impl
+/// for the class of the receiver.
+#[test]
+fn trait_method_static_dispatch() {
+    crate::assert_interpret!(
+        {
+            trait Describe {
+                fn describe(given self) -> Int ...;
+            }
+            class Foo { }
+            impl Describe for Foo {
+                fn describe(given self) -> Int {
+                    42;
+                }
+            }
+            fn describe_it[ty T](t: T) -> Int where T is Describe {
+                t.give.describe();
+            }
+            class Main {
+                fn main(given self) -> Int {
+                    let f = new Foo();
+                    describe_it[Foo](f.give);
+                }
+            }
+        },
+        expect_test::expect![[r#"
+            Output: Trace: enter Main.main
+            Output: Trace:   let _1_f = new Foo () ;
+            Output: Trace:   _1_f = Foo {  }
+            Output: Trace:   describe_it [Foo] (_1_f . give) ;
+            Output: Trace:   enter describe_it
+            Output: Trace:     _2_t . give . describe () ;
+            Output: Trace:     enter Foo.describe
+            Output: Trace:       42 ;
+            Output: Trace:     exit Foo.describe => 42
+            Output: Trace:   exit describe_it => 42
+            Output: Trace: exit Main.main => 42
+            Result: Ok: 42
+            Alloc 0x07: [Int(42)]"#]]
+    );
+}
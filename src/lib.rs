@@ -1,16 +1,27 @@
+use std::fmt::Write;
 use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 use dada_lang::FormalityLang;
 use fn_error_context::context;
+use formality_core::judgment::ProofTree;
 use formality_core::Fallible;
+use diagnostics::SourceFile;
 use grammar::Program;
 
+pub mod diagnostics;
+pub mod fuzz;
 pub mod grammar;
 pub mod interpreter;
+pub mod minimize;
+pub mod proof_tree;
+mod rng;
 pub mod test_util;
 pub mod type_system;
 
+#[cfg(test)]
+mod tests;
+
 formality_core::declare_language! {
     mod dada_lang {
         const NAME = "Dada";
@@ -29,33 +40,46 @@ formality_core::declare_language! {
             "atomic",
             "await",
             "Bool",
+            "boxed",
             "break",
+            "Char",
             "class",
+            "Closure",
             "copy",
             "drop",
             "else",
+            "enum",
             "false",
             "fn",
+            "for",
+            "Future",
             "give",
             "given",
             "given_from",
             "if",
+            "impl",
             "Int",
             "is",
             "is_last_ref",
             "let",
             "loop",
+            "match",
             "move",
             "mut",
             "new",
+            "once",
             "or",
             "owned",
             "print",
             "ref",
+            "relative",
             "self",
             "share",
             "size_of",
             "shared",
+            "spawn",
+            "String",
+            "trait",
             "true",
         ];
     }
@@ -64,23 +88,202 @@ formality_core::declare_language! {
 #[derive(Parser, Debug)] // requires `derive` feature
 #[command(author, version, about, long_about = None)]
 struct Args {
-    paths: Vec<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Type-check the given files.
+    Check {
+        paths: Vec<String>,
+
+        /// Print the proof tree of each file that type-checks.
+        #[arg(long, value_name = "FORMAT")]
+        proof_tree: Option<ProofTreeFormat>,
+
+        /// Check the consistency of the environment after each statement and each expression
+        /// typed against an expected type, reporting the first one that leaves it inconsistent.
+        #[arg(long)]
+        check_env: bool,
+    },
+
+    /// Type-check the given file and then interpret it,
+    /// instantiating `Main` and calling its `main` method.
+    Run {
+        path: String,
+
+        /// Skip type-checking and interpret the program directly.
+        #[arg(long)]
+        no_check: bool,
+
+        /// Dump the live heap allocations once the program completes.
+        #[arg(long)]
+        dump_heap: bool,
+
+        /// Seed of the scheduler that interleaves the tasks spawned by the program.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+
+    /// Generate random programs and report those that type-check
+    /// but fault in the interpreter.
+    Fuzz {
+        /// Seed of the first program; each subsequent program uses the next seed.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Number of programs to generate.
+        #[arg(long, default_value_t = 1000)]
+        count: usize,
+    },
+
+    /// Shrink a program that fails to type-check, or that faults in the interpreter,
+    /// to a smaller program that fails in the same way, and print it.
+    Minimize {
+        path: String,
+
+        /// Skip type-checking and minimize a fault in the interpreter.
+        #[arg(long)]
+        no_check: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ProofTreeFormat {
+    /// See [`proof_tree::proof_tree_to_json`].
+    Json,
 }
 
 pub fn main() -> Fallible<()> {
     let args = Args::try_parse()?;
 
-    for path in &args.paths {
-        check_file(path)?;
+    match &args.command {
+        Command::Check {
+            paths,
+            proof_tree,
+            check_env,
+        } => {
+            for path in paths {
+                let tree = if *check_env {
+                    type_system::env::with_consistency_checks(|| check_file(path))?
+                } else {
+                    check_file(path)?
+                };
+                if let Some(ProofTreeFormat::Json) = proof_tree {
+                    let json = proof_tree::proof_tree_to_json(&tree);
+                    println!("{}", serde_json::to_string_pretty(&json)?);
+                }
+            }
+        }
+
+        Command::Run {
+            path,
+            no_check,
+            dump_heap,
+            seed,
+        } => {
+            let mut output = String::new();
+            let result = run_file(path, !no_check, *dump_heap, *seed, &mut output);
+            print!("{output}");
+            result?;
+        }
+
+        Command::Fuzz { seed, count } => {
+            let report = fuzz::fuzz(*seed, *count);
+            for bug in &report.bugs {
+                println!("{bug}\n");
+            }
+            println!(
+                "{} programs generated, {} unparsed, {} well-typed, {} soundness bugs",
+                report.generated,
+                report.unparsed,
+                report.well_typed,
+                report.bugs.len()
+            );
+            if !report.bugs.is_empty() {
+                anyhow::bail!("found {} soundness bugs", report.bugs.len());
+            }
+        }
+
+        Command::Minimize { path, no_check } => {
+            let program = minimize_file(path, !no_check)?;
+            println!("{program:?}");
+        }
     }
 
     Ok(())
 }
 
 #[context("check input file `{path:?}`")]
-fn check_file(path: &str) -> Fallible<()> {
+fn check_file(path: &str) -> Fallible<ProofTree> {
     let text: String = std::fs::read_to_string(path)?;
     let program: Arc<Program> = dada_lang::try_term(&text)?;
-    let ((), _proof_tree) = type_system::check_program(&program).into_singleton()?;
+    check_program(&SourceFile::new(path, &text), &program)
+}
+
+/// Interpret the program in `path`, appending what it prints (and the result and heap,
+/// if requested) to `output`, which is complete even if the program faults.
+#[context("run input file `{path:?}`")]
+fn run_file(
+    path: &str,
+    check: bool,
+    dump_heap: bool,
+    seed: u64,
+    output: &mut String,
+) -> Fallible<()> {
+    let text: String = std::fs::read_to_string(path)?;
+    let mut program: Arc<Program> = dada_lang::try_term(&text)?;
+    if check {
+        check_program(&SourceFile::new(path, &text), &program)?;
+        program = type_system::elaborate_program(&program)?;
+    }
+
+    let mut interp = interpreter::Interpreter::new(&program);
+    interp.seed_scheduler(seed);
+    let result = interp.interpret();
+    output.push_str(interp.output());
+
+    // On a fault, we still report the heap (if requested) before propagating the error,
+    // since the heap state is usually what you want to see.
+    let result = result.and_then(|value| {
+        let env = type_system::env::Env::new(program.clone());
+        interp.display_value(&env, &value)
+    });
+    if let Ok(value) = &result {
+        writeln!(output, "Result: {value}")?;
+    }
+
+    if dump_heap {
+        for alloc in interp.dump_heap() {
+            writeln!(output, "Alloc {alloc}")?;
+        }
+    }
+
+    result?;
     Ok(())
 }
+
+#[context("minimize input file `{path:?}`")]
+fn minimize_file(path: &str, check: bool) -> Fallible<Arc<Program>> {
+    let text: String = std::fs::read_to_string(path)?;
+    let program: Arc<Program> = dada_lang::try_term(&text)?;
+    let Some(failure) = minimize::failure_of(&program, check) else {
+        anyhow::bail!("the program does not fail");
+    };
+    eprintln!("minimizing: {failure:?}");
+    Ok(minimize::minimize(&program, |candidate| {
+        minimize::failure_of(candidate, check).as_ref() == Some(&failure)
+    }))
+}
+
+/// Type-check `program`, reporting any failure as a diagnostic pointing into `source`.
+fn check_program(source: &SourceFile<'_>, program: &Arc<Program>) -> Fallible<ProofTree> {
+    match type_system::check_program(program).into_singleton() {
+        Ok(((), proof_tree)) => Ok(proof_tree),
+        Err(e) => anyhow::bail!(
+            "{}",
+            diagnostics::format_diagnostic(source, program, &e.into())
+        ),
+    }
+}
@@ -0,0 +1,1155 @@
+//! Test-case minimization.
+//!
+//! [`minimize`] shrinks a program for as long as a predicate keeps holding of it,
+//! typically "fails in the same way" as determined by [`failure_of`]: the program
+//! still fails to type-check at the same leaf judgment, or still faults in the
+//! interpreter with the same message.
+//!
+//! The reductions drop declarations, methods, fields, predicates, and statements,
+//! and simplify expressions, types, and permissions. As in delta debugging, runs of
+//! items are dropped before single items, so that large programs shrink quickly.
+//! The first reduction for which the predicate holds is kept and we start over,
+//! until none of the reductions of the program does.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+use formality_core::{judgment::FailedJudgment, Set};
+
+use crate::diagnostics::failure_paths;
+use crate::grammar::{
+    Access, Ascription, Async, Atomic, Binder, Block, Boxed, ClassDecl, ClassDeclBoundData,
+    ClassPredicate, Decl, DropBody, EnumDecl, EnumDeclBoundData, Expr, FieldDecl, FnDecl,
+    FnDeclBoundData, ImplDecl, ImplDeclBoundData, LocalVariableDecl, MatchArm, MethodBody,
+    MethodDecl, MethodDeclBoundData, NamedTy, Parameter, Perm, Place, PlaceExpr, Program,
+    Statement, ThisDecl, TraitDecl, Ty, VariantDecl,
+};
+use crate::interpreter::Interpreter;
+use crate::type_system;
+
+#[cfg(test)]
+mod tests;
+
+/// The number of statements we let the interpreter execute before giving up,
+/// since a reduced program may no longer terminate.
+const STEP_LIMIT: usize = 100_000;
+
+/// How a program fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The program does not type-check. `leaf` is the name of the judgment
+    /// at the end of the deepest path through the failure (see [`failure_paths`]).
+    Check { leaf: String },
+
+    /// The interpreter faults (or panics) with `message`.
+    Fault { message: String },
+}
+
+/// Determine how `program` fails, if it does. With `check`, the program is type-checked
+/// and only run (with the parameters the checker inferred written out) if it is accepted;
+/// otherwise it is run without being checked.
+pub fn failure_of(program: &Arc<Program>, check: bool) -> Option<Failure> {
+    let mut program = program.clone();
+    if check {
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            type_system::check_program(&program)
+                .into_singleton()
+                .map_err(anyhow::Error::from)
+                .and_then(|_| type_system::elaborate_program(&program))
+        }));
+        match result {
+            Ok(Ok(elaborated)) => program = elaborated,
+            Ok(Err(e)) => {
+                return Some(Failure::Check {
+                    leaf: deepest_leaf(&e),
+                })
+            }
+            Err(payload) => {
+                return Some(Failure::Check {
+                    leaf: format!("type checker panicked: {}", panic_message(&*payload)),
+                })
+            }
+        }
+    }
+
+    interpret_fault(&program).map(|fault| Failure::Fault {
+        message: format!("{fault:#}"),
+    })
+}
+
+/// Interpret `program`, returning its fault (or the panic of the interpreter) if it has one.
+/// Programs that run for more than [`STEP_LIMIT`] statements fault with
+/// [`FaultKind::StepLimit`](crate::interpreter::FaultKind::StepLimit).
+pub fn interpret_fault(program: &Program) -> Option<anyhow::Error> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut interpreter = Interpreter::new(program);
+        interpreter.limit_steps(STEP_LIMIT);
+        interpreter.interpret().map(|_| ())
+    }));
+    match result {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e),
+        Err(payload) => Some(anyhow::anyhow!(
+            "interpreter panicked: {}",
+            panic_message(&*payload)
+        )),
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> &str {
+    payload
+        .downcast_ref::<String>()
+        .map(|s| s.as_str())
+        .or_else(|| payload.downcast_ref::<&str>().copied())
+        .unwrap_or("<unknown>")
+}
+
+/// The name of the judgment at the end of the deepest path through the failed judgment
+/// in `e` or, if there is no failed judgment, the error message.
+fn deepest_leaf(e: &anyhow::Error) -> String {
+    let Some(failed) = e.chain().find_map(|cause| {
+        if let Some(failed) = cause.downcast_ref::<Box<FailedJudgment>>() {
+            return Some(&**failed);
+        }
+        cause.downcast_ref::<FailedJudgment>()
+    }) else {
+        return format!("{e:#}");
+    };
+
+    let paths = failure_paths(failed);
+    let deepest = paths.iter().fold(&paths[0], |deepest, path| {
+        if path.len() > deepest.len() {
+            path
+        } else {
+            deepest
+        }
+    });
+    let leaf = deepest.last().map(|j| j.as_str()).unwrap_or_default();
+    match leaf.split_once(" {") {
+        Some((name, _)) => name.to_string(),
+        None => leaf.to_string(),
+    }
+}
+
+/// Shrink `program` while `still_fails` holds of it (which it should of `program` itself).
+pub fn minimize(
+    program: &Arc<Program>,
+    mut still_fails: impl FnMut(&Arc<Program>) -> bool,
+) -> Arc<Program> {
+    let mut program = program.clone();
+    'reduce: loop {
+        for candidate in reduce_program(&program) {
+            let candidate = Arc::new(candidate);
+            if still_fails(&candidate) {
+                program = candidate;
+                continue 'reduce;
+            }
+        }
+        return program;
+    }
+}
+
+// --- Reductions ---
+//
+// Each `reduce_*` function returns the programs (types, expressions, ...) that are
+// one step smaller than its argument, roughly from the largest reduction to the smallest.
+
+/// The variants of `items` with a run of consecutive items removed: first all of them,
+/// then each half, each quarter, and so on down to each single item.
+fn without_runs<T: Clone>(items: &[T]) -> Vec<Vec<T>> {
+    let mut candidates = vec![];
+    let mut len = items.len();
+    while len > 0 {
+        for start in (0..items.len()).step_by(len) {
+            let end = (start + len).min(items.len());
+            candidates.push(
+                items[..start]
+                    .iter()
+                    .chain(&items[end..])
+                    .cloned()
+                    .collect(),
+            );
+        }
+        if len == 1 {
+            break;
+        }
+        len = len.div_ceil(2);
+    }
+    candidates
+}
+
+/// The variants of `items` with one item replaced by one of its reductions.
+fn with_each_reduced<T: Clone>(items: &[T], reduce: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    let mut candidates = vec![];
+    for (index, item) in items.iter().enumerate() {
+        for reduced in reduce(item) {
+            let mut candidate = items.to_vec();
+            candidate[index] = reduced;
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+fn reduce_program(program: &Program) -> Vec<Program> {
+    let Program { decls } = program;
+    without_runs(decls)
+        .into_iter()
+        .chain(with_each_reduced(decls, reduce_decl))
+        .map(|decls| Program { decls })
+        .collect()
+}
+
+fn reduce_decl(decl: &Decl) -> Vec<Decl> {
+    match decl {
+        Decl::ClassDecl(decl) => reduce_class(decl)
+            .into_iter()
+            .map(Decl::ClassDecl)
+            .collect(),
+        Decl::EnumDecl(decl) => reduce_enum(decl).into_iter().map(Decl::EnumDecl).collect(),
+        Decl::FnDecl(decl) => reduce_fn(decl).into_iter().map(Decl::FnDecl).collect(),
+        Decl::TraitDecl(decl) => reduce_trait(decl)
+            .into_iter()
+            .map(Decl::TraitDecl)
+            .collect(),
+        Decl::ImplDecl(decl) => reduce_impl(decl).into_iter().map(Decl::ImplDecl).collect(),
+    }
+}
+
+fn reduce_class(decl: &ClassDecl) -> Vec<ClassDecl> {
+    let ClassDecl {
+        name,
+        boxed,
+        class_predicate,
+        binder,
+    } = decl;
+
+    let mut candidates = vec![];
+    if *boxed != Boxed::default() {
+        candidates.push(ClassDecl {
+            boxed: Boxed::default(),
+            ..decl.clone()
+        });
+    }
+    if *class_predicate != ClassPredicate::default() {
+        candidates.push(ClassDecl {
+            class_predicate: ClassPredicate::default(),
+            ..decl.clone()
+        });
+    }
+
+    let (vars, data) = binder.open();
+    let ClassDeclBoundData {
+        predicates,
+        fields,
+        methods,
+        drop_body,
+    } = &data;
+
+    let mut datas = vec![];
+    datas.extend(
+        without_runs(methods)
+            .into_iter()
+            .map(|methods| ClassDeclBoundData {
+                methods,
+                ..data.clone()
+            }),
+    );
+    if *drop_body != DropBody::default() {
+        datas.push(ClassDeclBoundData {
+            drop_body: DropBody::default(),
+            ..data.clone()
+        });
+    }
+    datas.extend(
+        without_runs(fields)
+            .into_iter()
+            .map(|fields| ClassDeclBoundData {
+                fields,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(predicates)
+            .into_iter()
+            .map(|predicates| ClassDeclBoundData {
+                predicates,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(methods, reduce_method)
+            .into_iter()
+            .map(|methods| ClassDeclBoundData {
+                methods,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        reduce_block(&drop_body.block)
+            .into_iter()
+            .map(|block| ClassDeclBoundData {
+                drop_body: DropBody { block },
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(fields, reduce_field)
+            .into_iter()
+            .map(|fields| ClassDeclBoundData {
+                fields,
+                ..data.clone()
+            }),
+    );
+
+    candidates.extend(datas.into_iter().map(|data| ClassDecl {
+        name: name.clone(),
+        boxed: *boxed,
+        class_predicate: *class_predicate,
+        binder: Binder::new(vars.clone(), data),
+    }));
+    candidates
+}
+
+fn reduce_enum(decl: &EnumDecl) -> Vec<EnumDecl> {
+    let EnumDecl {
+        name,
+        class_predicate,
+        binder,
+    } = decl;
+
+    let mut candidates = vec![];
+    if *class_predicate != ClassPredicate::default() {
+        candidates.push(EnumDecl {
+            class_predicate: ClassPredicate::default(),
+            ..decl.clone()
+        });
+    }
+
+    let (vars, data) = binder.open();
+    let EnumDeclBoundData {
+        predicates,
+        variants,
+    } = &data;
+
+    let mut datas = vec![];
+    datas.extend(
+        without_runs(variants)
+            .into_iter()
+            .map(|variants| EnumDeclBoundData {
+                variants,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(predicates)
+            .into_iter()
+            .map(|predicates| EnumDeclBoundData {
+                predicates,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(variants, reduce_variant)
+            .into_iter()
+            .map(|variants| EnumDeclBoundData {
+                variants,
+                ..data.clone()
+            }),
+    );
+
+    candidates.extend(datas.into_iter().map(|data| EnumDecl {
+        name: name.clone(),
+        class_predicate: *class_predicate,
+        binder: Binder::new(vars.clone(), data),
+    }));
+    candidates
+}
+
+fn reduce_variant(variant: &VariantDecl) -> Vec<VariantDecl> {
+    let VariantDecl { name, fields } = variant;
+    without_runs(fields)
+        .into_iter()
+        .chain(with_each_reduced(fields, reduce_field))
+        .map(|fields| VariantDecl {
+            name: name.clone(),
+            fields,
+        })
+        .collect()
+}
+
+fn reduce_field(field: &FieldDecl) -> Vec<FieldDecl> {
+    let FieldDecl { atomic, name, ty } = field;
+
+    let mut candidates = vec![];
+    if *atomic != Atomic::default() {
+        candidates.push(FieldDecl {
+            atomic: Atomic::default(),
+            ..field.clone()
+        });
+    }
+    candidates.extend(reduce_ty(ty).into_iter().map(|ty| FieldDecl {
+        atomic: atomic.clone(),
+        name: name.clone(),
+        ty,
+    }));
+    candidates
+}
+
+fn reduce_method(decl: &MethodDecl) -> Vec<MethodDecl> {
+    let MethodDecl {
+        name,
+        is_async,
+        binder,
+        span,
+    } = decl;
+
+    let mut candidates = vec![];
+    if *is_async != Async::default() {
+        candidates.push(MethodDecl {
+            is_async: Async::default(),
+            ..decl.clone()
+        });
+    }
+
+    let (vars, data) = binder.open();
+    let MethodDeclBoundData {
+        this,
+        inputs,
+        output,
+        predicates,
+        body,
+    } = &data;
+
+    let mut datas = vec![];
+    datas.extend(
+        reduce_body(body)
+            .into_iter()
+            .map(|body| MethodDeclBoundData {
+                body,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(predicates)
+            .into_iter()
+            .map(|predicates| MethodDeclBoundData {
+                predicates,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(inputs)
+            .into_iter()
+            .map(|inputs| MethodDeclBoundData {
+                inputs,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        reduce_perm(&this.perm)
+            .into_iter()
+            .map(|perm| MethodDeclBoundData {
+                this: ThisDecl { perm },
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(inputs, reduce_local_variable_decl)
+            .into_iter()
+            .map(|inputs| MethodDeclBoundData {
+                inputs,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        reduce_ty(output)
+            .into_iter()
+            .map(|output| MethodDeclBoundData {
+                output,
+                ..data.clone()
+            }),
+    );
+
+    candidates.extend(datas.into_iter().map(|data| MethodDecl {
+        name: name.clone(),
+        is_async: *is_async,
+        binder: Binder::new(vars.clone(), data),
+        span: span.clone(),
+    }));
+    candidates
+}
+
+fn reduce_fn(decl: &FnDecl) -> Vec<FnDecl> {
+    let FnDecl { name, binder, span } = decl;
+    let (vars, data) = binder.open();
+    let FnDeclBoundData {
+        inputs,
+        output,
+        predicates,
+        body,
+    } = &data;
+
+    let mut datas = vec![];
+    datas.extend(reduce_body(body).into_iter().map(|body| FnDeclBoundData {
+        body,
+        ..data.clone()
+    }));
+    datas.extend(
+        without_runs(predicates)
+            .into_iter()
+            .map(|predicates| FnDeclBoundData {
+                predicates,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(inputs)
+            .into_iter()
+            .map(|inputs| FnDeclBoundData {
+                inputs,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(inputs, reduce_local_variable_decl)
+            .into_iter()
+            .map(|inputs| FnDeclBoundData {
+                inputs,
+                ..data.clone()
+            }),
+    );
+    datas.extend(reduce_ty(output).into_iter().map(|output| FnDeclBoundData {
+        output,
+        ..data.clone()
+    }));
+
+    datas
+        .into_iter()
+        .map(|data| FnDecl {
+            name: name.clone(),
+            binder: Binder::new(vars.clone(), data),
+            span: span.clone(),
+        })
+        .collect()
+}
+
+fn reduce_trait(decl: &TraitDecl) -> Vec<TraitDecl> {
+    let TraitDecl { name, methods } = decl;
+    without_runs(methods)
+        .into_iter()
+        .chain(with_each_reduced(methods, reduce_method))
+        .map(|methods| TraitDecl {
+            name: name.clone(),
+            methods,
+        })
+        .collect()
+}
+
+fn reduce_impl(decl: &ImplDecl) -> Vec<ImplDecl> {
+    let ImplDecl { binder } = decl;
+    let (vars, data) = binder.open();
+    let ImplDeclBoundData {
+        trait_name: _,
+        class_ty: _,
+        predicates,
+        methods,
+    } = &data;
+
+    let mut datas = vec![];
+    datas.extend(
+        without_runs(methods)
+            .into_iter()
+            .map(|methods| ImplDeclBoundData {
+                methods,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        without_runs(predicates)
+            .into_iter()
+            .map(|predicates| ImplDeclBoundData {
+                predicates,
+                ..data.clone()
+            }),
+    );
+    datas.extend(
+        with_each_reduced(methods, reduce_method)
+            .into_iter()
+            .map(|methods| ImplDeclBoundData {
+                methods,
+                ..data.clone()
+            }),
+    );
+
+    datas
+        .into_iter()
+        .map(|data| ImplDecl {
+            binder: Binder::new(vars.clone(), data),
+        })
+        .collect()
+}
+
+fn reduce_local_variable_decl(decl: &LocalVariableDecl) -> Vec<LocalVariableDecl> {
+    let LocalVariableDecl { name, ty } = decl;
+    reduce_ty(ty)
+        .into_iter()
+        .map(|ty| LocalVariableDecl {
+            name: name.clone(),
+            ty,
+        })
+        .collect()
+}
+
+fn reduce_body(body: &MethodBody) -> Vec<MethodBody> {
+    match body {
+        MethodBody::Trusted => vec![],
+        MethodBody::Block(block) => reduce_block(block)
+            .into_iter()
+            .map(MethodBody::Block)
+            .collect(),
+    }
+}
+
+fn reduce_block(block: &Block) -> Vec<Block> {
+    let Block { statements, span } = block;
+    without_runs(statements)
+        .into_iter()
+        .chain(with_each_reduced(statements, reduce_statement))
+        .map(|statements| Block {
+            statements,
+            span: span.clone(),
+        })
+        .collect()
+}
+
+fn reduce_statement(statement: &Statement) -> Vec<Statement> {
+    match statement {
+        Statement::Expr(expr, span) => reduce_expr(expr)
+            .into_iter()
+            .map(|expr| Statement::Expr(expr, span.clone()))
+            .collect(),
+
+        Statement::Let(name, ascription, expr, span) => {
+            let mut candidates = vec![];
+            if let Ascription::Ty(ty) = ascription {
+                candidates.push(Statement::Let(
+                    name.clone(),
+                    Ascription::NoTy,
+                    expr.clone(),
+                    span.clone(),
+                ));
+                candidates.extend(reduce_ty(ty).into_iter().map(|ty| {
+                    Statement::Let(name.clone(), Ascription::Ty(ty), expr.clone(), span.clone())
+                }));
+            }
+            candidates.extend(
+                reduce_arc_expr(expr).into_iter().map(|expr| {
+                    Statement::Let(name.clone(), ascription.clone(), expr, span.clone())
+                }),
+            );
+            candidates
+        }
+
+        Statement::Reassign(place, expr, span) => reduce_expr(expr)
+            .into_iter()
+            .map(|expr| Statement::Reassign(place.clone(), expr, span.clone()))
+            .collect(),
+
+        Statement::Loop(block, span) => {
+            std::iter::once(Statement::Expr(Expr::Block(block.clone()), span.clone()))
+                .chain(
+                    reduce_block(block)
+                        .into_iter()
+                        .map(|block| Statement::Loop(block, span.clone())),
+                )
+                .collect()
+        }
+
+        Statement::Break(_) => vec![],
+
+        Statement::Return(expr, span) => {
+            std::iter::once(Statement::Expr(expr.clone(), span.clone()))
+                .chain(
+                    reduce_expr(expr)
+                        .into_iter()
+                        .map(|expr| Statement::Return(expr, span.clone())),
+                )
+                .collect()
+        }
+
+        Statement::Print(expr, span) => {
+            std::iter::once(Statement::Expr(expr.clone(), span.clone()))
+                .chain(
+                    reduce_expr(expr)
+                        .into_iter()
+                        .map(|expr| Statement::Print(expr, span.clone())),
+                )
+                .collect()
+        }
+    }
+}
+
+fn reduce_arc_expr(expr: &Arc<Expr>) -> Vec<Arc<Expr>> {
+    reduce_expr(expr).into_iter().map(Arc::new).collect()
+}
+
+fn reduce_parameters(parameters: &[Parameter]) -> Vec<Vec<Parameter>> {
+    with_each_reduced(parameters, reduce_parameter)
+}
+
+fn reduce_expr(expr: &Expr) -> Vec<Expr> {
+    let mut candidates = vec![];
+
+    // Replace a compound expression by a literal of the two types we can always write.
+    if !matches!(
+        expr,
+        Expr::Integer(_)
+            | Expr::String(_)
+            | Expr::Char(_)
+            | Expr::True
+            | Expr::False
+            | Expr::Panic
+            | Expr::Clear(_)
+    ) && *expr != Expr::Tuple(vec![])
+    {
+        candidates.push(Expr::Integer(0));
+        candidates.push(Expr::Tuple(vec![]));
+    }
+
+    match expr {
+        Expr::Integer(_)
+        | Expr::String(_)
+        | Expr::Char(_)
+        | Expr::True
+        | Expr::False
+        | Expr::Panic
+        | Expr::Clear(_) => {}
+
+        Expr::Block(block) => {
+            candidates.extend(reduce_block(block).into_iter().map(Expr::Block));
+        }
+
+        Expr::Or(lhs, rhs) => candidates.extend(reduce_operands(lhs, rhs, Expr::Or)),
+
+        Expr::And(lhs, rhs) => candidates.extend(reduce_operands(lhs, rhs, Expr::And)),
+
+        Expr::Comparison(lhs, op, rhs) => candidates.extend(reduce_operands(lhs, rhs, |l, r| {
+            Expr::Comparison(l, op.clone(), r)
+        })),
+
+        Expr::Additive(lhs, op, rhs) => candidates.extend(reduce_operands(lhs, rhs, |l, r| {
+            Expr::Additive(l, op.clone(), r)
+        })),
+
+        Expr::Multiplicative(lhs, op, rhs) => {
+            candidates.extend(reduce_operands(lhs, rhs, |l, r| {
+                Expr::Multiplicative(l, op.clone(), r)
+            }))
+        }
+
+        Expr::Unary(op, expr) => {
+            candidates.push((**expr).clone());
+            candidates.extend(
+                reduce_arc_expr(expr)
+                    .into_iter()
+                    .map(|expr| Expr::Unary(op.clone(), expr)),
+            );
+        }
+
+        Expr::Place(place_expr) => {
+            candidates.extend(reduce_place_expr(place_expr).into_iter().map(Expr::Place));
+        }
+
+        Expr::Share(expr) => {
+            candidates.push((**expr).clone());
+            candidates.extend(reduce_arc_expr(expr).into_iter().map(Expr::Share));
+        }
+
+        Expr::Await(expr) => {
+            candidates.push((**expr).clone());
+            candidates.extend(reduce_arc_expr(expr).into_iter().map(Expr::Await));
+        }
+
+        Expr::Spawn(expr) => {
+            candidates.push((**expr).clone());
+            candidates.extend(reduce_arc_expr(expr).into_iter().map(Expr::Spawn));
+        }
+
+        Expr::Closure(inputs, output, body) => {
+            candidates.extend(
+                reduce_block(body)
+                    .into_iter()
+                    .map(|body| Expr::Closure(inputs.clone(), output.clone(), body)),
+            );
+        }
+
+        Expr::Tuple(exprs) => {
+            candidates.extend(
+                without_runs(exprs)
+                    .into_iter()
+                    .chain(with_each_reduced(exprs, reduce_expr))
+                    .map(Expr::Tuple),
+            );
+        }
+
+        Expr::Call(receiver, method, parameters, arguments) => {
+            candidates.extend(reduce_arc_expr(receiver).into_iter().map(|receiver| {
+                Expr::Call(
+                    receiver,
+                    method.clone(),
+                    parameters.clone(),
+                    arguments.clone(),
+                )
+            }));
+            candidates.extend(
+                without_runs(parameters)
+                    .into_iter()
+                    .chain(reduce_parameters(parameters))
+                    .map(|parameters| {
+                        Expr::Call(
+                            receiver.clone(),
+                            method.clone(),
+                            parameters,
+                            arguments.clone(),
+                        )
+                    }),
+            );
+            candidates.extend(
+                with_each_reduced(&arguments.exprs, reduce_expr)
+                    .into_iter()
+                    .map(|exprs| {
+                        Expr::Call(
+                            receiver.clone(),
+                            method.clone(),
+                            parameters.clone(),
+                            arguments.with_exprs(exprs),
+                        )
+                    }),
+            );
+        }
+
+        Expr::CallFn(name, parameters, arguments) => {
+            candidates.extend(
+                without_runs(parameters)
+                    .into_iter()
+                    .chain(reduce_parameters(parameters))
+                    .map(|parameters| Expr::CallFn(name.clone(), parameters, arguments.clone())),
+            );
+            candidates.extend(
+                with_each_reduced(&arguments.exprs, reduce_expr)
+                    .into_iter()
+                    .map(|exprs| {
+                        Expr::CallFn(
+                            name.clone(),
+                            parameters.clone(),
+                            arguments.with_exprs(exprs),
+                        )
+                    }),
+            );
+        }
+
+        Expr::New(name, parameters, arguments) => {
+            candidates.extend(
+                without_runs(parameters)
+                    .into_iter()
+                    .chain(reduce_parameters(parameters))
+                    .map(|parameters| Expr::New(name.clone(), parameters, arguments.clone())),
+            );
+            candidates.extend(
+                with_each_reduced(&arguments.exprs, reduce_expr)
+                    .into_iter()
+                    .map(|exprs| {
+                        Expr::New(
+                            name.clone(),
+                            parameters.clone(),
+                            arguments.with_exprs(exprs),
+                        )
+                    }),
+            );
+        }
+
+        Expr::NewVariant(name, parameters, variant, arguments) => {
+            candidates.extend(
+                without_runs(parameters)
+                    .into_iter()
+                    .chain(reduce_parameters(parameters))
+                    .map(|parameters| {
+                        Expr::NewVariant(
+                            name.clone(),
+                            parameters,
+                            variant.clone(),
+                            arguments.clone(),
+                        )
+                    }),
+            );
+            candidates.extend(
+                with_each_reduced(&arguments.exprs, reduce_expr)
+                    .into_iter()
+                    .map(|exprs| {
+                        Expr::NewVariant(
+                            name.clone(),
+                            parameters.clone(),
+                            variant.clone(),
+                            arguments.with_exprs(exprs),
+                        )
+                    }),
+            );
+        }
+
+        Expr::Match(scrutinee, arms) => {
+            candidates.extend(arms.iter().map(|arm| Expr::Block(arm.body.clone())));
+            candidates.extend(
+                without_runs(arms)
+                    .into_iter()
+                    .chain(with_each_reduced(arms, reduce_match_arm))
+                    .map(|arms| Expr::Match(scrutinee.clone(), arms)),
+            );
+            candidates.extend(
+                reduce_arc_expr(scrutinee)
+                    .into_iter()
+                    .map(|scrutinee| Expr::Match(scrutinee, arms.clone())),
+            );
+        }
+
+        Expr::If(condition, if_true, if_false) => {
+            candidates.push((**if_true).clone());
+            candidates.push((**if_false).clone());
+            candidates.extend(
+                reduce_arc_expr(condition)
+                    .into_iter()
+                    .map(|condition| Expr::If(condition, if_true.clone(), if_false.clone())),
+            );
+            candidates.extend(
+                reduce_arc_expr(if_true)
+                    .into_iter()
+                    .map(|if_true| Expr::If(condition.clone(), if_true, if_false.clone())),
+            );
+            candidates.extend(
+                reduce_arc_expr(if_false)
+                    .into_iter()
+                    .map(|if_false| Expr::If(condition.clone(), if_true.clone(), if_false)),
+            );
+        }
+
+        Expr::SizeOf(parameters) => {
+            candidates.extend(reduce_parameters(parameters).into_iter().map(Expr::SizeOf));
+        }
+
+        Expr::ArrayNew(parameters, length) => {
+            candidates.extend(
+                reduce_arc_expr(length)
+                    .into_iter()
+                    .map(|length| Expr::ArrayNew(parameters.clone(), length)),
+            );
+        }
+
+        Expr::ArrayCapacity(parameters, array) => {
+            candidates.extend(
+                reduce_arc_expr(array)
+                    .into_iter()
+                    .map(|array| Expr::ArrayCapacity(parameters.clone(), array)),
+            );
+        }
+
+        Expr::ArrayGive(parameters, array, index) => {
+            candidates.extend(
+                reduce_arc_expr(array)
+                    .into_iter()
+                    .map(|array| Expr::ArrayGive(parameters.clone(), array, index.clone())),
+            );
+            candidates.extend(
+                reduce_arc_expr(index)
+                    .into_iter()
+                    .map(|index| Expr::ArrayGive(parameters.clone(), array.clone(), index)),
+            );
+        }
+
+        Expr::ArrayDrop(parameters, array, from, to) => {
+            candidates.extend(
+                reduce_arc_expr(array).into_iter().map(|array| {
+                    Expr::ArrayDrop(parameters.clone(), array, from.clone(), to.clone())
+                }),
+            );
+            candidates.extend(
+                reduce_arc_expr(from).into_iter().map(|from| {
+                    Expr::ArrayDrop(parameters.clone(), array.clone(), from, to.clone())
+                }),
+            );
+            candidates.extend(
+                reduce_arc_expr(to)
+                    .into_iter()
+                    .map(|to| Expr::ArrayDrop(parameters.clone(), array.clone(), from.clone(), to)),
+            );
+        }
+
+        Expr::ArrayWrite(parameters, array, index, value) => {
+            candidates.extend(reduce_arc_expr(array).into_iter().map(|array| {
+                Expr::ArrayWrite(parameters.clone(), array, index.clone(), value.clone())
+            }));
+            candidates.extend(reduce_arc_expr(index).into_iter().map(|index| {
+                Expr::ArrayWrite(parameters.clone(), array.clone(), index, value.clone())
+            }));
+            candidates.extend(reduce_arc_expr(value).into_iter().map(|value| {
+                Expr::ArrayWrite(parameters.clone(), array.clone(), index.clone(), value)
+            }));
+        }
+
+        Expr::IsLastRef(parameters, expr) => {
+            candidates.extend(
+                reduce_arc_expr(expr)
+                    .into_iter()
+                    .map(|expr| Expr::IsLastRef(parameters.clone(), expr)),
+            );
+        }
+    }
+
+    candidates
+}
+
+/// Either operand of a binary operator on its own, or the operator (as built by `op`)
+/// with one of its operands reduced.
+fn reduce_operands(
+    lhs: &Arc<Expr>,
+    rhs: &Arc<Expr>,
+    op: impl Fn(Arc<Expr>, Arc<Expr>) -> Expr,
+) -> Vec<Expr> {
+    let mut candidates = vec![(**lhs).clone(), (**rhs).clone()];
+    candidates.extend(
+        reduce_arc_expr(lhs)
+            .into_iter()
+            .map(|lhs| op(lhs, rhs.clone())),
+    );
+    candidates.extend(
+        reduce_arc_expr(rhs)
+            .into_iter()
+            .map(|rhs| op(lhs.clone(), rhs)),
+    );
+    candidates
+}
+
+fn reduce_match_arm(arm: &MatchArm) -> Vec<MatchArm> {
+    reduce_block(&arm.body)
+        .into_iter()
+        .map(|body| MatchArm {
+            body,
+            ..arm.clone()
+        })
+        .collect()
+}
+
+/// Access the place by `ref` rather than `give`, `mut` or `drop`, or access a shorter place.
+fn reduce_place_expr(place_expr: &PlaceExpr) -> Vec<PlaceExpr> {
+    let PlaceExpr {
+        place,
+        access,
+        span,
+    } = place_expr;
+
+    let mut candidates = vec![];
+    if *access != Access::Rf {
+        candidates.push(PlaceExpr {
+            place: place.clone(),
+            access: Access::Rf,
+            span: span.clone(),
+        });
+    }
+    if let Some(owner) = place.owner() {
+        candidates.push(PlaceExpr {
+            place: owner,
+            access: *access,
+            span: span.clone(),
+        });
+    }
+    candidates
+}
+
+fn reduce_parameter(parameter: &Parameter) -> Vec<Parameter> {
+    match parameter {
+        Parameter::Ty(ty) => reduce_ty(ty).into_iter().map(Parameter::Ty).collect(),
+        Parameter::Perm(perm) => reduce_perm(perm).into_iter().map(Parameter::Perm).collect(),
+    }
+}
+
+/// Strip permissions from types, and simplify the permissions and type parameters within.
+fn reduce_ty(ty: &Ty) -> Vec<Ty> {
+    match ty {
+        Ty::NamedTy(NamedTy { name, parameters }) => reduce_parameters(parameters)
+            .into_iter()
+            .map(|parameters| {
+                Ty::NamedTy(NamedTy {
+                    name: name.clone(),
+                    parameters,
+                })
+            })
+            .collect(),
+
+        Ty::Var(_) => vec![],
+
+        Ty::ApplyPerm(perm, ty) => std::iter::once((**ty).clone())
+            .chain(
+                reduce_perm(perm)
+                    .into_iter()
+                    .map(|perm| Ty::ApplyPerm(perm, ty.clone())),
+            )
+            .chain(
+                reduce_ty(ty)
+                    .into_iter()
+                    .map(|ty| Ty::ApplyPerm(perm.clone(), Arc::new(ty))),
+            )
+            .collect(),
+    }
+}
+
+/// Drop places from `given_from[..]`, `ref[..]` and `mut[..]` (keeping at least one
+/// for `given_from` and `mut`), and drop permissions from compositions and `or(..)`.
+fn reduce_perm(perm: &Perm) -> Vec<Perm> {
+    let without_each_place = |places: &Set<Place>| -> Vec<Set<Place>> {
+        places
+            .iter()
+            .map(|place| places.iter().filter(|p| *p != place).cloned().collect())
+            .collect()
+    };
+
+    match perm {
+        Perm::Mv(places) if places.len() > 1 => without_each_place(places)
+            .into_iter()
+            .map(Perm::Mv)
+            .collect(),
+        Perm::Mt(places) if places.len() > 1 => without_each_place(places)
+            .into_iter()
+            .map(Perm::Mt)
+            .collect(),
+        Perm::Rf(places) => without_each_place(places)
+            .into_iter()
+            .map(Perm::Rf)
+            .collect(),
+        Perm::Mv(_) | Perm::Mt(_) | Perm::Given | Perm::Shared | Perm::Var(_) => vec![],
+
+        Perm::Apply(lhs, rhs) => [(**lhs).clone(), (**rhs).clone()]
+            .into_iter()
+            .chain(
+                reduce_perm(lhs)
+                    .into_iter()
+                    .map(|lhs| Perm::Apply(Arc::new(lhs), rhs.clone())),
+            )
+            .chain(
+                reduce_perm(rhs)
+                    .into_iter()
+                    .map(|rhs| Perm::Apply(lhs.clone(), Arc::new(rhs))),
+            )
+            .collect(),
+
+        Perm::Or(perms) => {
+            let mut candidates: Vec<Perm> = perms.iter().cloned().collect();
+            if perms.len() > 2 {
+                candidates.extend(
+                    perms.iter().map(|perm| {
+                        Perm::Or(perms.iter().filter(|p| *p != perm).cloned().collect())
+                    }),
+                );
+            }
+            candidates
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::{dada_lang, grammar::Program};
+
+use super::{failure_of, minimize, without_runs, Failure};
+
+#[test]
+fn without_runs_largest_first() {
+    assert_eq!(
+        without_runs(&[1, 2, 3]),
+        vec![
+            vec![],
+            vec![3],
+            vec![1, 2],
+            vec![2, 3],
+            vec![1, 3],
+            vec![1, 2],
+        ]
+    );
+}
+
+#[test]
+fn failure_of_well_typed_program() {
+    let program: Arc<Program> = dada_lang::term(
+        "
+        class Main {
+            fn main(given self) -> Int {
+                22;
+            }
+        }
+        ",
+    );
+    assert_eq!(failure_of(&program, true), None);
+}
+
+#[test]
+fn minimize_type_error() {
+    let program: Arc<Program> = dada_lang::term(
+        "
+        class Unrelated {
+            x: Int;
+            fn get(ref self) -> Int { self.x.give; }
+        }
+
+        class Data {
+            x: Int;
+            y: Int;
+        }
+
+        class Main {
+            fn main(given self) -> Int {
+                let u = new Unrelated(1);
+                let d = new Data(22, 44);
+                print(u.ref.get());
+                let e = d.give;
+                let f = d.give;
+                0;
+            }
+        }
+        ",
+    );
+
+    let failure = failure_of(&program, true).expect("program should fail");
+    assert!(matches!(failure, Failure::Check { .. }), "{failure:?}");
+
+    let minimized = minimize(&program, |p| failure_of(p, true).as_ref() == Some(&failure));
+    assert_eq!(failure_of(&minimized, true), Some(failure));
+    assert!(
+        minimized.decls.len() < program.decls.len(),
+        "not minimized: {minimized:?}"
+    );
+}
+
+#[test]
+fn minimize_fault_without_check() {
+    let program: Arc<Program> = dada_lang::term(
+        "
+        class Data {
+            x: Int;
+        }
+
+        class Main {
+            fn main(given self) -> Int {
+                let d = new Data(22);
+                let n = 1;
+                print(n.give);
+                let e = d.give;
+                d.x.give;
+            }
+        }
+        ",
+    );
+
+    let failure = failure_of(&program, false).expect("program should fault");
+    assert!(matches!(failure, Failure::Fault { .. }), "{failure:?}");
+
+    let minimized = minimize(&program, |p| {
+        failure_of(p, false).as_ref() == Some(&failure)
+    });
+    assert_eq!(failure_of(&minimized, false), Some(failure));
+    assert!(
+        format!("{minimized:?}").len() < format!("{program:?}").len(),
+        "not minimized: {minimized:?}"
+    );
+}
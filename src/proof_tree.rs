@@ -0,0 +1,159 @@
+//! Access to the proof trees built by the type checker, e.g. as JSON,
+//! so that they can be explored by other tools (such as the `{{#proof}}`
+//! directive of `mdbook-judgment`) or diffed between versions of the rules.
+
+use std::sync::Arc;
+
+use formality_core::{judgment::ProofTree, Fallible};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    dada_lang,
+    diagnostics::{format_diagnostic, SourceFile},
+    grammar::Program,
+    type_system,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// Parse and type-check the program `text`, returning its proof tree.
+/// A failure is reported as a diagnostic pointing into `text`, attributed to `path`.
+pub fn prove_program(path: &str, text: &str) -> Fallible<ProofTree> {
+    let program: Arc<Program> = dada_lang::try_term(text)?;
+    match type_system::check_program(&program).into_singleton() {
+        Ok(((), proof_tree)) => Ok(proof_tree),
+        Err(e) => anyhow::bail!(
+            "{}",
+            format_diagnostic(&SourceFile::new(path, text), &program, &e.into())
+        ),
+    }
+}
+
+/// Serialize `tree` as JSON. Each node of the tree becomes an object
+///
+/// ```json
+/// {
+///   "judgment": "type_expr",
+///   "rule": "give",
+///   "inputs": { "expr": "d . give", "env": "Env { .. }", "live_after": "..." },
+///   "output": null,
+///   "children": [ .. ]
+/// }
+/// ```
+///
+/// where the inputs and output are rendered with `Debug`. Nodes that are not
+/// the application of a judgment (e.g., the leaves built by hand in some of the
+/// rules) have their full text as `judgment` and no inputs.
+pub fn proof_tree_to_json(tree: &ProofTree) -> Value {
+    let (judgment, inputs, output) = match split_judgment(&tree.attempted) {
+        Some(JudgmentText {
+            name,
+            inputs,
+            output,
+        }) => (name, inputs, output),
+        None => (tree.attempted.as_str(), vec![], None),
+    };
+
+    let inputs: Map<String, Value> = inputs
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), Value::from(value)))
+        .collect();
+
+    json!({
+        "judgment": judgment,
+        "rule": tree.rule_name,
+        "inputs": inputs,
+        "output": output,
+        "children": tree.children.iter().map(proof_tree_to_json).collect::<Vec<_>>(),
+    })
+}
+
+/// The `Debug` text of a judgment, `name { input: value, .. }`,
+/// possibly followed by ` => output`, picked apart.
+#[derive(Debug, PartialEq, Eq)]
+struct JudgmentText<'t> {
+    name: &'t str,
+    inputs: Vec<(&'t str, &'t str)>,
+    output: Option<&'t str>,
+}
+
+fn split_judgment(text: &str) -> Option<JudgmentText<'_>> {
+    let (name, rest) = text.split_once(" { ")?;
+    if !is_identifier(name) {
+        return None;
+    }
+
+    let fields_start = name.len() + " { ".len();
+    let fields_end = fields_start + top_level_position(rest, '}')?;
+    let fields = text[fields_start..fields_end].trim_end();
+
+    let mut inputs = vec![];
+    for field in split_top_level(fields) {
+        let (input, value) = field.split_once(": ")?;
+        if !is_identifier(input) {
+            return None;
+        }
+        inputs.push((input, value));
+    }
+
+    let after = &text[fields_end + 1..];
+    let output = match after.strip_prefix(" => ") {
+        Some(output) => Some(output),
+        None if after.is_empty() => None,
+        None => return None,
+    };
+
+    Some(JudgmentText {
+        name,
+        inputs,
+        output,
+    })
+}
+
+fn is_identifier(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Split `text` at the `, ` that are not nested within brackets or string literals.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut pieces = vec![];
+    let mut rest = text;
+    while let Some(index) = top_level_position(rest, ',') {
+        pieces.push(&rest[..index]);
+        rest = rest[index + 1..].trim_start();
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    pieces
+}
+
+/// Byte offset of the first occurrence of `needle` in `text` that is not nested
+/// within brackets or string literals (a closing bracket is found when it closes
+/// a bracket opened before the start of `text`).
+fn top_level_position(text: &str, needle: char) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            _ if c == needle && depth == 0 => return Some(index),
+            '"' => {
+                // Skip to the end of the string literal, minding escapes.
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.checked_sub(1)?,
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,98 @@
+use super::{proof_tree_to_json, split_judgment, JudgmentText};
+
+#[test]
+fn split_judgment_with_inputs() {
+    let text = concat!(
+        r#"type_expr { expr: d . give, env: Env { program: "...", fresh: 0 }, "#,
+        "live_after: LivePlaces { accessed: {d}, traversed: {} } }",
+    );
+    assert_eq!(
+        split_judgment(text),
+        Some(JudgmentText {
+            name: "type_expr",
+            inputs: vec![
+                ("expr", "d . give"),
+                ("env", r#"Env { program: "...", fresh: 0 }"#),
+                ("live_after", "LivePlaces { accessed: {d}, traversed: {} }"),
+            ],
+            output: None,
+        })
+    );
+}
+
+#[test]
+fn split_judgment_with_output() {
+    assert_eq!(
+        split_judgment("prove_predicate { predicate: shared is copy, env: Env { } } => ()"),
+        Some(JudgmentText {
+            name: "prove_predicate",
+            inputs: vec![("predicate", "shared is copy"), ("env", "Env { }")],
+            output: Some("()"),
+        })
+    );
+}
+
+#[test]
+fn split_judgment_with_commas_in_strings() {
+    assert_eq!(
+        split_judgment(r#"check_thing { text: "a, b }", n: 1 }"#),
+        Some(JudgmentText {
+            name: "check_thing",
+            inputs: vec![("text", r#""a, b }""#), ("n", "1")],
+            output: None,
+        })
+    );
+}
+
+#[test]
+fn split_not_a_judgment() {
+    assert_eq!(split_judgment("prove_is_move_if_some: None"), None);
+    assert_eq!(split_judgment("collect"), None);
+}
+
+/// The proof tree of a whole program starts at `check_program` and
+/// has a `check_decl` node for each declaration.
+#[test]
+fn check_program_to_json() {
+    let tree = crate::test_util::test_program_ok(
+        "
+        class Data { }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                ();
+            }
+        }
+        ",
+    )
+    .unwrap();
+    let json = proof_tree_to_json(&tree);
+
+    assert_eq!(json["judgment"], "check_program");
+    assert_eq!(json["rule"], "check_program");
+    assert!(json["inputs"]["program"]
+        .as_str()
+        .unwrap()
+        .starts_with("class Data"));
+
+    let mut decls = vec![];
+    collect_inputs(&json, "check_decl", "decl", &mut decls);
+    assert_eq!(decls.len(), 2);
+    assert!(decls[0].starts_with("class Data"));
+    assert!(decls[1].starts_with("class Main"));
+}
+
+/// Collect the `input` of each node of `json` that applies `judgment`, in order.
+fn collect_inputs<'j>(
+    json: &'j serde_json::Value,
+    judgment: &str,
+    input: &str,
+    out: &mut Vec<&'j str>,
+) {
+    if json["judgment"] == judgment {
+        out.extend(json["inputs"][input].as_str());
+    }
+    for child in json["children"].as_array().unwrap() {
+        collect_inputs(child, judgment, input, out);
+    }
+}
@@ -4,6 +4,7 @@ use formality_core::judgment::{FailedJudgment, ProofTree};
 use formality_core::Fallible;
 
 use crate::dada_lang;
+use crate::diagnostics::{format_diagnostic, SourceFile};
 use crate::grammar::Program;
 use crate::interpreter::Interpreter;
 use crate::type_system;
@@ -14,6 +15,20 @@ pub fn test_program_ok(input: &str) -> Fallible<ProofTree> {
     Ok(proof_tree)
 }
 
+/// Type-check `input`, formatting any failure as a diagnostic with the location
+/// of the failing statement, expression or place (see [`crate::diagnostics`]).
+pub fn test_program_diagnostic(input: &str) -> Fallible<String> {
+    let program: Arc<Program> = dada_lang::try_term(input)?;
+    match type_system::check_program(&program).into_singleton() {
+        Ok(((), proof_tree)) => anyhow::bail!("expected `Err`, got `Ok`:\n{proof_tree:?}"),
+        Err(e) => Ok(format_diagnostic(
+            &SourceFile::new("input", input),
+            &program,
+            &e.into(),
+        )),
+    }
+}
+
 /// Result of running the interpreter.
 pub struct InterpretResult {
     pub result: String,
@@ -44,6 +59,7 @@ impl InterpretResult {
 pub fn test_interpret(input: &str) -> anyhow::Result<InterpretResult> {
     let program: Arc<Program> = dada_lang::try_term(input)?;
     let ((), _proof_tree) = type_system::check_program(&program).into_singleton()?;
+    let program = type_system::elaborate_program(&program)?;
     Ok(run_interpreter(&program))
 }
 
@@ -125,6 +141,18 @@ macro_rules! assert_err {
     }};
 }
 
+/// Like `assert_err!` but the snapshot includes the `line:col` of the failure
+/// and the offending source line. The input is a string literal, rather than tokens,
+/// so that line numbers are meaningful.
+#[macro_export]
+macro_rules! assert_diagnostic {
+    ($input:expr, $expect:expr) => {{
+        let output = $crate::test_util::test_program_diagnostic($input).expect("expected a type-check failure");
+        let output = formality_core::test_util::normalize_paths(output);
+        $expect.assert_eq(&output);
+    }};
+}
+
 #[macro_export]
 macro_rules! assert_interpret {
     ({ $($input:tt)* }, $expect:expr) => {{
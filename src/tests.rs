@@ -0,0 +1,63 @@
+// Tests for the `check` and `run` subcommands, driven on the fixture files in `src/tests`.
+
+fn fixture(name: &str) -> String {
+    format!("{}/src/tests/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn check_well_typed_file() {
+    super::check_file(&fixture("hello.dada")).unwrap();
+}
+
+#[test]
+fn check_ill_typed_file() {
+    let e = super::check_file(&fixture("ill_typed.dada")).unwrap_err();
+    let message = format!("{e:#}");
+    assert!(message.contains("ill_typed.dada:2:33"), "{message}");
+}
+
+#[test]
+fn run_file() {
+    let mut output = String::new();
+    super::run_file(&fixture("hello.dada"), true, false, 0, &mut output).unwrap();
+    expect_test::expect![[r#"
+        Trace: enter Main.main
+        Trace:   let _1_x = 22 ;
+        Trace:   _1_x = 22
+        Trace:   print(_1_x . give) ;
+        ----->   22
+        Trace:   _1_x . give + 1 ;
+        Trace: exit Main.main => 23
+        Result: 23
+    "#]]
+    .assert_eq(&output);
+}
+
+#[test]
+fn run_file_dump_heap() {
+    let mut output = String::new();
+    super::run_file(&fixture("hello.dada"), true, true, 0, &mut output).unwrap();
+    expect_test::expect![[r#"
+        Trace: enter Main.main
+        Trace:   let _1_x = 22 ;
+        Trace:   _1_x = 22
+        Trace:   print(_1_x . give) ;
+        ----->   22
+        Trace:   _1_x . give + 1 ;
+        Trace: exit Main.main => 23
+        Result: 23
+        Alloc 0x07: [Int(23)]
+    "#]]
+    .assert_eq(&output);
+}
+
+/// An ill-typed file is not run, unless type-checking is skipped with `--no-check`.
+#[test]
+fn run_file_no_check() {
+    let mut output = String::new();
+    assert!(super::run_file(&fixture("ill_typed.dada"), true, false, 0, &mut output).is_err());
+    assert_eq!(output, "");
+
+    super::run_file(&fixture("ill_typed.dada"), false, false, 0, &mut output).unwrap();
+    assert!(output.ends_with("Result: 1\n"), "{output}");
+}
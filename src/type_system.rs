@@ -1,24 +1,33 @@
-use std::sync::Arc;
+use std::{fmt::Debug, sync::Arc};
 
-use formality_core::judgment_fn;
+use anyhow::bail;
+use formality_core::{judgment_fn, Fallible, ProvenSet};
 
 use crate::grammar::{Decl, Program};
 
+use env::Env;
+
 mod accesses;
 mod blocks;
 mod classes;
+mod enums;
 pub mod env;
 mod expressions;
 pub mod in_flight;
+pub mod inference;
+mod join;
 mod liveness;
-mod local_liens;
+pub mod local_liens;
 mod methods;
 mod perm_matcher;
 mod places;
+mod pop_normalize;
 pub mod predicates;
 mod redperms;
 mod statements;
 mod subtypes;
+mod tracked;
+mod traits;
 mod types;
 
 #[cfg(test)]
@@ -52,6 +61,66 @@ judgment_fn! {
             ----------------------- ("class")
             (check_decl(program, Decl::ClassDecl(class_decl)) => ())
         )
+
+        (
+            (enums::check_enum(program, enum_decl) => ())
+            ----------------------- ("enum")
+            (check_decl(program, Decl::EnumDecl(enum_decl)) => ())
+        )
+
+        (
+            (methods::check_fn(Env::new(program), fn_decl) => _)
+            ----------------------- ("fn")
+            (check_decl(program, Decl::FnDecl(fn_decl)) => ())
+        )
+
+        (
+            (traits::check_trait(program, trait_decl) => ())
+            ----------------------- ("trait")
+            (check_decl(program, Decl::TraitDecl(trait_decl)) => ())
+        )
+
+        (
+            (traits::check_impl(program, impl_decl) => ())
+            ----------------------- ("impl")
+            (check_decl(program, Decl::ImplDecl(impl_decl)) => ())
+        )
     }
 }
 // ANCHOR_END: check_program
+
+/// Returns `program` with the generic parameters inferred by the type checker
+/// written out wherever they were omitted (see `inference.rs`), so that the
+/// interpreter runs the program exactly as it was checked.
+/// The bodies are typed again, so `program` must be well-typed (see [`check_program`]).
+pub fn elaborate_program(program: &Arc<Program>) -> Fallible<Arc<Program>> {
+    let decls = program
+        .decls
+        .iter()
+        .map(|decl| elaborate_decl(program, decl))
+        .collect::<Fallible<_>>()?;
+    Ok(Arc::new(Program { decls }))
+}
+
+fn elaborate_decl(program: &Arc<Program>, decl: &Decl) -> Fallible<Decl> {
+    Ok(match decl {
+        Decl::ClassDecl(class_decl) => {
+            Decl::ClassDecl(classes::elaborate_class(program, class_decl)?)
+        }
+        Decl::FnDecl(fn_decl) => {
+            Decl::FnDecl(some_output(methods::check_fn(Env::new(program), fn_decl))?)
+        }
+        Decl::ImplDecl(impl_decl) => Decl::ImplDecl(traits::elaborate_impl(program, impl_decl)?),
+        Decl::EnumDecl(_) | Decl::TraitDecl(_) => decl.clone(),
+    })
+}
+
+/// The output of one of the proofs of a judgment that checks a declaration.
+/// Different proofs can only differ in how they inferred some parameters,
+/// so any of them will do.
+fn some_output<T: Ord + Debug + Clone>(outputs: ProvenSet<T>) -> Fallible<T> {
+    match outputs.into_map()?.into_keys().next() {
+        Some(output) => Ok(output),
+        None => bail!("judgment has no proofs"),
+    }
+}
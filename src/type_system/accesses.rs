@@ -1,12 +1,13 @@
-use formality_core::{judgment_fn, Cons};
+use formality_core::{judgment_fn, Fallible, To};
 
 use crate::{
-    grammar::{Access, FieldDecl, Parameter, Place, Ty},
+    grammar::{ty_impls::PermTy, Access, FieldDecl, Parameter, Perm, Place, Var},
     type_system::{
         env::Env,
         in_flight::InFlight,
         liveness::LivePlaces,
         local_liens::{liens, Lien},
+        predicates::{prove_is_copy, prove_is_move},
     },
 };
 
@@ -42,13 +43,83 @@ judgment_fn! {
         debug(access, place, env, live_after)
 
         (
+            (if let false = is_copy_give(&env, access, &place)?)!
             (env_permits_access(env, live_after, access, place) => env)
             -------------------------------- ("access_permitted")
             (access_permitted(env, live_after, access, place) => env)
         )
+
+        (
+            // Giving a value of copy type copies it out of `place`, leaving it initialized,
+            // so it is permitted wherever reading `place` is.
+            (if let true = is_copy_give(&env, access, &place)?)!
+            (env_permits_access(env, live_after, Access::Rf, place) => env)
+            -------------------------------- ("give copy")
+            (access_permitted(env, live_after, access, place) => env)
+        )
+    }
+}
+
+judgment_fn! {
+    /// True if the permissions of the owners of `place` (its strict prefixes)
+    /// permit it to be mutated. A permission that is only known through a
+    /// permission variable or an `or(..)` may be shared at runtime, so it must
+    /// be proven to be `mut` or `given` (e.g., by a `where P is mut` clause).
+    /// Other permissions are checked by `prove_is_move_if_some` and the access rules.
+    pub fn owners_permit_mutation(
+        env: Env,
+        place: Place,
+    ) => () {
+        debug(place, env)
+
+        (
+            (for_all(owner in place.strict_prefixes())
+                (owner_permits_mutation(env, owner) => ()))
+            -------------------------------- ("owners_permit_mutation")
+            (owners_permit_mutation(env, place) => ())
+        )
     }
 }
 
+judgment_fn! {
+    fn owner_permits_mutation(
+        env: Env,
+        owner: Place,
+    ) => () {
+        debug(owner, env)
+
+        (
+            (let PermTy(perm, _) = env.place_ty(&owner)?.to())
+            (if !perm_may_be_shared(&perm))!
+            -------------------------------- ("known perm")
+            (owner_permits_mutation(env, owner) => ())
+        )
+
+        (
+            (let PermTy(perm, _) = env.place_ty(&owner)?.to())
+            (if perm_may_be_shared(&perm))!
+            (prove_is_move(env, perm) => ())
+            -------------------------------- ("perm may be shared")
+            (owner_permits_mutation(env, owner) => ())
+        )
+    }
+}
+
+/// True if `perm` involves a permission variable or an `or(..)`,
+/// and so may turn out to be shared.
+fn perm_may_be_shared(perm: &Perm) -> bool {
+    match perm {
+        Perm::Var(_) | Perm::Or(_) => true,
+        Perm::Apply(l, r) => perm_may_be_shared(l) || perm_may_be_shared(r),
+        Perm::Given | Perm::Shared | Perm::Mv(_) | Perm::Rf(_) | Perm::Mt(_) => false,
+    }
+}
+
+/// True if `access` gives `place` and the type of `place` is copy.
+fn is_copy_give(env: &Env, access: &Access, place: &Place) -> Fallible<bool> {
+    Ok(*access == Access::Gv && prove_is_copy(env, env.place_ty(place)?).is_proven())
+}
+
 judgment_fn! {
     /// True if accessing `place` in the fashion given by `access`
     /// is permitted by the other variables in the environment.
@@ -63,8 +134,9 @@ judgment_fn! {
         debug(access, place, env, live_after)
 
         (
-            (let live_var_tys: Vec<Ty> = live_after.vars().iter().map(|var| env.var_ty(var).unwrap()).cloned().collect())
-            (parameters_permit_access(env, live_var_tys, access, place) => env)
+            (let live_vars: Vec<Var> = live_after.vars().into_iter().cloned().collect())
+            (for_all(var in live_vars) with(env)
+                (live_variable_permits_access(env, var, access, place) => env))
             (accessed_place_permits_access(env, live_after, access, place) => env)
             -------------------------------- ("env_permits_access")
             (env_permits_access(env, live_after, access, place) => env)
@@ -73,25 +145,21 @@ judgment_fn! {
 }
 
 judgment_fn! {
-    fn parameters_permit_access(
+    /// True if the liens in the type of the live variable `var`
+    /// permit `place` to be accessed in the fashion given by `access`.
+    fn live_variable_permits_access(
         env: Env,
-        parameters: Vec<Parameter>,
+        var: Var,
         access: Access,
         place: Place,
     ) => Env {
-        debug(parameters, access, place, env)
-
-        (
-            -------------------------------- ("nil")
-            (parameters_permit_access(env, (), _access, _place) => env)
-        )
-
+        debug(var, access, place, env)
 
         (
-            (parameter_permits_access(env, parameter, access, place) => env)
-            (parameters_permit_access(env, parameters, access, place) => env)
-            -------------------------------- ("cons")
-            (parameters_permit_access(env, Cons(parameter, parameters), access, place) => env)
+            (let var_ty = env.var_ty(var)?.clone())
+            (parameter_permits_access(env, var_ty, access, place) => env)
+            -------------------------------- ("live variable")
+            (live_variable_permits_access(env, var, access, place) => env)
         )
     }
 }
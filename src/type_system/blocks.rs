@@ -14,9 +14,14 @@ judgment_fn! {
         debug(block, env, live_after)
 
         (
-            (type_statements(env, live_after, statements) => (env, ty))
+            (type_statements(env, live_after, statements) => (env_out, ty))
+
+            // Variables declared in the block go out of scope at its end;
+            // the block's type and the remaining variables cannot refer to them.
+            (let popped_vars = env_out.local_variables_not_in(&env))
+            (let (env, ty) = env_out.pop_local_variables_normalizing(&live_after, &popped_vars, &ty)?)
             ----------------------------------- ("place")
-            (type_block(env, live_after, Block { statements }) => (env, ty))
+            (type_block(env, live_after, Block { statements, .. }) => (env, ty))
         )
     }
 }
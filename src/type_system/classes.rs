@@ -1,18 +1,21 @@
 use std::sync::Arc;
 
-use formality_core::judgment_fn;
+use anyhow::bail;
+use formality_core::{judgment_fn, Fallible};
 
 use crate::grammar::{
-    Atomic, ClassDecl, ClassDeclBoundData, ClassPredicate, DropBody, FieldDecl, Kind, NamedTy,
-    Perm, Predicate, Program, Ty, UniversalVar, Var, VarianceKind,
+    Atomic, Binder, ClassDecl, ClassDeclBoundData, ClassPredicate, DropBody, FieldDecl, Kind,
+    MethodDecl, NamedTy, Parameter, Perm, Predicate, Program, Ty, TypeName, UniversalVar, Var,
+    VarianceKind,
 };
 
 use super::{
     env::Env,
-    expressions::can_type_expr_as,
+    expressions::type_expr_as,
     liveness::LivePlaces,
     methods::check_method,
-    predicates::{check_predicates, prove_predicate},
+    predicates::{check_predicates, prove_is_droppable, prove_predicate},
+    some_output,
     types::check_type,
 };
 
@@ -25,7 +28,7 @@ judgment_fn! {
         debug(decl, program)
 
         (
-            (let ClassDecl { class_predicate, name, binder } = decl)
+            (let ClassDecl { boxed: _, class_predicate, name, binder } = decl)
             (let env = Env::new(program))
 
             (let (env, substitution, ClassDeclBoundData { predicates, fields, methods, drop_body }) =
@@ -33,6 +36,8 @@ judgment_fn! {
 
             (let class_ty = NamedTy::new(name, substitution))
 
+            (let () = check_finite_size(&env, &class_ty)?)
+
             (let env = env.add_assumptions(predicates))
 
             (check_predicates(env, predicates) => ())
@@ -41,9 +46,11 @@ judgment_fn! {
                 (check_field(class_ty, env, substitution, class_predicate, field) => ()))
 
             (for_all(method in methods)
-                (check_method(class_ty, env, method) => ()))
+                (check_method(class_ty, env, method) => _))
+
+            (let () = check_consuming_method(&class_ty, &class_predicate, &methods)?)
 
-            (check_drop_body(class_ty, class_predicate, env, drop_body) => ())
+            (check_drop_body(class_ty, class_predicate, env, drop_body) => _)
 
             ----------------------------------- ("check_class")
             (check_class(program, decl) => ())
@@ -52,28 +59,62 @@ judgment_fn! {
 }
 // ANCHOR_END: check_class
 
+/// Returns `decl` with the parameters inferred in its methods and drop body written out,
+/// checking them in the environment that [`check_class`] sets up.
+pub fn elaborate_class(program: &Arc<Program>, decl: &ClassDecl) -> Fallible<ClassDecl> {
+    let env = Env::new(program);
+    let (env, substitution, data) = env.open_universally(&decl.binder);
+    let class_ty = NamedTy::new(&decl.name, &substitution);
+    let env = env.add_assumptions(&data.predicates);
+    let methods = data
+        .methods
+        .iter()
+        .map(|method| some_output(check_method(&class_ty, &env, method)))
+        .collect::<Fallible<_>>()?;
+    let drop_body = some_output(check_drop_body(
+        &class_ty,
+        decl.class_predicate,
+        &env,
+        &data.drop_body,
+    ))?;
+    Ok(ClassDecl {
+        binder: Binder::new(
+            substitution,
+            ClassDeclBoundData {
+                methods,
+                drop_body,
+                ..data
+            },
+        ),
+        ..decl.clone()
+    })
+}
+
 judgment_fn! {
+    /// Check the drop body of a class, returning it with the parameters inferred
+    /// within it written out (see `elaborate_program`).
     fn check_drop_body(
         class_ty: NamedTy,
         class_predicate: ClassPredicate,
         env: Env,
         drop_body: DropBody,
-    ) => () {
+    ) => DropBody {
         debug(drop_body, class_ty, class_predicate, env)
 
         // Empty drop body — nothing to check.
         (
             (if drop_body.block.statements.is_empty())!
             ----------------------------------- ("empty_drop")
-            (check_drop_body(_class_ty, _class_predicate, _env, drop_body) => ())
+            (check_drop_body(_class_ty, _class_predicate, _env, drop_body) => drop_body)
         )
 
         // Given class: self has type `given Class[...]`.
         (
             (let env = env.push_local_variable(Var::This, class_ty)?)
-            (can_type_expr_as(env, LivePlaces::default(), &drop_body.block, Ty::unit()) => ())
+            (type_expr_as(env, LivePlaces::default(), &drop_body.block, Ty::unit()) => env)
+            (let block = env.elaborate_block(&drop_body.block)?)
             ----------------------------------- ("given_class_drop")
-            (check_drop_body(class_ty, ClassPredicate::Given, env, drop_body) => ())
+            (check_drop_body(class_ty, ClassPredicate::Given, env, drop_body) => DropBody { block: block.clone() })
         )
 
         // Share or Shared class: introduce a universal perm variable P with `P is ref` assumed,
@@ -85,16 +126,20 @@ judgment_fn! {
             )]))
             (let self_ty: Ty = Ty::apply_perm(Perm::var(perm_var), class_ty))
             (let env = env.push_local_variable(Var::This, self_ty)?)
-            (can_type_expr_as(env, LivePlaces::default(), &drop_body.block, Ty::unit()) => ())
+            (type_expr_as(env, LivePlaces::default(), &drop_body.block, Ty::unit()) => env)
+
+            // `P` is only in scope here, so no parameter inferred in terms of it could be written out.
+            (let () = env.elaborations_independent_of(perm_var)?)
+            (let block = env.elaborate_block(&drop_body.block)?)
             ----------------------------------- ("share_class_drop")
-            (check_drop_body(class_ty, ClassPredicate::Share | ClassPredicate::Shared, env, drop_body) => ())
+            (check_drop_body(class_ty, ClassPredicate::Share | ClassPredicate::Shared, env, drop_body) => DropBody { block: block.clone() })
         )
     }
 }
 
 // ANCHOR: check_field
 judgment_fn! {
-    fn check_field(
+    pub fn check_field(
         class_ty: NamedTy,
         env: Env,
         class_substitution: Vec<UniversalVar>,
@@ -135,6 +180,16 @@ judgment_fn! {
                 Ok::<_, anyhow::Error>(())
             }?)
 
+            // Only a tracked class (or enum) can own a value of a tracked class,
+            // as the others can be dropped implicitly.
+            (let () = match class_predicate {
+                ClassPredicate::Tracked => Ok::<_, anyhow::Error>(()),
+                _ => {
+                    let ((), _) = prove_is_droppable(env, ty).into_singleton()?;
+                    Ok(())
+                }
+            }?)
+
             (let () = match atomic {
                 Atomic::No => Ok::<_, anyhow::Error>(()),
                 Atomic::Yes => {
@@ -150,6 +205,118 @@ judgment_fn! {
 }
 // ANCHOR_END: check_field
 
+/// Fails if a value of `class_ty` would contain another value of the same class
+/// inline, i.e., without a boxed type (such as `Array` or a `boxed class`) in between,
+/// since such a value would have no finite size.
+fn check_finite_size(env: &Env, class_ty: &NamedTy) -> Fallible<()> {
+    let NamedTy { name, parameters } = class_ty;
+    for ty in inline_field_tys(env, name, parameters)? {
+        check_inline_ty(env, &class_ty.name, &ty, &mut vec![name.clone()])?;
+    }
+    Ok(())
+}
+
+/// Walks the types stored inline in a value of type `ty`. `path` holds the names of the
+/// types we are currently inside of: finding one of them again is a cycle, which is an
+/// error if it leads back to `class_name` (other cycles are reported when checking their class).
+fn check_inline_ty(
+    env: &Env,
+    class_name: &TypeName,
+    ty: &Ty,
+    path: &mut Vec<TypeName>,
+) -> Fallible<()> {
+    let NamedTy { name, parameters } = match ty {
+        Ty::NamedTy(named_ty) => named_ty,
+        Ty::ApplyPerm(_, ty) => return check_inline_ty(env, class_name, ty, path),
+        Ty::Var(_) => return Ok(()),
+    };
+
+    if env.is_boxed_ty(name)? {
+        return Ok(());
+    }
+
+    if name == class_name {
+        bail!(
+            "class `{:?}` contains itself without indirection; declare it as a `boxed class`",
+            class_name
+        );
+    }
+
+    if path.contains(name) {
+        return Ok(());
+    }
+
+    path.push(name.clone());
+    for ty in inline_field_tys(env, name, parameters)? {
+        check_inline_ty(env, class_name, &ty, path)?;
+    }
+    path.pop();
+    Ok(())
+}
+
+/// The types of the fields of a value of type `name[parameters]`,
+/// or of the fields of all variants if it is an enum.
+fn inline_field_tys(env: &Env, name: &TypeName, parameters: &[Parameter]) -> Fallible<Vec<Ty>> {
+    Ok(match name {
+        // The values captured by a future are held by the executor, not inline,
+        // and those captured by a closure are held in its environment.
+        TypeName::Int
+        | TypeName::Bool
+        | TypeName::Char
+        | TypeName::String
+        | TypeName::Array
+        | TypeName::Future
+        | TypeName::Closure(_) => vec![],
+        TypeName::Tuple(_) => parameters
+            .iter()
+            .filter_map(|p| p.as_ty())
+            .cloned()
+            .collect(),
+        TypeName::Id(id) => match env.program().enum_named(id) {
+            Ok(enum_decl) => enum_decl
+                .binder
+                .instantiate_with(parameters)?
+                .variants
+                .into_iter()
+                .flat_map(|variant| variant.fields)
+                .map(|field| field.ty)
+                .collect(),
+            Err(_) => env
+                .program()
+                .class_named(id)?
+                .binder
+                .instantiate_with(parameters)?
+                .fields
+                .into_iter()
+                .map(|field| field.ty)
+                .collect(),
+        },
+    })
+}
+
+/// A `tracked class` must declare a method taking `given self`,
+/// since giving its values to one of those is the only way to consume them.
+fn check_consuming_method(
+    class_ty: &NamedTy,
+    class_predicate: &ClassPredicate,
+    methods: &[MethodDecl],
+) -> Fallible<()> {
+    if *class_predicate != ClassPredicate::Tracked {
+        return Ok(());
+    }
+    let consumes = methods.iter().any(|method| {
+        let (_, data) = method.binder.open();
+        data.this.perm == Perm::Given
+    });
+    if !consumes {
+        bail!(
+            "tracked class `{:?}` has no method taking `given self` to consume its values",
+            class_ty.name
+        );
+    }
+    Ok(())
+}
+
 impl ClassDecl {
     /// Compute, for each generic parameter of this class,
     /// the relevant variance declarations.
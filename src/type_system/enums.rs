@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use formality_core::{judgment_fn, Fallible};
+
+use crate::grammar::{
+    ClassPredicate, EnumDecl, EnumDeclBoundData, LocalVariableDecl, MatchArm, NamedTy, Predicate,
+    Program, Ty, TypeName, UniversalVar, Var, VarianceKind, VariantDecl,
+};
+
+use super::{classes::check_field, env::Env, in_flight::InFlight, predicates::check_predicates};
+
+// ANCHOR: check_enum
+judgment_fn! {
+    pub fn check_enum(
+        program: Arc<Program>,
+        decl: EnumDecl,
+    ) => () {
+        debug(decl, program)
+
+        (
+            (let EnumDecl { class_predicate, name, binder } = decl)
+            (let env = Env::new(program))
+
+            (let (env, substitution, EnumDeclBoundData { predicates, variants }) =
+                env.open_universally(binder))
+
+            (let enum_ty = NamedTy::new(name, substitution))
+
+            (let env = env.add_assumptions(predicates))
+
+            (check_predicates(env, predicates) => ())
+
+            (let () = check_variant_names(&variants)?)
+
+            (for_all(variant in variants)
+                (check_variant(enum_ty, env, substitution, class_predicate, variant) => ()))
+
+            ----------------------------------- ("check_enum")
+            (check_enum(program, decl) => ())
+        )
+    }
+}
+// ANCHOR_END: check_enum
+
+judgment_fn! {
+    fn check_variant(
+        enum_ty: NamedTy,
+        env: Env,
+        enum_substitution: Vec<UniversalVar>,
+        class_predicate: ClassPredicate,
+        decl: VariantDecl,
+    ) => () {
+        debug(decl, enum_ty, class_predicate, env)
+
+        // The fields of each variant are checked just like the fields of a class,
+        // so e.g. a `shared enum` can only carry shared values.
+        (
+            (let VariantDecl { name: _, fields } = decl)
+            (for_all(field in fields)
+                (check_field(enum_ty, env, enum_substitution, class_predicate, field) => ()))
+            ----------------------------------- ("check_variant")
+            (check_variant(enum_ty, env, enum_substitution, class_predicate, decl) => ())
+        )
+    }
+}
+
+fn check_variant_names(variants: &[VariantDecl]) -> Fallible<()> {
+    for (index, variant) in variants.iter().enumerate() {
+        if variants[..index].iter().any(|v| v.name == variant.name) {
+            bail!("variant `{:?}` is declared more than once", variant.name);
+        }
+    }
+    Ok(())
+}
+
+impl EnumDecl {
+    /// Compute, for each generic parameter of this enum,
+    /// the relevant variance declarations (see [`crate::grammar::ClassDecl::variances`]).
+    pub fn variances(&self) -> Vec<Vec<VarianceKind>> {
+        let (
+            bound_vars,
+            EnumDeclBoundData {
+                predicates,
+                variants: _,
+            },
+        ) = self.binder.open();
+
+        bound_vars
+            .iter()
+            .map(|v| {
+                predicates
+                    .iter()
+                    .filter_map(|p| match p {
+                        Predicate::Variance(kind, parameter) if parameter.is_var(v) => Some(*kind),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Env {
+    /// Check that `arms` are an exhaustive match on a value of type `ty`:
+    /// `ty` must be an enum and each of its variants must be matched by exactly one arm,
+    /// which binds each of the variant's fields.
+    pub fn check_match_arms(&self, ty: &Ty, arms: &[MatchArm]) -> Fallible<()> {
+        let Some(NamedTy {
+            name: TypeName::Id(name),
+            parameters,
+        }) = ty.to_named_ty()
+        else {
+            bail!("cannot match on a value of type `{ty:?}`");
+        };
+        let enum_decl = self.program().enum_named(&name)?;
+        let data = enum_decl.binder.instantiate_with(&parameters)?;
+
+        for (index, arm) in arms.iter().enumerate() {
+            let (_, variant) = data.variant_named(&arm.variant)?;
+            if arms[..index].iter().any(|a| a.variant == arm.variant) {
+                bail!("variant `{:?}` is matched more than once", arm.variant);
+            }
+            if arm.bindings.len() != variant.fields.len() {
+                bail!(
+                    "variant `{:?}` has {} field(s) but the arm binds {}",
+                    arm.variant,
+                    variant.fields.len(),
+                    arm.bindings.len(),
+                );
+            }
+        }
+
+        for variant in &data.variants {
+            if !arms.iter().any(|a| a.variant == variant.name) {
+                bail!("non-exhaustive match: variant `{:?}` is not covered", variant.name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Bring the bindings of `arm` into scope, where the value being matched is stored
+    /// in `scrutinee_var` and has type `scrutinee_ty`. Each binding has the type of the
+    /// corresponding field of the variant with the permissions of the scrutinee applied,
+    /// so matching on a `ref[x] Option[T]` yields `ref[x] T` bindings while matching on
+    /// a `given Option[T]` moves the payload into a `given T` binding.
+    /// Returns the new environment and the bound variables.
+    pub fn push_match_bindings(
+        &self,
+        scrutinee_var: &Var,
+        scrutinee_ty: &Ty,
+        arm: &MatchArm,
+    ) -> Fallible<(Env, Vec<Var>)> {
+        let fields = self.variant_fields(scrutinee_ty, &arm.variant)?;
+        let decls: Vec<LocalVariableDecl> = arm
+            .bindings
+            .iter()
+            .zip(fields)
+            .map(|(name, field)| LocalVariableDecl {
+                name: name.clone(),
+                ty: field.ty.with_this_stored_to(scrutinee_var.clone()),
+            })
+            .collect();
+        let env = self.push_local_variable_decls(&decls)?;
+        let vars = arm.bindings.iter().map(|name| Var::Id(name.clone())).collect();
+        Ok((env, vars))
+    }
+}
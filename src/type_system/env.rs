@@ -1,6 +1,6 @@
-use std::sync::Arc;
+use std::{cell::Cell, sync::Arc};
 
-use anyhow::bail;
+use anyhow::{bail, Context};
 use formality_core::{set, term, Fallible, Map, Set, To, Upcast};
 
 use crate::{
@@ -9,12 +9,18 @@ use crate::{
         Term,
     },
     grammar::{
-        ClassPredicate, Kind, LocalVariableDecl, ParameterPredicate, Predicate, Program, Ty,
-        TypeName, Var, VarianceKind,
+        Boxed, ClassPredicate, Expr, Kind, LocalVariableDecl, NamedTy, Parameter,
+        ParameterPredicate, Perm, Place, Predicate, Program, Ty, TypeName, ValueId, Var,
+        VarianceKind,
     },
 };
 
-use super::in_flight::{InFlight, Transform};
+use super::{
+    in_flight::{InFlight, Transform},
+    inference::{written_parameters, Elaborations},
+    join::{check_joined_ty, join_tys},
+    liveness::LivePlaces,
+};
 
 // ANCHOR: Env
 #[derive(Clone, Ord, Eq, PartialEq, PartialOrd, Hash)]
@@ -25,9 +31,79 @@ pub struct Env {
     local_variables: Map<Var, Ty>,
     assumptions: Set<Predicate>,
     fresh: usize,
+    output_ty: Option<Ty>,
+    in_async: bool,
+    elaborations: Elaborations,
+
+    /// Places given away and not yet reassigned (see [`Env::check_consistency`]).
+    moved: MovedPlaces,
+
+    /// Inside of a `loop`, where control goes on a `break` (see [`Env::with_break`]).
+    loop_exit: Option<Arc<LoopExit>>,
 }
 // ANCHOR_END: Env
 
+/// The places given away and not yet reassigned. These are only consulted by
+/// [`Env::check_consistency`], so they are ignored when comparing environments:
+/// otherwise, checking consistency could change how typing proceeds, e.g., how many
+/// times the body of a loop is typed before reaching a fixed point.
+#[derive(Clone, Default)]
+struct MovedPlaces(Set<Place>);
+
+impl PartialEq for MovedPlaces {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for MovedPlaces {}
+
+impl PartialOrd for MovedPlaces {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MovedPlaces {
+    fn cmp(&self, _other: &Self) -> std::cmp::Ordering {
+        std::cmp::Ordering::Equal
+    }
+}
+
+impl std::hash::Hash for MovedPlaces {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl std::ops::Deref for MovedPlaces {
+    type Target = Set<Place>;
+
+    fn deref(&self) -> &Set<Place> {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for MovedPlaces {
+    fn deref_mut(&mut self) -> &mut Set<Place> {
+        &mut self.0
+    }
+}
+
+impl FromIterator<Place> for MovedPlaces {
+    fn from_iter<I: IntoIterator<Item = Place>>(iter: I) -> Self {
+        MovedPlaces(iter.into_iter().collect())
+    }
+}
+
+/// The exit of the innermost enclosing `loop`.
+#[derive(Clone, Ord, Eq, PartialEq, PartialOrd, Hash)]
+struct LoopExit {
+    /// The environment on entry to the loop; only its local variables remain in scope after it.
+    entry: Env,
+
+    /// The environments at the `break`s typed so far, joined, if there are any.
+    after: Option<Env>,
+}
+
 #[term]
 #[derive(Copy)]
 pub struct Universe(usize);
@@ -43,15 +119,26 @@ impl Env {
             local_variables: Default::default(),
             assumptions: set![],
             fresh: 0,
+            output_ty: None,
+            in_async: false,
+            elaborations: Default::default(),
+            moved: MovedPlaces::default(),
+            loop_exit: None,
         }
     }
 
     pub fn variances(&self, type_name: &TypeName) -> Fallible<Vec<Vec<VarianceKind>>> {
         match type_name {
             TypeName::Tuple(n) => Ok(vec![vec![]; *n]),
-            TypeName::Int | TypeName::Bool => Ok(vec![]),
+            TypeName::Int | TypeName::Bool | TypeName::Char | TypeName::String => Ok(vec![]),
             TypeName::Array => Ok(vec![vec![]]), // 1 type parameter, no variance constraints
-            TypeName::Id(name) => Ok(self.program.class_named(name)?.variances()),
+            TypeName::Future => Ok(vec![vec![], vec![]]), // output and captured values
+            // Inputs are contravariant, which cannot be expressed, so they are invariant.
+            TypeName::Closure(_) => Ok(vec![vec![VarianceKind::Relative], vec![], vec![]]),
+            TypeName::Id(name) => match self.program.enum_named(name) {
+                Ok(enum_decl) => Ok(enum_decl.variances()),
+                Err(_) => Ok(self.program.class_named(name)?.variances()),
+            },
         }
     }
 
@@ -71,22 +158,139 @@ impl Env {
         self.assumptions.contains(&Predicate::parameter(k, v))
     }
 
+    /// The traits `ty` is assumed to implement, i.e., those with a `ty is Trait` assumption.
+    pub fn assumed_traits(&self, ty: &Ty) -> Vec<ValueId> {
+        self.assumptions
+            .iter()
+            .filter_map(|predicate| match predicate {
+                Predicate::Implements(trait_name, Parameter::Ty(t)) if t == ty => {
+                    Some(trait_name.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Record `output_ty` as the declared output type of the method being checked,
+    /// against which `return` statements are typed.
+    pub fn with_output_ty(&self, output_ty: impl Upcast<Ty>) -> Env {
+        let mut env = self.clone();
+        env.output_ty = Some(output_ty.upcast());
+        env
+    }
+
+    /// The declared output type of the method being checked.
+    pub fn output_ty(&self) -> Fallible<&Ty> {
+        match &self.output_ty {
+            Some(ty) => Ok(ty),
+            None => bail!("`return` outside of a method body"),
+        }
+    }
+
+    /// Record that the body being checked is that of an `async fn`,
+    /// in which futures can be `await`ed.
+    pub fn with_async_body(&self) -> Env {
+        let mut env = self.clone();
+        env.in_async = true;
+        env
+    }
+
+    /// Record that the body being checked is that of a closure returning `output_ty`:
+    /// `return` statements are typed against it, futures cannot be `await`ed,
+    /// and no `break` can leave it.
+    pub fn with_closure_body(&self, output_ty: impl Upcast<Ty>) -> Env {
+        let mut env = self.with_output_ty(output_ty);
+        env.in_async = false;
+        env.loop_exit = None;
+        env
+    }
+
+    /// Record `parameters` as the generic parameters of `expr`, a use of a generic,
+    /// if they were inferred rather than written in `expr` (see `elaborate_program`).
+    pub fn with_elaboration(&self, expr: &Expr, parameters: &[Parameter]) -> Env {
+        if written_parameters(expr) == parameters {
+            return self.clone();
+        }
+        let mut env = self.clone();
+        env.elaborations
+            .entry(expr.clone())
+            .or_default()
+            .insert(parameters.to_vec());
+        env
+    }
+
+    /// Returns a copy of `self` that also records the parameters inferred in `other`,
+    /// e.g., the body of a closure, which is checked in an environment of its own.
+    pub fn with_elaborations_of(&self, other: &Env) -> Env {
+        let mut env = self.clone();
+        for (expr, parameters) in &other.elaborations {
+            env.elaborations
+                .entry(expr.clone())
+                .or_default()
+                .extend(parameters.iter().cloned());
+        }
+        env
+    }
+
+    /// The parameters inferred so far, by the expression they were inferred for.
+    pub fn elaborations(&self) -> &Elaborations {
+        &self.elaborations
+    }
+
+    /// Ok unless one of the parameters inferred so far mentions `var`, a variable
+    /// that is in scope only while checking and so cannot be written into the program.
+    pub fn elaborations_independent_of(&self, var: impl Upcast<Variable>) -> Fallible<()> {
+        let var: Variable = var.upcast();
+        for (expr, inferred) in &self.elaborations {
+            let mut variables = vec![];
+            let mut places = vec![];
+            for parameter in inferred.iter().flatten() {
+                match parameter {
+                    Parameter::Ty(ty) => mentions_in_ty(ty, &mut variables, &mut places),
+                    Parameter::Perm(perm) => mentions_in_perm(perm, &mut variables, &mut places),
+                }
+            }
+            if variables.contains(&var) {
+                bail!(
+                    "the parameters inferred for `{expr:?}` refer to `{var:?}`, \
+                     they must be given explicitly"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Ok if futures can be `await`ed here (see [`Env::with_async_body`]).
+    pub fn await_permitted(&self) -> Fallible<()> {
+        if !self.in_async {
+            bail!("`await` outside of an `async fn`");
+        }
+        Ok(())
+    }
+
     pub fn program(&self) -> &Program {
         &self.program
     }
 
     /// True if the given type name meets the given class predicate.
     /// Tuples/ids are value types and hence meet all predicates.
-    /// Classes meet the predicates they are declared to meet.
+    /// Classes and enums meet the predicates they are declared to meet.
     pub fn meets_class_predicate(
         &self,
         name: &TypeName,
         class_predicate: ClassPredicate,
     ) -> Fallible<bool> {
         let cp_for_name = match name {
-            TypeName::Tuple(_) | TypeName::Int | TypeName::Bool => ClassPredicate::Shared,
-            TypeName::Array => ClassPredicate::Share, // Array is a share class
-            TypeName::Id(n) => self.program.class_named(n)?.class_predicate,
+            TypeName::Tuple(_) | TypeName::Int | TypeName::Bool | TypeName::Char => {
+                ClassPredicate::Shared
+            }
+            TypeName::Array | TypeName::String => ClassPredicate::Share, // share classes
+            TypeName::Future => ClassPredicate::Given, // awaiting a future consumes it
+            TypeName::Closure(_) => ClassPredicate::Given, // it may hold unique captures
+            TypeName::Id(n) => match self.program.enum_named(n) {
+                Ok(enum_decl) => enum_decl.class_predicate,
+                Err(_) => self.program.class_named(n)?.class_predicate,
+            },
         };
         Ok(class_predicate <= cp_for_name)
     }
@@ -96,6 +300,84 @@ impl Env {
         self.meets_class_predicate(name, ClassPredicate::Shared)
     }
 
+    /// True if values of the given type name are stored in the heap behind a
+    /// `[Flags, Pointer]` pair: `Array`, `String`, and classes declared `boxed class`.
+    pub fn is_boxed_ty(&self, name: &TypeName) -> Fallible<bool> {
+        match name {
+            TypeName::Tuple(_)
+            | TypeName::Int
+            | TypeName::Bool
+            | TypeName::Char
+            | TypeName::Future
+            | TypeName::Closure(_) => Ok(false),
+            TypeName::Array | TypeName::String => Ok(true),
+            TypeName::Id(n) => match self.program.enum_named(n) {
+                Ok(_) => Ok(false),
+                Err(_) => Ok(self.program.class_named(n)?.boxed == Boxed::Yes),
+            },
+        }
+    }
+
+    /// True if the given type name is declared as a `tracked class` (or enum).
+    pub fn is_tracked_ty(&self, name: &TypeName) -> Fallible<bool> {
+        match name {
+            TypeName::Tuple(_)
+            | TypeName::Int
+            | TypeName::Bool
+            | TypeName::Char
+            | TypeName::String
+            | TypeName::Array
+            | TypeName::Future
+            | TypeName::Closure(_) => Ok(false),
+            TypeName::Id(n) => match self.program.enum_named(n) {
+                Ok(enum_decl) => Ok(enum_decl.class_predicate == ClassPredicate::Tracked),
+                Err(_) => {
+                    Ok(self.program.class_named(n)?.class_predicate == ClassPredicate::Tracked)
+                }
+            },
+        }
+    }
+
+    /// True if a value of type `ty` may own a value of a tracked type (see [`Env::is_tracked_ty`]),
+    /// either directly or through its type parameters.
+    /// Borrowed values (`ref[..]` or `mut[..]`) own nothing. Type variables are not tracked,
+    /// since a tracked type is never a type argument (see [`Env::check_type_arguments_untracked`]).
+    pub fn owns_tracked_value(&self, ty: &Ty) -> Fallible<bool> {
+        match ty {
+            Ty::NamedTy(NamedTy { name, parameters }) => {
+                if self.is_tracked_ty(name)? {
+                    return Ok(true);
+                }
+                for parameter in parameters {
+                    if let Parameter::Ty(ty) = parameter {
+                        if self.owns_tracked_value(ty)? {
+                            return Ok(true);
+                        }
+                    }
+                }
+                Ok(false)
+            }
+            Ty::Var(_) => Ok(false),
+            Ty::ApplyPerm(Perm::Rf(_) | Perm::Mt(_), _) => Ok(false),
+            Ty::ApplyPerm(_, ty) => self.owns_tracked_value(ty),
+        }
+    }
+
+    /// Generic code may drop the values of its type parameters, so the type arguments
+    /// `parameters` of a class, enum, method or function must not own a tracked value.
+    pub fn check_type_arguments_untracked(&self, parameters: &[Parameter]) -> Fallible<()> {
+        for parameter in parameters {
+            if let Parameter::Ty(ty) = parameter {
+                if self.owns_tracked_value(ty)? {
+                    bail!(
+                        "type argument `{ty:?}` owns a tracked value, which generic code may drop"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Check that the variable is in the environment.
     /// This should always be true, especially because the
     /// parser is aware of in-scope variable names as it parses,
@@ -108,7 +390,7 @@ impl Env {
                 self.in_scope_vars.contains(&v) && var_index.index < self.universe.0
             }
 
-            Variable::ExistentialVar(ExistentialVar { .. }) => false,
+            Variable::ExistentialVar(ExistentialVar { .. }) => self.in_scope_vars.contains(&v),
 
             Variable::BoundVar(_) => true,
         }
@@ -135,6 +417,22 @@ impl Env {
         var
     }
 
+    /// Create a fresh existential variable of kind `kind`,
+    /// standing for a generic parameter that is to be inferred.
+    fn push_next_existential_var(&mut self, kind: Kind) -> ExistentialVar {
+        let index = self
+            .in_scope_vars
+            .iter()
+            .filter(|v| matches!(v, Variable::ExistentialVar(_)))
+            .count();
+        let var = ExistentialVar {
+            kind,
+            var_index: VarIndex { index },
+        };
+        self.in_scope_vars.push(var.to());
+        var
+    }
+
     /// Create a fresh universal perm variable. Returns the updated env and the variable.
     pub fn open_universal_perm_var(&self) -> (Env, UniversalVar) {
         let mut env = self.clone();
@@ -157,6 +455,23 @@ impl Env {
         (env, universal_vars, result)
     }
 
+    /// Replace all the bound variables in `b` with fresh existential variables
+    /// and return the contents (see `inference.rs`). The variables are not brought
+    /// into scope: they stand for the parameters being inferred, which are solved
+    /// by unification before anything mentioning them leaves `inference.rs`.
+    pub fn open_existentially<T: Term>(&self, b: &Binder<T>) -> Fallible<(Vec<ExistentialVar>, T)> {
+        let mut env = self.clone();
+        let existential_vars: Vec<_> = b
+            .kinds()
+            .iter()
+            .map(|&k| env.push_next_existential_var(k))
+            .collect();
+
+        let result = b.instantiate_with(&existential_vars)?;
+
+        Ok((existential_vars, result))
+    }
+
     /// Introduces multiple program variables into scope, failing if this would introduce shadowing.
     pub fn push_local_variable_decls(&self, decls: &[LocalVariableDecl]) -> Fallible<Env> {
         let mut env = self.clone();
@@ -218,16 +533,321 @@ impl Env {
         env
     }
 
+    /// Returns a copy of `self` that retains only the local variables also in scope in `env`.
+    /// Used when control flows back to a point (e.g., a loop head) where `env` was the environment.
+    /// The fresh counter is reset to that of `env`, since any temporaries created since then
+    /// are no longer in scope.
+    pub fn retain_local_variables_of(&self, env: &Env) -> Env {
+        let mut result = self.clone();
+        result
+            .local_variables
+            .retain(|var, _| env.local_variables.contains_key(var));
+        result
+            .moved
+            .retain(|place| env.local_variables.contains_key(&place.var));
+        result.fresh = env.fresh;
+        result
+    }
+
+    /// The environment after an `if` whose branches were both typed starting from `before`
+    /// and ended in `self` and `other` respectively. Only the variables of `before` remain in scope.
+    /// Places moved in either branch need no special treatment: both branches are typed
+    /// against the places live after the `if`, so a place moved in one branch is already
+    /// known to be dead afterwards, and it is recorded as moved after the `if`.
+    /// What can differ is the types of variables whose permissions were renamed by moves
+    /// within a branch; these are joined with `or(..)`.
+    pub fn join(&self, other: &Env, before: &Env) -> Fallible<Env> {
+        let mut env = before
+            .with_elaborations_of(self)
+            .with_elaborations_of(other);
+        env.moved = self
+            .moved
+            .union(&other.moved)
+            .filter(|place| env.local_variables.contains_key(&place.var))
+            .cloned()
+            .collect();
+        env.assumptions = self
+            .assumptions
+            .intersection(&other.assumptions)
+            .cloned()
+            .collect();
+        env.loop_exit = join_loop_exits(&self.loop_exit, &other.loop_exit)?;
+        for (var, ty) in env.local_variables.iter_mut() {
+            *ty = join_tys(self.var_ty(var.clone())?, other.var_ty(var.clone())?)?;
+        }
+        Ok(env)
+    }
+
+    /// Returns a copy of `self` that also records what happened in `other`, a branch
+    /// that never completes (e.g., one ending in `return` or `break`): the parameters
+    /// inferred within it and the environments at its `break`s.
+    pub fn with_diverging_branch(&self, other: &Env) -> Fallible<Env> {
+        let mut env = self.with_elaborations_of(other);
+        env.loop_exit = join_loop_exits(&self.loop_exit, &other.loop_exit)?;
+        Ok(env)
+    }
+
+    /// The environment at the head of a loop entered from `self`, before any `break` in it.
+    pub fn entering_loop(&self) -> Env {
+        let mut env = self.clone();
+        env.loop_exit = Some(Arc::new(LoopExit {
+            entry: self.clone(),
+            after: None,
+        }));
+        env
+    }
+
+    /// Returns a copy of `self` at the head of the innermost enclosing loop,
+    /// forgetting the `break`s typed since the loop was entered.
+    pub fn at_loop_head(&self) -> Env {
+        let mut env = self.clone();
+        if let Some(loop_exit) = &self.loop_exit {
+            env.loop_exit = Some(Arc::new(LoopExit {
+                entry: loop_exit.entry.clone(),
+                after: None,
+            }));
+        }
+        env
+    }
+
+    /// Records a `break` from the innermost enclosing loop with the environment `self`,
+    /// where `live_after_loop` are the places live after the loop.
+    /// The variables declared within the loop go out of scope, as at the end of a block,
+    /// and the result is joined with the environments at the other `break`s.
+    pub fn with_break(&self, live_after_loop: &LivePlaces) -> Fallible<Env> {
+        let Some(loop_exit) = &self.loop_exit else {
+            bail!("`break` outside of a loop");
+        };
+        let popped_vars = self.local_variables_not_in(&loop_exit.entry);
+        let (mut env_break, _) =
+            self.pop_local_variables_normalizing(live_after_loop, &popped_vars, &Ty::unit())?;
+        env_break.fresh = loop_exit.entry.fresh;
+        env_break.loop_exit = loop_exit.entry.loop_exit.clone();
+        let after = match &loop_exit.after {
+            Some(after) => after.join(&env_break, after)?,
+            None => env_break,
+        };
+
+        let mut env = self.clone();
+        env.loop_exit = Some(Arc::new(LoopExit {
+            entry: loop_exit.entry.clone(),
+            after: Some(after),
+        }));
+        Ok(env)
+    }
+
+    /// The environment after the innermost enclosing loop, whose body ended in `self`:
+    /// the join of the environments at its `break`s. A loop without a `break` never
+    /// completes, so whatever follows it is unreachable and is typed with `self`.
+    pub fn after_loop(&self) -> Env {
+        let Some(loop_exit) = &self.loop_exit else {
+            return self.clone();
+        };
+        let mut env = match &loop_exit.after {
+            Some(after) => after.with_elaborations_of(self),
+            None => self.clone(),
+        };
+        env.loop_exit = loop_exit.entry.loop_exit.clone();
+        env
+    }
+
+    /// The local variables in scope, e.g., the `self` and inputs of a method on entry to its body.
+    pub fn local_variable_names(&self) -> Vec<Var> {
+        self.local_variables.keys().cloned().collect()
+    }
+
+    /// The local variables in scope in `self` that were not in scope in `env`,
+    /// e.g., those declared within a block when `env` is the environment on entry to the block.
+    pub fn local_variables_not_in(&self, env: &Env) -> Vec<Var> {
+        self.local_variables
+            .keys()
+            .filter(|var| !env.local_variables.contains_key(var))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns a copy of `self` where the type of each local variable is replaced by `op(var, ty)`.
+    pub fn with_local_variable_tys(
+        &self,
+        mut op: impl FnMut(&Var, &Ty) -> Fallible<Ty>,
+    ) -> Fallible<Env> {
+        let mut env = self.clone();
+        for (var, ty) in env.local_variables.iter_mut() {
+            *ty = op(var, ty)?;
+        }
+        Ok(env)
+    }
+
+    /// Returns a copy of `self` without the local variables `vars` (see [`Env::pop_local_variables`]).
+    pub fn without_local_variables(&self, vars: impl Upcast<Vec<Var>>) -> Fallible<Env> {
+        let mut env = self.clone();
+        env.pop_local_variables(vars)?;
+        Ok(env)
+    }
+
     pub fn pop_local_variables(&mut self, vars: impl Upcast<Vec<Var>>) -> Fallible<()> {
         let vars: Vec<Var> = vars.upcast();
         for var in vars {
             if self.local_variables.remove(&var).is_none() {
                 bail!("local variable `{var:?}` not found in environment");
             }
+            self.moved.retain(|place| place.var != var);
+        }
+
+        Ok(())
+    }
+}
+
+impl Env {
+    /// Checks the invariants that typing maintains on the environment,
+    /// reporting the first violation found:
+    ///
+    /// * the fresh variables in scope are exactly `@fresh(0)` up to the fresh counter,
+    ///   as they are pushed and popped in stack order;
+    /// * the type of each variable live in `live_after` refers only to variables in scope
+    ///   (a lien on a place that went out of scope would violate this),
+    ///   where `@in_flight` counts as in scope since it stands for the value being produced;
+    /// * no place referenced by those types overlaps a place that was moved
+    ///   (giving a place away renames the liens on it to `@in_flight`);
+    /// * each `or(..)` permission in those types has branches of a single category.
+    ///
+    /// The types of dead variables are never consulted again and so are not checked.
+    pub fn check_consistency(&self, live_after: &LivePlaces) -> Fallible<()> {
+        for index in 0..self.fresh {
+            let var = Var::Fresh(index);
+            if !self.local_variables.contains_key(&var) {
+                bail!("fresh variable `{var:?}` is not in scope");
+            }
+        }
+
+        for (var, ty) in &self.local_variables {
+            if let Var::Fresh(index) = var {
+                if *index >= self.fresh {
+                    bail!("fresh variable `{var:?}` was not popped");
+                }
+            }
+
+            if !live_after.is_live(var) {
+                continue;
+            }
+
+            let mut variables = vec![];
+            let mut places = vec![];
+            mentions_in_ty(ty, &mut variables, &mut places);
+            for v in variables {
+                if !self.var_in_scope(&v) {
+                    bail!("type `{ty:?}` of `{var:?}` refers to `{v:?}`, which is not in scope");
+                }
+            }
+            for place in places {
+                if place.var != Var::InFlight && !self.local_variables.contains_key(&place.var) {
+                    bail!(
+                        "type `{ty:?}` of `{var:?}` refers to `{place:?}`, which is not in scope"
+                    );
+                }
+                if let Some(moved) = self.moved.iter().find(|m| m.is_overlapping_with(&place)) {
+                    bail!("type `{ty:?}` of `{var:?}` refers to `{place:?}`, but `{moved:?}` was moved");
+                }
+            }
+
+            check_joined_ty(self, ty).with_context(|| format!("type `{ty:?}` of `{var:?}`"))?;
         }
 
         Ok(())
     }
+
+    /// When consistency checks are enabled (see [`with_consistency_checks`]),
+    /// checks that `self`, produced by the judgment described by `judgment`, is consistent.
+    pub fn debug_check_consistency(
+        &self,
+        live_after: &LivePlaces,
+        judgment: impl FnOnce() -> String,
+    ) -> Fallible<()> {
+        if !CONSISTENCY_CHECKS.with(|enabled| enabled.get()) {
+            return Ok(());
+        }
+
+        self.check_consistency(live_after)
+            .with_context(|| format!("inconsistent environment after `{}`", judgment()))
+    }
+}
+
+thread_local! {
+    static CONSISTENCY_CHECKS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `op` with the environment checked for consistency (see [`Env::check_consistency`])
+/// after typing each statement and each expression typed against an expected type
+/// (`type_statement` and `type_expr_as`). Expressions typed by `type_expr` alone, such as
+/// the receiver of a call, are not checked on their own: their environment is checked as
+/// part of the enclosing statement or expression.
+/// The first of these judgments to produce an inconsistent environment fails with the violation.
+pub fn with_consistency_checks<R>(op: impl FnOnce() -> R) -> R {
+    /// Restores the previous setting when dropped, even if `op` panics.
+    struct Restore(bool);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CONSISTENCY_CHECKS.with(|enabled| enabled.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CONSISTENCY_CHECKS.with(|enabled| enabled.replace(true)));
+    op()
+}
+
+/// Joins the records of the `break`s typed along two branches (see [`Env::join`]).
+fn join_loop_exits(
+    a: &Option<Arc<LoopExit>>,
+    b: &Option<Arc<LoopExit>>,
+) -> Fallible<Option<Arc<LoopExit>>> {
+    let (Some(a), Some(b)) = (a, b) else {
+        return Ok(a.clone().or_else(|| b.clone()));
+    };
+    let after = match (&a.after, &b.after) {
+        (Some(after_a), Some(after_b)) => Some(after_a.join(after_b, after_a)?),
+        (after_a, after_b) => after_a.clone().or_else(|| after_b.clone()),
+    };
+    Ok(Some(Arc::new(LoopExit {
+        entry: a.entry.clone(),
+        after,
+    })))
+}
+
+/// Collects the type and permission variables and the places mentioned by `ty`.
+fn mentions_in_ty(ty: &Ty, variables: &mut Vec<Variable>, places: &mut Vec<Place>) {
+    match ty {
+        Ty::NamedTy(named_ty) => {
+            for parameter in &named_ty.parameters {
+                match parameter {
+                    Parameter::Ty(ty) => mentions_in_ty(ty, variables, places),
+                    Parameter::Perm(perm) => mentions_in_perm(perm, variables, places),
+                }
+            }
+        }
+        Ty::Var(v) => variables.push(v.clone()),
+        Ty::ApplyPerm(perm, ty) => {
+            mentions_in_perm(perm, variables, places);
+            mentions_in_ty(ty, variables, places);
+        }
+    }
+}
+
+fn mentions_in_perm(perm: &Perm, variables: &mut Vec<Variable>, places: &mut Vec<Place>) {
+    match perm {
+        Perm::Mv(ps) | Perm::Rf(ps) | Perm::Mt(ps) => places.extend(ps.iter().cloned()),
+        Perm::Var(v) => variables.push(v.clone()),
+        Perm::Apply(l, r) => {
+            mentions_in_perm(l, variables, places);
+            mentions_in_perm(r, variables, places);
+        }
+        Perm::Or(perms) => {
+            for perm in perms {
+                mentions_in_perm(perm, variables, places);
+            }
+        }
+        Perm::Given | Perm::Shared => {}
+    }
 }
 
 impl InFlight for Env {
@@ -239,6 +859,29 @@ impl InFlight for Env {
             local_variables: self.local_variables.with_places_transformed(transform),
             assumptions: self.assumptions.with_places_transformed(transform),
             fresh: self.fresh,
+            // The output type is expressed in terms of the method's declared inputs,
+            // which are not renamed by moves within the body.
+            output_ty: self.output_ty.clone(),
+            in_async: self.in_async,
+            // Inferred parameters are recorded as written in the program.
+            elaborations: self.elaborations.clone(),
+            moved: match transform {
+                Transform::Give(place) => {
+                    let mut moved = self.moved.clone();
+                    moved.insert(place.clone());
+                    moved
+                }
+                // Storing into a place reinitializes it.
+                Transform::Put(_, places) => self
+                    .moved
+                    .iter()
+                    .filter(|m| !places.iter().any(|place| place.is_prefix_of(m)))
+                    .cloned()
+                    .collect(),
+                Transform::Rename(..) => MovedPlaces(self.moved.with_places_transformed(transform)),
+            },
+            // The environments at each `break` were recorded when control left the loop.
+            loop_exit: self.loop_exit.clone(),
         }
     }
 }
@@ -1,41 +1,34 @@
-use formality_core::{judgment_fn, set, Cons};
+use std::sync::Arc;
+
+use anyhow::bail;
+use formality_core::{judgment_fn, set, Cons, Fallible, Set, Upcast};
 
 use crate::{
     grammar::{
-        Access, ClassDeclBoundData, Expr, FieldDecl, LocalVariableDecl, MethodDecl,
-        MethodDeclBoundData, MethodId, NamedTy, Parameter, Perm, Place, PlaceExpr, Predicate,
-        ThisDecl, Ty, TypeName, ValueId, Var,
+        Access, Async, BinaryOp, CallKind, ClassDeclBoundData, EnumDeclBoundData, Expr, FieldDecl,
+        FnDeclBoundData, ImplDeclBoundData, LocalVariableDecl, MatchArm, MethodDecl,
+        MethodDeclBoundData, MethodId, NamedTy, Parameter, Perm, Place, PlaceExpr, Predicate, Span,
+        ThisDecl, TraitDecl, Ty, TypeName, ValueId, Var,
     },
     type_system::{
-        accesses::{access_permitted, accesses_permitted},
+        accesses::{access_permitted, accesses_permitted, owners_permit_mutation},
         blocks::type_block,
         env::Env,
         in_flight::InFlight,
+        inference::{elaborate_parameters, Generic},
+        join::{check_joined_ty, join_tys},
         liveness::LivePlaces,
+        pop_normalize::normalize_output_for_pop,
         predicates::{
-            prove_is_copy, prove_is_move, prove_is_mut, prove_is_shareable, prove_predicates,
+            prove_is_copy, prove_is_copy_owned, prove_is_droppable, prove_is_move, prove_is_mut,
+            prove_is_shareable, prove_isnt_known_to_be_copy, prove_predicates,
         },
         subtypes::sub,
+        tracked::{branches_consume_tracked_values, place_not_abandoned, tracked_values_consumed},
+        types::check_type,
     },
 };
 
-judgment_fn! {
-    pub fn can_type_expr_as(
-        env: Env,
-        live_after: LivePlaces,
-        expr: Expr,
-        as_ty: Ty,
-    ) => () {
-        debug(expr, as_ty, env, live_after)
-
-        (
-            (type_expr_as(env, live_after, expr, as_ty) => _)
-            -------------------------------- ("can_type_expr_as")
-            (can_type_expr_as(env, live_after, expr, as_ty) => ())
-        )
-    }
-}
-
 judgment_fn! {
     pub fn type_expr_as(
         env: Env,
@@ -51,7 +44,18 @@ judgment_fn! {
         )
 
         (
+            // A block ending in `break` or `return` never produces a value,
+            // so it can be given any type.
+            (if block.diverges())!
+            (type_block(env, live_after, block) => (env, _ty))
+            -------------------------------- ("type_expr_as diverging block")
+            (type_expr_as(env, live_after, Expr::Block(block), _as_ty) => env)
+        )
+
+        (
+            (if !expr.diverges())!
             (type_expr(env, live_after, expr) => (env, ty))
+            (let () = env.debug_check_consistency(&live_after, || format!("type_expr({expr:?})"))?)
             (sub(env, live_after, ty, as_ty) => ())
             -------------------------------- ("type_expr_as")
             (type_expr_as(env, live_after, expr, as_ty) => env)
@@ -81,6 +85,16 @@ judgment_fn! {
             (type_expr(env, _live_after, Expr::Integer(_)) => (env, Ty::int()))
         )
 
+        (
+            ----------------------------------- ("string")
+            (type_expr(env, _live_after, Expr::String(_)) => (env, Ty::string()))
+        )
+
+        (
+            ----------------------------------- ("char")
+            (type_expr(env, _live_after, Expr::Char(_)) => (env, Ty::char()))
+        )
+
         (
             ----------------------------------- ("true")
             (type_expr(env, _live_after, Expr::True) => (env, Ty::bool()))
@@ -91,22 +105,43 @@ judgment_fn! {
             (type_expr(env, _live_after, Expr::False) => (env, Ty::bool()))
         )
 
-        // Arithmetic: Int × Int → Int
+        // The operators of each precedence level are typed alike, see `type_binary_op`.
         (
-            (if op.is_arithmetic())!
-            (type_expr_as(env, live_after.before(&**rhs), &**lhs, Ty::int()) => env)
-            (type_expr_as(env, live_after, &**rhs, Ty::int()) => env)
-            ----------------------------------- ("arithmetic")
-            (type_expr(env, live_after, Expr::BinaryOp(lhs, op, rhs)) => (env, Ty::int()))
+            (type_binary_op(env, live_after, lhs, &op.op, rhs) => (env, ty))
+            ----------------------------------- ("comparison operator")
+            (type_expr(env, live_after, Expr::Comparison(lhs, op, rhs)) => (env, ty))
         )
 
-        // Comparison: Int × Int → Bool
         (
-            (if op.is_comparison())!
-            (type_expr_as(env, live_after.before(&**rhs), &**lhs, Ty::int()) => env)
-            (type_expr_as(env, live_after, &**rhs, Ty::int()) => env)
-            ----------------------------------- ("comparison")
-            (type_expr(env, live_after, Expr::BinaryOp(lhs, op, rhs)) => (env, Ty::bool()))
+            (type_binary_op(env, live_after, lhs, &op.op, rhs) => (env, ty))
+            ----------------------------------- ("additive operator")
+            (type_expr(env, live_after, Expr::Additive(lhs, op, rhs)) => (env, ty))
+        )
+
+        (
+            (type_binary_op(env, live_after, lhs, &op.op, rhs) => (env, ty))
+            ----------------------------------- ("multiplicative operator")
+            (type_expr(env, live_after, Expr::Multiplicative(lhs, op, rhs)) => (env, ty))
+        )
+
+        (
+            (type_short_circuit(env, live_after, lhs, rhs) => env)
+            ----------------------------------- ("or")
+            (type_expr(env, live_after, Expr::Or(lhs, rhs)) => (env, Ty::bool()))
+        )
+
+        (
+            (type_short_circuit(env, live_after, lhs, rhs) => env)
+            ----------------------------------- ("and")
+            (type_expr(env, live_after, Expr::And(lhs, rhs)) => (env, Ty::bool()))
+        )
+
+        // Negation: Int → Int and Bool → Bool.
+        (
+            (let ty = op.operand_ty())
+            (type_expr_as(env, live_after, &**expr, ty) => env)
+            ----------------------------------- ("unary")
+            (type_expr(env, live_after, Expr::Unary(op, expr)) => (env, ty))
         )
 
         (
@@ -160,6 +195,8 @@ judgment_fn! {
             (let (array_named_ty, element_ty, perm_a) = NamedTy::array_with_a(parameters)?)
             (let expected_array_ty: Ty = Ty::apply_perm(perm_a, array_named_ty))
             (prove_is_mut(env, perm_a) => ())
+            (for_all(place in array.mutated_place())
+                (owners_permit_mutation(env, place) => ()))
             (type_expr_as(env, live_after.before(&**index).before(&**value), &**array, expected_array_ty) => env)
             (type_expr_as(env, live_after.before(&**value), &**index, Ty::int()) => env)
             (type_expr_as(env, live_after, &**value, element_ty) => env)
@@ -174,6 +211,53 @@ judgment_fn! {
             (type_expr(env, live_after, Expr::Share(expr)) => (env, Ty::apply_perm(Perm::Shared, ty)))
         )
 
+        (
+            // Awaiting a future runs the call it was created from, consuming the future
+            // (see `owned_future_parameters`). The liens of its captured arguments end here,
+            // but those of the result, which is typed as the call's output, remain.
+            (let () = env.await_permitted()?)
+            (type_expr(env, live_after, &**future) => (env, future_ty))
+            (let (output, _captured) = owned_future_parameters(&future_ty, "await")?)
+            ----------------------------------- ("await")
+            (type_expr(env, live_after, Expr::Await(future)) => (env, output))
+        )
+
+        (
+            // A spawned task runs concurrently with the rest of the program, for as long
+            // as it likes, so the future may only capture data that is both copy and owned
+            // (i.e., shared): nothing borrowed that could go away, and nothing unique.
+            // The task's result is dropped when it finishes.
+            (type_expr(env, live_after, &**future) => (env, future_ty))
+            (let (output, captured) = owned_future_parameters(&future_ty, "spawn")?)
+            (prove_is_copy_owned(env, captured) => ())
+            (prove_is_droppable(env, output) => ())
+            ----------------------------------- ("spawn")
+            (type_expr(env, live_after, Expr::Spawn(future)) => (env, Ty::unit()))
+        )
+
+        (
+            // The body is checked where the closure is created, as if it were called right
+            // away, with the places it captures at the types they have here. They keep those
+            // types for as long as the closure is live, since it either holds liens on them
+            // (see `liens`) or has taken their values.
+            (let body_env = env.with_closure_body(&output).push_local_variable_decls(&inputs)?)
+            (for_all(input in &inputs)
+                (check_type(&body_env, &input.ty) => ()))
+            (check_type(&env, &output) => ())
+            (type_expr_as(&body_env, LivePlaces::default(), Expr::Block(body.clone()), &output) => body_env)
+
+            // Creating the closure accesses each captured place as its body does,
+            // like the elements of a tuple.
+            (let captures = Expr::closure_captures(&inputs, &body))
+            (type_exprs(env, live_after, capture_exprs(&captures)) => (env, captured_tys))
+            (let kind = closure_kind(&env, &captures, &captured_tys))
+            (let input_tys: Vec<Ty> = inputs.iter().map(|input| input.ty.clone()).collect())
+            (let ty = NamedTy::closure(kind, input_tys, &output, captured_tys))
+            (let env = env.with_elaborations_of(&body_env))
+            ----------------------------------- ("closure")
+            (type_expr(env, live_after, Expr::Closure(inputs, output, body)) => (env, ty))
+        )
+
         // is_last_ref[A](value) — returns Bool
         // A must be a ref permission. Value is typed as A T for some T.
         (
@@ -185,16 +269,18 @@ judgment_fn! {
         (
             // Must not be conflicting permissions in the environment.
             (access_permitted(env, live_after, Access::Rf, place) => env)
+            (tracked_values_consumed(env, live_after, Access::Rf, place) => ())
 
             // Resulting type is `ref[place]` with the underlying object type.
             (let ty_place = env.place_ty(place)?)
             (let ty = Ty::apply_perm(Perm::rf(set![place]), ty_place.strip_perm()))
             ----------------------------------- ("ref place")
-            (type_expr(env, live_after, PlaceExpr { access: Access::Rf, place }) => (env, ty))
+            (type_expr(env, live_after, PlaceExpr { access: Access::Rf, place, .. }) => (env, ty))
         )
 
         (
             (access_permitted(env, live_after, Access::Mt, place) => env)
+            (tracked_values_consumed(env, live_after, Access::Mt, place) => ())
 
             // You can only apply `.mut` to places that you have unique access to.
             (let ty_place = env.place_ty(place)?)
@@ -203,26 +289,35 @@ judgment_fn! {
             // Resulting type is `mut[place]` with the underlying object type.
             (let ty = Ty::apply_perm(Perm::mt(set![place]), ty_place.strip_perm()))
             ----------------------------------- ("mut place")
-            (type_expr(env, live_after, PlaceExpr { access: Access::Mt, place }) => (env, ty))
+            (type_expr(env, live_after, PlaceExpr { access: Access::Mt, place, .. }) => (env, ty))
         )
 
         (
             (access_permitted(env, live_after, Access::Gv, place) => env)
+            (tracked_values_consumed(env, live_after, Access::Gv, place) => ())
             (let ty = env.place_ty(place)?)
             (move_place(env, live_after, place, ty) => env)
             ----------------------------------- ("give place")
-            (type_expr(env, live_after, PlaceExpr { access: Access::Gv, place }) => (env, ty))
+            (type_expr(env, live_after, PlaceExpr { access: Access::Gv, place, .. }) => (env, ty))
         )
 
         (
             (access_permitted(env, live_after, Access::Drop, place) => env)
+            (tracked_values_consumed(env, live_after, Access::Drop, place) => ())
             (let ty = env.place_ty(place)?)
             (move_place(env, live_after, place, ty) => env)
             ----------------------------------- ("drop place")
-            (type_expr(env, live_after, PlaceExpr { access: Access::Drop, place }) => (env, Ty::unit()))
+            (type_expr(env, live_after, PlaceExpr { access: Access::Drop, place, .. }) => (env, Ty::unit()))
         )
 
         (
+            // Infer the class parameters from the arguments if they were omitted.
+            (let expr = Expr::New(class_name.clone(), parameters.clone(), arguments.clone()))
+            (let exprs = &arguments.exprs)
+            (elaborate_parameters(env, live_after, Generic::Class(class_name.clone()), parameters, (), exprs) => parameters)
+            (let env = env.with_elaboration(&expr, &parameters))
+            (let () = env.check_type_arguments_untracked(&parameters)?)
+
             // Find the class definition
             (let class_decl = env.program().class_named(class_name)?)
 
@@ -246,50 +341,323 @@ judgment_fn! {
             (let env = env.with_place_in_flight(temp_var))
             (let env = env.pop_fresh_variable(temp_var))
             ----------------------------------- ("new")
-            (type_expr(env, live_after, Expr::New(class_name, parameters, exprs)) => (env, this_ty))
+            (type_expr(env, live_after, Expr::New(class_name, parameters, arguments)) => (env, this_ty))
+        )
+
+        (
+            (let expr = Expr::NewVariant(enum_name.clone(), parameters.clone(), variant_name.clone(), arguments.clone()))
+            (let exprs = &arguments.exprs)
+            (elaborate_parameters(env, live_after, Generic::Variant(enum_name.clone(), variant_name.clone()), parameters, (), exprs) => parameters)
+            (let env = env.with_elaboration(&expr, &parameters))
+            (let () = env.check_type_arguments_untracked(&parameters)?)
+
+            // Find the enum definition and the fields of the variant being constructed.
+            (let enum_decl = env.program().enum_named(enum_name)?)
+            (let enum_data = enum_decl.binder.instantiate_with(parameters)?)
+            (let EnumDeclBoundData { predicates, variants: _ } = &enum_data)
+            (let fields = enum_data.variant_named(variant_name)?.1.fields.clone())
+
+            // Check we have the correct number of arguments.
+            (if fields.len() == exprs.len())
+
+            // Prove that the enum requirements hold.
+            (let this_ty = NamedTy::new(enum_name, parameters))
+            (prove_predicates(env, predicates) => ())
+
+            // As with `new`, the fields are stored into a temporary that becomes the in-flight value.
+            (let (env, temp_var) = env.push_fresh_variable(this_ty))
+            (type_field_exprs_as(env, live_after, temp_var, exprs, fields) => env)
+            (let env = env.with_place_in_flight(temp_var))
+            (let env = env.pop_fresh_variable(temp_var))
+            ----------------------------------- ("new variant")
+            (type_expr(env, live_after, Expr::NewVariant(enum_name, parameters, variant_name, arguments)) => (env, this_ty))
+        )
+
+        (
+            // The value being matched is stored in a temporary for the duration of the match;
+            // the bindings of each arm are typed as its fields (see `Env::push_match_bindings`).
+            (type_expr(env, live_after.before_all(arms), &**scrutinee) => (env, scrutinee_ty))
+            (let () = env.check_match_arms(&scrutinee_ty, &arms)?)
+            (let (env, scrutinee_var) = env.push_fresh_variable_with_in_flight(&scrutinee_ty))
+            (branches_consume_tracked_values(env, arms.iter().map(|arm| live_after.before(arm)).collect::<Vec<_>>()) => ())
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, arms) => (env, ty))
+            (let env = env.pop_fresh_variable(scrutinee_var))
+            ----------------------------------- ("match")
+            (type_expr(env, live_after, Expr::Match(scrutinee, arms)) => (env, ty))
         )
 
         (
             // Start by typing the `this` expression, store into `@temp(0)`
+            (let expr = Expr::Call(receiver.clone(), method_name.clone(), parameters.clone(), arguments.clone()))
+            (let exprs = &arguments.exprs)
             (let live_after_receiver = live_after.before(exprs))
             (type_expr(env, live_after_receiver, &**receiver) => (env, receiver_ty))
             (let (env, this_var) = env.push_fresh_variable_with_in_flight(receiver_ty))
 
+            // Infer the method parameters from the receiver and arguments if they were omitted.
+            (let generic = Generic::Method(receiver_ty.clone(), method_name.clone()))
+            (elaborate_parameters(env, live_after.before_all([this_var.clone()]), generic, parameters, (receiver_ty,), exprs) => parameters)
+            (let env = env.with_elaboration(&expr, &parameters))
+            (let () = env.check_type_arguments_untracked(&parameters)?)
+
             // Use receiver type to look up the method
-            (resolve_method(env, receiver_ty, method_name, parameters) => (this_input_ty, inputs, output, predicates))
+            (resolve_method(env, receiver_ty, method_name, parameters) => (this_input_ty, inputs, output, predicates, is_async))
 
             // Rename each of the arguments (including `this`) to a temporary variable, with `this` being `temp(0)`.
             (let input_names: Vec<ValueId> = inputs.iter().map(|input| input.name.clone()).collect())
             (let input_tys: Vec<Ty> = inputs.iter().map(|input| input.ty.clone()).collect())
 
             // The self type must match what method expects
-            (let (this_input_ty, input_tys) = (this_input_ty.clone(), input_tys.clone()).with_this_stored_to(this_var))
+            (let (this_input_ty, input_tys, output) = (this_input_ty.clone(), input_tys.clone(), output.clone()).with_this_stored_to(this_var))
             (sub(env, live_after_receiver, receiver_ty, this_input_ty) => ())
 
             // Type each of the method arguments, remapping them to `temp(i)` appropriately as well
-            (type_method_arguments_as(env, live_after, exprs, (this_var,), input_names, input_tys) => (env, input_temps))
+            (type_method_arguments_as(env, live_after, exprs, (this_var,), input_names, input_tys, output) => (env, input_temps, output))
 
             // Prove predicates
             (prove_predicates(env, predicates) => ())
 
-            // Drop all the temporaries
+            // The arguments of an `async fn` are moved into the future it returns,
+            // which keeps their liens until it is awaited.
+            (let output = call_output_ty(&env, is_async, output, &input_temps)?)
+
+            // Drop all the temporaries, first rewriting the output type so it does not refer to them
             (accesses_permitted(env, live_after, Access::Drop, input_temps) => env)
+            (let input_vars = call_input_vars(&input_names, Some(Var::This)))
+            (normalize_output_for_pop(env, live_after, output, input_temps, input_vars) => output)
             (let env = env.pop_fresh_variables(input_temps))
+            (check_type(env, output) => ())
 
             // Rename output variable to in-flight
             (let output = output.with_place_in_flight(Var::Return))
             ----------------------------------- ("call")
-            (type_expr(env, live_after, Expr::Call(receiver, method_name, parameters, exprs)) => (env, output))
+            (type_expr(env, live_after, Expr::Call(receiver, method_name, parameters, arguments)) => (env, output))
         )
 
         (
-            (type_expr_as(env, live_after.before_all([if_true, if_false]), &**cond, TypeName::Bool) => env)
-            (type_expr_as(env, live_after, &**if_true, Ty::unit()) => env)
-            (type_expr_as(env, live_after, &**if_false, Ty::unit()) => env)
+            // As with a method call, except that there is no `self`.
+            (let expr = Expr::CallFn(fn_name.clone(), parameters.clone(), arguments.clone()))
+            (let exprs = &arguments.exprs)
+            (elaborate_parameters(env, live_after, Generic::Fn(fn_name.clone()), parameters, (), exprs) => parameters)
+            (let env = env.with_elaboration(&expr, &parameters))
+            (let () = env.check_type_arguments_untracked(&parameters)?)
+            (let fn_decl = env.program().fn_named(fn_name)?)
+            (let FnDeclBoundData { inputs, output, predicates, body: _ } = fn_decl.binder.instantiate_with(&parameters)?)
+
+            (let input_names: Vec<ValueId> = inputs.iter().map(|input| input.name.clone()).collect())
+            (let input_tys: Vec<Ty> = inputs.iter().map(|input| input.ty.clone()).collect())
+            (type_method_arguments_as(env, live_after, exprs, (), input_names, input_tys, output) => (env, input_temps, output))
+
+            (prove_predicates(env, predicates) => ())
+
+            (accesses_permitted(env, live_after, Access::Drop, input_temps) => env)
+            (let input_vars = call_input_vars(&input_names, None))
+            (normalize_output_for_pop(env, live_after, output, input_temps, input_vars) => output)
+            (let env = env.pop_fresh_variables(input_temps))
+            (check_type(env, output) => ())
+
+            (let output = output.with_place_in_flight(Var::Return))
+            ----------------------------------- ("call fn")
+            (type_expr(env, live_after, Expr::CallFn(fn_name, parameters, arguments)) => (env, output))
+        )
+
+        (
+            // Both branches start from the environment after the condition
+            // and their resulting environments and types are joined.
+            (if !if_true.diverges() && !if_false.diverges())!
+            (type_expr_as(env, live_after.before_all([if_true, if_false]), &**cond, TypeName::Bool) => env_cond)
+            (branches_consume_tracked_values(env_cond, vec![live_after.before(&if_true), live_after.before(&if_false)]) => ())
+            (type_expr(env_cond, live_after, &**if_true) => (env_true, ty_true))
+            (type_expr(env_cond, live_after, &**if_false) => (env_false, ty_false))
+            (let ty = join_tys(&ty_true, &ty_false)?)
+            (sub(env_true, live_after, ty_true, ty) => ())
+            (sub(env_false, live_after, ty_false, ty) => ())
+            (let env = env_true.join(&env_false, &env_cond)?)
+            (let () = check_joined_ty(&env, &ty)?)
             ----------------------------------- ("if")
-            (type_expr(env, live_after, Expr::If(cond, if_true, if_false)) => (env, Ty::unit()))
+            (type_expr(env, live_after, Expr::If(cond, if_true, if_false)) => (env, ty))
+        )
+
+        (
+            // A branch that never completes contributes neither an environment nor a type,
+            // only the parameters inferred within it and the environments at its `break`s.
+            (if if_true.diverges())!
+            (type_expr_as(env, live_after.before_all([if_true, if_false]), &**cond, TypeName::Bool) => env_cond)
+            (branches_consume_tracked_values(env_cond, vec![live_after.before(&if_true), live_after.before(&if_false)]) => ())
+            (type_expr_as(env_cond, live_after, &**if_true, Ty::unit()) => env_true)
+            (type_expr(env_cond, live_after, &**if_false) => (env, ty))
+            (let env = env.with_diverging_branch(&env_true)?)
+            ----------------------------------- ("if diverging true")
+            (type_expr(env, live_after, Expr::If(cond, if_true, if_false)) => (env, ty))
+        )
+
+        (
+            (if !if_true.diverges() && if_false.diverges())!
+            (type_expr_as(env, live_after.before_all([if_true, if_false]), &**cond, TypeName::Bool) => env_cond)
+            (branches_consume_tracked_values(env_cond, vec![live_after.before(&if_true), live_after.before(&if_false)]) => ())
+            (type_expr(env_cond, live_after, &**if_true) => (env, ty))
+            (type_expr_as(env_cond, live_after, &**if_false, Ty::unit()) => env_false)
+            (let env = env.with_diverging_branch(&env_false)?)
+            ----------------------------------- ("if diverging false")
+            (type_expr(env, live_after, Expr::If(cond, if_true, if_false)) => (env, ty))
+        )
+
+    }
+}
+
+judgment_fn! {
+    /// Type the arms of a `match` on the value stored in `scrutinee_var`.
+    /// As with the branches of an `if`, each arm starts from the environment `env`
+    /// after the scrutinee, and the resulting environments and types are joined.
+    fn type_match_arms(
+        env: Env,
+        live_after: LivePlaces,
+        scrutinee_var: Var,
+        scrutinee_ty: Ty,
+        arms: Vec<MatchArm>,
+    ) => (Env, Ty) {
+        debug(scrutinee_var, scrutinee_ty, arms, env, live_after)
+
+        (
+            // Only an enum with no variants can be matched with no arms,
+            // and no value of such an enum can exist.
+            ----------------------------------- ("no arms")
+            (type_match_arms(env, _live_after, _scrutinee_var, _scrutinee_ty, ()) => (env, Ty::unit()))
+        )
+
+        (
+            // An arm that never completes contributes neither an environment nor a type,
+            // only the parameters inferred within it and the environments at its `break`s.
+            (if arm.body.diverges())!
+            (type_match_arm(env, live_after, scrutinee_var, scrutinee_ty, arm) => (env_arm, _ty_arm))
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, arms) => (env, ty))
+            (let env = env.with_diverging_branch(&env_arm)?)
+            ----------------------------------- ("diverging arm")
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, Cons(arm, arms)) => (env, ty))
+        )
+
+        (
+            (if !arm.body.diverges() && arms.iter().all(|arm| arm.body.diverges()))!
+            (type_match_arm(env, live_after, scrutinee_var, scrutinee_ty, arm) => (env_arm, ty))
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, arms) => (env_arms, _ty_arms))
+            (let env_arm = env_arm.with_diverging_branch(&env_arms)?)
+            ----------------------------------- ("last arm")
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, Cons(arm, arms)) => (env_arm, ty))
+        )
+
+        (
+            (if !arm.body.diverges() && !arms.iter().all(|arm| arm.body.diverges()))!
+            (type_match_arm(env, live_after, scrutinee_var, scrutinee_ty, arm) => (env_arm, ty_arm))
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, arms) => (env_arms, ty_arms))
+            (let ty = join_tys(&ty_arm, &ty_arms)?)
+            (sub(env_arm, live_after, ty_arm, ty) => ())
+            (sub(env_arms, live_after, ty_arms, ty) => ())
+            (let env = env_arm.join(&env_arms, &env)?)
+            (let () = check_joined_ty(&env, &ty)?)
+            ----------------------------------- ("arms")
+            (type_match_arms(env, live_after, scrutinee_var, scrutinee_ty, Cons(arm, arms)) => (env, ty))
+        )
+    }
+}
+
+judgment_fn! {
+    /// Compute the type of `lhs op rhs`, the same at every precedence level.
+    fn type_binary_op(
+        env: Env,
+        live_after: LivePlaces,
+        lhs: Arc<Expr>,
+        op: BinaryOp,
+        rhs: Arc<Expr>,
+    ) => (Env, Ty) {
+        debug(lhs, op, rhs, env, live_after)
+
+        // Arithmetic: Int × Int → Int
+        (
+            (if op.is_arithmetic())!
+            (type_expr_as(env, live_after.before(&**rhs), &**lhs, Ty::int()) => env)
+            (type_expr_as(env, live_after, &**rhs, Ty::int()) => env)
+            ----------------------------------- ("arithmetic")
+            (type_binary_op(env, live_after, lhs, op, rhs) => (env, Ty::int()))
+        )
+
+        // Comparison: Int × Int → Bool
+        (
+            (if op.is_comparison())!
+            (type_expr_as(env, live_after.before(&**rhs), &**lhs, Ty::int()) => env)
+            (type_expr_as(env, live_after, &**rhs, Ty::int()) => env)
+            ----------------------------------- ("comparison")
+            (type_binary_op(env, live_after, lhs, op, rhs) => (env, Ty::bool()))
+        )
+
+        // Comparison: Char × Char → Bool
+        (
+            (if op.is_comparison())!
+            (type_expr_as(env, live_after.before(&**rhs), &**lhs, Ty::char()) => env)
+            (type_expr_as(env, live_after, &**rhs, Ty::char()) => env)
+            ----------------------------------- ("char comparison")
+            (type_binary_op(env, live_after, lhs, op, rhs) => (env, Ty::bool()))
+        )
+
+        // Concatenation: String × String → String, and comparison: String × String → Bool.
+        // The operands are only read, so they may be strings with any permission
+        // (e.g., `a.ref + b.ref`); given operands are dropped afterwards.
+        (
+            (if op.is_concatenation() || op.is_comparison())!
+            (type_expr(env, live_after.before(&**rhs), &**lhs) => (env, lhs_ty))
+            (if lhs_ty.strip_perm() == Ty::string())!
+            (type_expr(env, live_after, &**rhs) => (env, rhs_ty))
+            (if rhs_ty.strip_perm() == Ty::string())
+            (let ty = if op.is_comparison() { Ty::bool() } else { Ty::string() })
+            ----------------------------------- ("string operator")
+            (type_binary_op(env, live_after, lhs, op, rhs) => (env, ty))
         )
+    }
+}
+
+judgment_fn! {
+    /// Type the `Bool` operands of `lhs || rhs` or `lhs && rhs`.
+    fn type_short_circuit(
+        env: Env,
+        live_after: LivePlaces,
+        lhs: Arc<Expr>,
+        rhs: Arc<Expr>,
+    ) => Env {
+        debug(lhs, rhs, env, live_after)
+
+        // Bool × Bool, where `rhs` may not be evaluated,
+        // so the environment after it is joined with the one after `lhs`.
+        (
+            (type_expr_as(env, live_after.before_maybe(&**rhs), &**lhs, Ty::bool()) => env_lhs)
+            (branches_consume_tracked_values(env_lhs, vec![live_after.before(&**rhs), live_after.clone()]) => ())
+            (type_expr_as(env_lhs, live_after, &**rhs, Ty::bool()) => env_rhs)
+            (let env = env_rhs.join(&env_lhs, &env_lhs)?)
+            ----------------------------------- ("short-circuit")
+            (type_short_circuit(env, live_after, lhs, rhs) => env)
+        )
+    }
+}
 
+judgment_fn! {
+    /// Type one arm of a `match` on the value stored in `scrutinee_var`,
+    /// with its bindings in scope for the duration of its body.
+    fn type_match_arm(
+        env: Env,
+        live_after: LivePlaces,
+        scrutinee_var: Var,
+        scrutinee_ty: Ty,
+        arm: MatchArm,
+    ) => (Env, Ty) {
+        debug(scrutinee_var, scrutinee_ty, arm, env, live_after)
+
+        (
+            (let (env, bindings) = env.push_match_bindings(&scrutinee_var, &scrutinee_ty, &arm)?)
+            (for_all(binding in &bindings)
+                (place_not_abandoned(env, live_after.before(&arm.body), binding) => ()))
+            (type_block(env, live_after, &arm.body) => (env, ty))
+            (let env = env.without_local_variables(bindings)?)
+            ----------------------------------- ("arm")
+            (type_match_arm(env, live_after, scrutinee_var, scrutinee_ty, arm) => (env, ty))
+        )
     }
 }
 
@@ -299,19 +667,68 @@ judgment_fn! {
         receiver_ty: Ty,
         method_name: MethodId,
         method_parameters: Vec<Parameter>,
-    ) => (Ty, Vec<LocalVariableDecl>, Ty, Vec<Predicate>) {
+    ) => (Ty, Vec<LocalVariableDecl>, Ty, Vec<Predicate>, Async) {
         debug(receiver_ty, method_name, method_parameters, env)
 
         (
             (if let NamedTy { name: TypeName::Id(class_name), parameters: class_parameters } = &named_ty)!
             (let class_decl = env.program().class_named(class_name)?)
             (let ClassDeclBoundData { predicates: _, fields: _, methods, drop_body: _ } = class_decl.binder.instantiate_with(class_parameters)?)
-            (MethodDecl { name: _, binder } in methods.into_iter().filter(|m| m.name == *method_name))
+            (MethodDecl { name: _, is_async, binder, span: _ } in methods.into_iter().filter(|m| m.name == *method_name))
             (let () = tracing::debug!("found method in class {:?}: {:?}", class_name, binder))
             (let MethodDeclBoundData { this: ThisDecl { perm }, inputs, output, predicates, body: _ } = binder.instantiate_with(method_parameters)?)
             (let this_ty = Ty::apply_perm(perm, named_ty))
             ----------------------------------- ("class-method")
-            (resolve_method(env, named_ty: NamedTy, method_name, method_parameters) => (this_ty, inputs, output, predicates))
+            (resolve_method(env, named_ty: NamedTy, method_name, method_parameters) => (this_ty, inputs, output, predicates, is_async))
+        )
+
+        (
+            // Methods from the impls of a class are resolved like its own methods,
+            // except that the impl's parameters are found by matching the class type
+            // and the where-clauses of the impl must hold as well.
+            (let impls = env.program().impls_for(&named_ty)?)
+            (ImplDeclBoundData { trait_name, class_ty: _, predicates: impl_predicates, methods } in impls)
+            (MethodDecl { name: _, is_async, binder, span: _ } in methods.into_iter().filter(|m| m.name == *method_name))
+            (let () = tracing::debug!("found method in impl of {:?}: {:?}", trait_name, binder))
+            (let MethodDeclBoundData { this: ThisDecl { perm }, inputs, output, predicates, body: _ } = binder.instantiate_with(method_parameters)?)
+            (let this_ty = Ty::apply_perm(perm, named_ty))
+            (let predicates: Vec<Predicate> = impl_predicates.into_iter().chain(predicates).collect())
+            ----------------------------------- ("impl-method")
+            (resolve_method(env, named_ty: NamedTy, method_name, method_parameters) => (this_ty, inputs, output, predicates, is_async))
+        )
+
+        (
+            // On a type variable, we can call the methods of the traits it is assumed to implement.
+            (trait_name in env.assumed_traits(&Ty::Var(var.clone())))
+            (let TraitDecl { name: _, methods } = env.program().trait_named(&trait_name)?.clone())
+            (MethodDecl { name: _, is_async, binder, span: _ } in methods.into_iter().filter(|m| m.name == *method_name))
+            (let MethodDeclBoundData { this: ThisDecl { perm }, inputs, output, predicates, body: _ } = binder.instantiate_with(method_parameters)?)
+            (let this_ty = Ty::apply_perm(perm, Ty::Var(var.clone())))
+            ----------------------------------- ("trait-method")
+            (resolve_method(env, Ty::Var(var), method_name, method_parameters) => (this_ty, inputs, output, predicates, is_async))
+        )
+
+        (
+            // `len` is built into strings, counting their characters.
+            (if let NamedTy { name: TypeName::String, parameters: _ } = &named_ty)!
+            (if method_name.is_len())!
+            (if method_parameters.is_empty())
+            (let this_ty = Ty::apply_perm(Perm::rf(Set::<Place>::new()), &named_ty))
+            ----------------------------------- ("string-len")
+            (resolve_method(env, named_ty: NamedTy, method_name, method_parameters) => (this_ty, Vec::<LocalVariableDecl>::new(), Ty::int(), Vec::<Predicate>::new(), Async::No))
+        )
+
+        (
+            // A closure is called through the receiver permission its kind requires
+            // (see `CallKind`), taking its inputs and returning its output.
+            (if let NamedTy { name: TypeName::Closure(kind), parameters } = &named_ty)!
+            (if method_name.is_call())!
+            (if method_parameters.is_empty())
+            (let (input_tys, output, _captured) = NamedTy::closure_parameters(parameters)?)
+            (let inputs = LocalVariableDecl::closure_inputs(input_tys))
+            (let this_ty = Ty::apply_perm(kind.this_perm(), &named_ty))
+            ----------------------------------- ("closure-call")
+            (resolve_method(env, named_ty: NamedTy, method_name, method_parameters) => (this_ty, inputs, output, Vec::<Predicate>::new(), Async::No))
         )
 
         (
@@ -332,7 +749,8 @@ judgment_fn! {
         debug(place, ty, env, live_after)
 
         (
-            (if live_after.is_live(place))!
+            // Values of copy types are copied, leaving `place` initialized,
+            // whether or not it is used again.
             (prove_is_copy(env, ty) => ())
             ----------------------------------- ("copy")
             (move_place(env, _live_after, _place, ty) => env)
@@ -340,9 +758,10 @@ judgment_fn! {
 
         (
             (if !live_after.is_live(place))
+            (prove_isnt_known_to_be_copy(env, ty) => ())
             (let env = env.with_place_in_flight(place))
             ----------------------------------- ("give")
-            (move_place(env, live_after, place, _ty) => env)
+            (move_place(env, live_after, place, ty) => env)
         )
     }
 }
@@ -420,12 +839,13 @@ judgment_fn! {
         input_temps: Vec<Var>,
         input_names: Vec<ValueId>,
         input_tys: Vec<Ty>,
-    ) => (Env, Vec<Var>) {
-        debug(exprs, input_temps, input_names, input_tys, env, live_after)
+        output: Ty,
+    ) => (Env, Vec<Var>, Ty) {
+        debug(exprs, input_temps, input_names, input_tys, output, env, live_after)
 
         (
             ----------------------------------- ("none")
-            (type_method_arguments_as(env, _live_after, (), temps, (), ()) => (env, temps))
+            (type_method_arguments_as(env, _live_after, (), temps, (), (), output) => (env, temps, output))
         )
 
         (
@@ -463,8 +883,8 @@ judgment_fn! {
             (let input_ty = input_ty.with_var_stored_to(input_name, input_temp))
             (sub(env, live_after_expr, expr_ty, input_ty) => ())
 
-            (let input_tys = input_tys.with_var_stored_to(input_name, input_temp))
-            (type_method_arguments_as(env, live_after, exprs, Cons(input_temp, input_temps), input_names, input_tys) => pair)
+            (let (input_tys, output) = (input_tys.clone(), output.clone()).with_var_stored_to(input_name, input_temp))
+            (type_method_arguments_as(env, live_after, exprs, Cons(input_temp, input_temps), input_names, input_tys, output) => triple)
             ----------------------------------- ("cons")
             (type_method_arguments_as(
                 env,
@@ -473,7 +893,8 @@ judgment_fn! {
                 input_temps,
                 Cons(input_name, input_names),
                 Cons(input_ty, input_tys),
-            ) => pair)
+                output,
+            ) => triple)
         )
     }
 }
@@ -523,3 +944,77 @@ judgment_fn! {
 
     }
 }
+
+/// The type of a call whose declared output type is `output` and whose arguments
+/// (including `self`) are stored in `input_temps`. A call to an `async fn` produces
+/// a future that captures the arguments (see [`Async`]).
+fn call_output_ty(env: &Env, is_async: Async, output: Ty, input_temps: &[Var]) -> Fallible<Ty> {
+    match is_async {
+        Async::No => Ok(output),
+        Async::Yes => {
+            // The temporaries are listed last argument first.
+            let captured = input_temps
+                .iter()
+                .rev()
+                .map(|temp| Ok(env.var_ty(temp)?.clone()))
+                .collect::<Fallible<Vec<Ty>>>()?;
+            Ok(NamedTy::future(output, captured).upcast())
+        }
+    }
+}
+
+/// The inputs of the callee that the temporaries holding the arguments of a call were
+/// passed as. Like the temporaries, these are listed last argument first, followed by
+/// `self` for a method.
+fn call_input_vars(input_names: &[ValueId], this: Option<Var>) -> Vec<Var> {
+    input_names
+        .iter()
+        .rev()
+        .map(|name| Var::Id(name.clone()))
+        .chain(this)
+        .collect()
+}
+
+/// The output type `R` and the captured type `C` of a future of type `Future[R, C]`.
+/// Only a future that is owned can be awaited or spawned, since that consumes it.
+fn owned_future_parameters(future_ty: &Ty, action: &str) -> Fallible<(Ty, Ty)> {
+    match future_ty {
+        Ty::NamedTy(NamedTy {
+            name: TypeName::Future,
+            parameters,
+        }) => NamedTy::future_parameters(parameters),
+        Ty::ApplyPerm(Perm::Given, ty) => owned_future_parameters(ty, action),
+        _ => bail!(
+            "cannot {action} a value of type `{future_ty:?}`: only an owned future can be {action}ed"
+        ),
+    }
+}
+
+/// The place expressions through which a closure captures `captures` (see [`Expr::closure_captures`]).
+fn capture_exprs(captures: &[(Var, Access)]) -> Vec<Expr> {
+    captures
+        .iter()
+        .map(|(var, access)| Expr::Place(PlaceExpr::new(var.clone(), *access, Span::default())))
+        .collect()
+}
+
+/// The kind of a closure whose captures `captures` have the types `captured_tys`.
+/// Giving away a captured value consumes it, unless it is copied.
+fn closure_kind(env: &Env, captures: &[(Var, Access)], captured_tys: &[Ty]) -> CallKind {
+    captures
+        .iter()
+        .zip(captured_tys)
+        .map(|((_var, access), ty)| match access {
+            Access::Rf => CallKind::Shared,
+            Access::Mt => CallKind::Mut,
+            Access::Gv | Access::Drop => {
+                if prove_is_copy(env, ty).is_proven() {
+                    CallKind::Shared
+                } else {
+                    CallKind::Once
+                }
+            }
+        })
+        .max()
+        .unwrap_or(CallKind::Shared)
+}
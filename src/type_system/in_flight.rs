@@ -3,9 +3,9 @@ use std::sync::Arc;
 use formality_core::{seq, Map, Set, Upcast};
 
 use crate::grammar::{
-    Ascription, Block, DropBody, Expr, FieldDecl, LocalVariableDecl, MethodBody,
-    MethodDeclBoundData, NamedTy, Parameter, Perm, Place, PlaceExpr, Predicate, Statement,
-    ThisDecl, Ty, ValueId, Var,
+    Arguments, Ascription, Block, DropBody, Expr, FieldDecl, FnDeclBoundData, LocalVariableDecl,
+    MatchArm, MethodBody, MethodDeclBoundData, NamedTy, Parameter, Perm, Place, PlaceExpr,
+    Predicate, Statement, ThisDecl, Ty, ValueId, Var,
 };
 
 pub trait InFlight: Sized {
@@ -209,6 +209,10 @@ impl InFlight for Predicate {
             Predicate::Variance(kind, parameter) => {
                 Predicate::Variance(*kind, parameter.with_places_transformed(transform))
             }
+            Predicate::Implements(trait_name, parameter) => Predicate::Implements(
+                trait_name.clone(),
+                parameter.with_places_transformed(transform),
+            ),
         }
     }
 }
@@ -294,6 +298,7 @@ impl InFlight for Block {
     fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
         Block {
             statements: self.statements.with_places_transformed(transform),
+            span: self.span.clone(),
         }
     }
 }
@@ -301,25 +306,29 @@ impl InFlight for Block {
 impl InFlight for Statement {
     fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
         match self {
-            Statement::Expr(expr) => Statement::Expr(expr.with_places_transformed(transform)),
-            Statement::Let(name, ascription, expr) => Statement::Let(
+            Statement::Expr(expr, span) => {
+                Statement::Expr(expr.with_places_transformed(transform), span.clone())
+            }
+            Statement::Let(name, ascription, expr, span) => Statement::Let(
                 rename_value_id(name, transform),
                 ascription.with_places_transformed(transform),
                 expr.with_places_transformed(transform),
+                span.clone(),
             ),
-            Statement::Reassign(place, expr) => Statement::Reassign(
+            Statement::Reassign(place, expr, span) => Statement::Reassign(
                 place.with_places_transformed(transform),
                 expr.with_places_transformed(transform),
+                span.clone(),
             ),
-            Statement::Loop(block) => {
-                Statement::Loop(block.with_places_transformed(transform))
+            Statement::Loop(block, span) => {
+                Statement::Loop(block.with_places_transformed(transform), span.clone())
             }
-            Statement::Break => Statement::Break,
-            Statement::Return(expr) => {
-                Statement::Return(expr.with_places_transformed(transform))
+            Statement::Break(span) => Statement::Break(span.clone()),
+            Statement::Return(expr, span) => {
+                Statement::Return(expr.with_places_transformed(transform), span.clone())
             }
-            Statement::Print(expr) => {
-                Statement::Print(expr.with_places_transformed(transform))
+            Statement::Print(expr, span) => {
+                Statement::Print(expr.with_places_transformed(transform), span.clone())
             }
         }
     }
@@ -339,17 +348,47 @@ impl InFlight for Expr {
         match self {
             Expr::Block(block) => Expr::Block(block.with_places_transformed(transform)),
             Expr::Integer(n) => Expr::Integer(*n),
+            Expr::String(literal) => Expr::String(literal.clone()),
+            Expr::Char(literal) => Expr::Char(literal.clone()),
             Expr::True => Expr::True,
             Expr::False => Expr::False,
-            Expr::BinaryOp(lhs, op, rhs) => Expr::BinaryOp(
+            Expr::Or(lhs, rhs) => Expr::Or(
+                lhs.with_places_transformed(transform),
+                rhs.with_places_transformed(transform),
+            ),
+            Expr::And(lhs, rhs) => Expr::And(
+                lhs.with_places_transformed(transform),
+                rhs.with_places_transformed(transform),
+            ),
+            Expr::Comparison(lhs, op, rhs) => Expr::Comparison(
+                lhs.with_places_transformed(transform),
+                op.clone(),
+                rhs.with_places_transformed(transform),
+            ),
+            Expr::Additive(lhs, op, rhs) => Expr::Additive(
+                lhs.with_places_transformed(transform),
+                op.clone(),
+                rhs.with_places_transformed(transform),
+            ),
+            Expr::Multiplicative(lhs, op, rhs) => Expr::Multiplicative(
                 lhs.with_places_transformed(transform),
                 op.clone(),
                 rhs.with_places_transformed(transform),
             ),
+            Expr::Unary(op, expr) => {
+                Expr::Unary(op.clone(), expr.with_places_transformed(transform))
+            }
             Expr::Place(place_expr) => {
                 Expr::Place(place_expr.with_places_transformed(transform))
             }
             Expr::Share(expr) => Expr::Share(expr.with_places_transformed(transform)),
+            Expr::Await(expr) => Expr::Await(expr.with_places_transformed(transform)),
+            Expr::Spawn(expr) => Expr::Spawn(expr.with_places_transformed(transform)),
+            Expr::Closure(inputs, output, body) => Expr::Closure(
+                inputs.with_places_transformed(transform),
+                output.with_places_transformed(transform),
+                body.with_places_transformed(transform),
+            ),
             Expr::Tuple(exprs) => Expr::Tuple(exprs.with_places_transformed(transform)),
             Expr::Call(receiver, method_id, params, args) => Expr::Call(
                 receiver.with_places_transformed(transform),
@@ -357,11 +396,26 @@ impl InFlight for Expr {
                 params.with_places_transformed(transform),
                 args.with_places_transformed(transform),
             ),
+            Expr::CallFn(fn_name, params, args) => Expr::CallFn(
+                fn_name.clone(), // function name — not a variable, don't rename
+                params.with_places_transformed(transform),
+                args.with_places_transformed(transform),
+            ),
             Expr::New(class_name, params, args) => Expr::New(
                 class_name.clone(), // class name — not a variable, don't rename
                 params.with_places_transformed(transform),
                 args.with_places_transformed(transform),
             ),
+            Expr::NewVariant(enum_name, params, variant_name, args) => Expr::NewVariant(
+                enum_name.clone(),
+                params.with_places_transformed(transform),
+                variant_name.clone(), // variant name — not a variable, don't rename
+                args.with_places_transformed(transform),
+            ),
+            Expr::Match(scrutinee, arms) => Expr::Match(
+                scrutinee.with_places_transformed(transform),
+                arms.with_places_transformed(transform),
+            ),
             Expr::Clear(var_name) => Expr::Clear(rename_value_id(var_name, transform)),
             Expr::If(cond, then_branch, else_branch) => Expr::If(
                 cond.with_places_transformed(transform),
@@ -403,11 +457,32 @@ impl InFlight for Expr {
     }
 }
 
+impl InFlight for Arguments {
+    fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
+        self.with_exprs(self.exprs.with_places_transformed(transform))
+    }
+}
+
+impl InFlight for MatchArm {
+    fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
+        MatchArm {
+            variant: self.variant.clone(),
+            bindings: self
+                .bindings
+                .iter()
+                .map(|binding| rename_value_id(binding, transform))
+                .collect(),
+            body: self.body.with_places_transformed(transform),
+        }
+    }
+}
+
 impl InFlight for PlaceExpr {
     fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
         PlaceExpr {
             place: self.place.with_places_transformed(transform),
             access: self.access,
+            span: self.span.clone(),
         }
     }
 }
@@ -431,6 +506,17 @@ impl InFlight for DropBody {
     }
 }
 
+impl InFlight for FnDeclBoundData {
+    fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
+        FnDeclBoundData {
+            inputs: self.inputs.with_places_transformed(transform),
+            output: self.output.with_places_transformed(transform),
+            predicates: self.predicates.with_places_transformed(transform),
+            body: self.body.with_places_transformed(transform),
+        }
+    }
+}
+
 impl InFlight for MethodDeclBoundData {
     fn with_places_transformed(&self, transform: Transform<'_>) -> Self {
         MethodDeclBoundData {
@@ -0,0 +1,528 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use formality_core::{judgment_fn, term, Cons, Fallible, Map, Set, Upcast};
+
+use crate::{
+    dada_lang::grammar::{ExistentialVar, Variable},
+    grammar::{
+        Arguments, Block, Expr, Kind, MatchArm, MethodDecl, MethodId, NamedTy, Parameter, Perm,
+        Statement, Ty, TypeName, ValueId,
+    },
+    type_system::{env::Env, expressions::type_expr, liveness::LivePlaces},
+};
+
+/// The parameters inferred while typing a body, by the expression they were inferred for
+/// (see [`Env::with_elaboration`]). Each use of a generic records where its arguments were
+/// written (see [`Arguments`]), so uses that are written alike are different keys. The same
+/// use may still be typed more than once, e.g., in a loop; the parameters inferred each
+/// time are kept so that they can be compared.
+pub type Elaborations = Map<Expr, Set<Vec<Parameter>>>;
+
+/// Something whose generic parameters can be inferred from the types
+/// of the arguments it is applied to.
+#[term]
+pub enum Generic {
+    /// `new Class(..)`: the arguments are the fields of the class.
+    #[grammar(class $v0)]
+    Class(ValueId),
+
+    /// `new Enum::Variant(..)`: the arguments are the fields of the variant.
+    #[grammar(enum $v0 :: $v1)]
+    Variant(ValueId, ValueId),
+
+    /// `receiver.method(..)` where the receiver has the given type:
+    /// the arguments are `self` followed by the inputs.
+    #[grammar(method $v0 . $v1)]
+    Method(Ty, MethodId),
+
+    /// `function(..)`: the arguments are the inputs of the function.
+    #[grammar(fn $v0)]
+    Fn(ValueId),
+}
+
+impl Generic {
+    pub fn describe(&self) -> String {
+        match self {
+            Generic::Class(name) => format!("class `{name:?}`"),
+            Generic::Variant(name, variant) => format!("variant `{name:?}::{variant:?}`"),
+            Generic::Method(receiver_ty, method) => {
+                format!("method `{method:?}` of `{:?}`", receiver_ty.strip_perm())
+            }
+            Generic::Fn(name) => format!("function `{name:?}`"),
+        }
+    }
+}
+
+judgment_fn! {
+    /// Elaborate the generic `parameters` written at a use of `generic`.
+    /// If they were omitted but `generic` has generic parameters, they are
+    /// inferred from `prefix_tys` (e.g., the type of a method's receiver)
+    /// followed by the types of the arguments `exprs`.
+    ///
+    /// Inference only proposes parameters: the caller goes on to check
+    /// the arguments against the elaborated declaration as usual.
+    pub fn elaborate_parameters(
+        env: Env,
+        live_after: LivePlaces,
+        generic: Generic,
+        parameters: Vec<Parameter>,
+        prefix_tys: Vec<Ty>,
+        exprs: Vec<Expr>,
+    ) => Vec<Parameter> {
+        debug(generic, parameters, prefix_tys, exprs, env, live_after)
+
+        (
+            (if !env.needs_inference(&generic, &parameters))!
+            ----------------------------------- ("explicit")
+            (elaborate_parameters(env, _live_after, generic, parameters, _prefix_tys, _exprs) => parameters)
+        )
+
+        (
+            (if env.needs_inference(&generic, &parameters))!
+            (type_argument_exprs(env, live_after, exprs) => arg_tys)
+            (let arg_tys: Vec<Ty> = prefix_tys.iter().chain(arg_tys.iter()).cloned().collect())
+            (let parameters = env.infer_parameters(&generic, &arg_tys)?)
+            ----------------------------------- ("inferred")
+            (elaborate_parameters(env, live_after, generic, parameters, prefix_tys, exprs) => parameters)
+        )
+    }
+}
+
+judgment_fn! {
+    /// Type each of `exprs` in turn, returning their types.
+    /// The resulting environment is discarded: once the parameters are known,
+    /// the arguments are typed again against their declared types.
+    fn type_argument_exprs(
+        env: Env,
+        live_after: LivePlaces,
+        exprs: Vec<Expr>,
+    ) => Vec<Ty> {
+        debug(exprs, env, live_after)
+
+        (
+            ----------------------------------- ("none")
+            (type_argument_exprs(_env, _live_after, ()) => ())
+        )
+
+        (
+            (type_expr(env, live_after.before(exprs), expr) => (env, ty))
+            (type_argument_exprs(env, live_after, exprs) => tys)
+            (let tys: Vec<Ty> = std::iter::once(ty.clone()).chain(tys.iter().cloned()).collect())
+            ----------------------------------- ("cons")
+            (type_argument_exprs(env, live_after, Cons(expr, exprs)) => tys)
+        )
+    }
+}
+
+/// The generic parameters written in `expr`, if it is a use of a generic (see [`Generic`]).
+pub fn written_parameters(expr: &Expr) -> &[Parameter] {
+    match expr {
+        Expr::New(_, parameters, _)
+        | Expr::NewVariant(_, parameters, _, _)
+        | Expr::Call(_, _, parameters, _)
+        | Expr::CallFn(_, parameters, _) => parameters,
+        _ => &[],
+    }
+}
+
+impl Env {
+    /// Returns `block` with the parameters inferred for each use of a generic within it
+    /// (see [`Env::elaborations`]) written out, so that it can be run without inferring them again.
+    pub fn elaborate_block(&self, block: &Block) -> Fallible<Block> {
+        elaborate_block(self.elaborations(), block)
+    }
+
+    /// True if no `parameters` were written for `generic` even though it has generic
+    /// parameters, in which case they must be inferred (see [`Env::infer_parameters`]).
+    pub fn needs_inference(&self, generic: &Generic, parameters: &[Parameter]) -> bool {
+        parameters.is_empty()
+            && self
+                .open_generic_existentially(generic)
+                .map_or(false, |(vars, _)| !vars.is_empty())
+    }
+
+    /// Infer the generic parameters of `generic` given `arg_tys`, the types of
+    /// the arguments it is applied to. Each declared argument type is unified with
+    /// the corresponding argument type; an error is reported if some parameter
+    /// is not determined by the arguments or if they determine conflicting values.
+    pub fn infer_parameters(&self, generic: &Generic, arg_tys: &[Ty]) -> Fallible<Vec<Parameter>> {
+        let (vars, declared_tys) = self.open_generic_existentially(generic)?;
+
+        let mut unifier = Unifier::default();
+        for (declared_ty, arg_ty) in declared_tys.iter().zip(arg_tys) {
+            unifier.unify_ty(declared_ty, arg_ty);
+        }
+
+        vars.iter()
+            .enumerate()
+            .map(|(index, var)| {
+                let kind = match var.kind {
+                    Kind::Ty => "type",
+                    Kind::Perm => "perm",
+                };
+                if let Some((a, b)) = unifier.conflicts.get(var) {
+                    bail!(
+                        "ambiguous {kind} parameter {index} of {}: could be `{a:?}` or `{b:?}`",
+                        generic.describe()
+                    );
+                }
+                match unifier.solutions.get(var) {
+                    Some(parameter) => Ok(parameter.clone()),
+                    None => bail!(
+                        "cannot infer {kind} parameter {index} of {}, it must be given explicitly",
+                        generic.describe()
+                    ),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the generic parameters of `generic`, as fresh existential variables,
+    /// along with the declared types of its arguments in terms of those variables.
+    fn open_generic_existentially(
+        &self,
+        generic: &Generic,
+    ) -> Fallible<(Vec<ExistentialVar>, Vec<Ty>)> {
+        match generic {
+            Generic::Class(name) => {
+                let class_decl = self.program().class_named(name)?;
+                let (vars, data) = self.open_existentially(&class_decl.binder)?;
+                Ok((vars, data.fields.into_iter().map(|f| f.ty).collect()))
+            }
+            Generic::Variant(name, variant) => {
+                let enum_decl = self.program().enum_named(name)?;
+                let (vars, data) = self.open_existentially(&enum_decl.binder)?;
+                let (_, variant_decl) = data.variant_named(variant)?;
+                Ok((
+                    vars,
+                    variant_decl.fields.iter().map(|f| f.ty.clone()).collect(),
+                ))
+            }
+            Generic::Method(receiver_ty, method) => {
+                let (self_ty, method_decl) = self.method_decl(receiver_ty, method)?;
+                let (vars, data) = self.open_existentially(&method_decl.binder)?;
+                let this_ty = Ty::apply_perm(&data.this.perm, self_ty);
+                let input_tys = data.inputs.into_iter().map(|input| input.ty);
+                Ok((vars, std::iter::once(this_ty).chain(input_tys).collect()))
+            }
+            Generic::Fn(name) => {
+                let fn_decl = self.program().fn_named(name)?;
+                let (vars, data) = self.open_existentially(&fn_decl.binder)?;
+                Ok((
+                    vars,
+                    data.inputs.into_iter().map(|input| input.ty).collect(),
+                ))
+            }
+        }
+    }
+
+    /// Returns the declaration of the method `method` invoked on a receiver of type
+    /// `receiver_ty`, along with the receiver type without its permissions.
+    /// The method is found as in `resolve_method`: among the methods of the class
+    /// and of its impls or, for a type variable, of the traits it is assumed to implement.
+    fn method_decl(&self, receiver_ty: &Ty, method: &MethodId) -> Fallible<(Ty, MethodDecl)> {
+        let self_ty = receiver_ty.strip_perm();
+        let methods: Vec<MethodDecl> = match &self_ty {
+            Ty::NamedTy(
+                named_ty @ NamedTy {
+                    name: TypeName::Id(class_name),
+                    parameters,
+                },
+            ) => {
+                let class_decl = self.program().class_named(class_name)?;
+                let class_data = class_decl.binder.instantiate_with(parameters)?;
+                let impls = self.program().impls_for(named_ty)?;
+                class_data
+                    .methods
+                    .into_iter()
+                    .chain(impls.into_iter().flat_map(|i| i.methods))
+                    .collect()
+            }
+            Ty::Var(_) => self
+                .assumed_traits(&self_ty)
+                .iter()
+                .map(|trait_name| Ok(self.program().trait_named(trait_name)?.methods.clone()))
+                .collect::<Fallible<Vec<_>>>()?
+                .into_iter()
+                .flatten()
+                .collect(),
+            _ => vec![],
+        };
+        match methods.into_iter().find(|m| m.name == *method) {
+            Some(method_decl) => Ok((self_ty, method_decl)),
+            None => bail!("no method `{method:?}` on type `{receiver_ty:?}`"),
+        }
+    }
+}
+
+/// Solutions for existential variables found by unifying declared types
+/// (which may mention them) with the types of actual arguments (which do not).
+///
+/// Unification is syntactic and best-effort: where the two types do not have
+/// the same shape it simply gives up, leaving it to subtyping to report the
+/// mismatch once the inferred parameters have been substituted.
+#[derive(Default)]
+struct Unifier {
+    solutions: Map<ExistentialVar, Parameter>,
+    conflicts: Map<ExistentialVar, (Parameter, Parameter)>,
+}
+
+impl Unifier {
+    fn unify_parameter(&mut self, declared: &Parameter, actual: &Parameter) {
+        match (declared, actual) {
+            (Parameter::Ty(declared), Parameter::Ty(actual)) => self.unify_ty(declared, actual),
+            (Parameter::Perm(declared), Parameter::Perm(actual)) => {
+                self.unify_perm(declared, actual)
+            }
+            _ => {}
+        }
+    }
+
+    fn unify_ty(&mut self, declared: &Ty, actual: &Ty) {
+        match declared {
+            Ty::Var(Variable::ExistentialVar(var)) => self.bind(var, actual),
+            Ty::Var(_) => {}
+            Ty::NamedTy(NamedTy { name, parameters }) => {
+                // The permissions on `actual` are checked by subtyping;
+                // we only need the parameters of the named type.
+                if let Some(actual) = actual.to_named_ty() {
+                    if *name == actual.name {
+                        for (d, a) in parameters.iter().zip(&actual.parameters) {
+                            self.unify_parameter(d, a);
+                        }
+                    }
+                }
+            }
+            Ty::ApplyPerm(perm, ty) => match (&**ty, actual) {
+                (Ty::ApplyPerm(..), Ty::ApplyPerm(actual_perm, actual_ty)) => {
+                    self.unify_perm(perm, actual_perm);
+                    self.unify_ty(ty, actual_ty);
+                }
+                (Ty::ApplyPerm(..), _) => {}
+                (_, _) => {
+                    // A single permission like `P T` matches all the
+                    // permissions on `actual`, e.g. `ref[x] mut[y] Data`.
+                    let (actual_perm, actual_ty) = split_perms(actual);
+                    self.unify_perm(perm, &actual_perm);
+                    self.unify_ty(ty, &actual_ty);
+                }
+            },
+        }
+    }
+
+    fn unify_perm(&mut self, declared: &Perm, actual: &Perm) {
+        match (declared, actual) {
+            (Perm::Var(Variable::ExistentialVar(var)), _) => self.bind(var, actual),
+            (Perm::Apply(d1, d2), Perm::Apply(a1, a2)) => {
+                self.unify_perm(d1, a1);
+                self.unify_perm(d2, a2);
+            }
+            _ => {}
+        }
+    }
+
+    /// Record `value` as the solution for `var`. If `var` already has a different solution,
+    /// the two are merged if they are references (or mutable references) to the same type
+    /// from different places (e.g., `ref[a] Data` and `ref[b] Data` give `ref[a, b] Data`);
+    /// otherwise, the conflict is recorded.
+    fn bind(&mut self, var: &ExistentialVar, value: impl Upcast<Parameter>) {
+        let value: Parameter = value.upcast();
+        let merged = match self.solutions.get(var) {
+            None => Some(value.clone()),
+            Some(previous) => merge_parameters(previous, &value),
+        };
+        match merged {
+            Some(merged) => {
+                self.solutions.insert(var.clone(), merged);
+            }
+            None => {
+                let previous = self.solutions[var].clone();
+                self.conflicts
+                    .entry(var.clone())
+                    .or_insert((previous, value));
+            }
+        }
+    }
+}
+
+/// Splits `ty` into the permissions applied to it, composed into one
+/// (`given` if there are none), and the type they are applied to.
+fn split_perms(ty: &Ty) -> (Perm, Ty) {
+    match ty {
+        Ty::ApplyPerm(perm, ty) => match split_perms(ty) {
+            (Perm::Given, ty) => (perm.clone(), ty),
+            (inner_perm, ty) => (Perm::apply(perm, inner_perm), ty),
+        },
+        Ty::NamedTy(_) | Ty::Var(_) => (Perm::Given, ty.clone()),
+    }
+}
+
+fn merge_parameters(a: &Parameter, b: &Parameter) -> Option<Parameter> {
+    if a == b {
+        return Some(a.clone());
+    }
+    match (a, b) {
+        (Parameter::Perm(a), Parameter::Perm(b)) => merge_perms(a, b).map(|p| p.upcast()),
+        (Parameter::Ty(Ty::ApplyPerm(p, a)), Parameter::Ty(Ty::ApplyPerm(q, b))) if a == b => {
+            merge_perms(p, q).map(|perm| Ty::apply_perm(perm, a).upcast())
+        }
+        _ => None,
+    }
+}
+
+fn merge_perms(a: &Perm, b: &Perm) -> Option<Perm> {
+    match (a, b) {
+        (Perm::Rf(a), Perm::Rf(b)) => {
+            Some(Perm::rf(a.iter().chain(b).cloned().collect::<Set<_>>()))
+        }
+        (Perm::Mt(a), Perm::Mt(b)) => {
+            Some(Perm::mt(a.iter().chain(b).cloned().collect::<Set<_>>()))
+        }
+        _ => None,
+    }
+}
+
+fn elaborate_block(elaborations: &Elaborations, block: &Block) -> Fallible<Block> {
+    Ok(Block {
+        statements: block
+            .statements
+            .iter()
+            .map(|statement| elaborate_statement(elaborations, statement))
+            .collect::<Fallible<_>>()?,
+        span: block.span.clone(),
+    })
+}
+
+fn elaborate_statement(elaborations: &Elaborations, statement: &Statement) -> Fallible<Statement> {
+    Ok(match statement {
+        Statement::Expr(expr, span) => {
+            Statement::Expr(elaborate_expr(elaborations, expr)?, span.clone())
+        }
+        Statement::Let(id, ascription, expr, span) => Statement::Let(
+            id.clone(),
+            ascription.clone(),
+            elaborate_arc_expr(elaborations, expr)?,
+            span.clone(),
+        ),
+        Statement::Reassign(place, expr, span) => Statement::Reassign(
+            place.clone(),
+            elaborate_expr(elaborations, expr)?,
+            span.clone(),
+        ),
+        Statement::Loop(block, span) => {
+            Statement::Loop(elaborate_block(elaborations, block)?, span.clone())
+        }
+        Statement::Break(span) => Statement::Break(span.clone()),
+        Statement::Return(expr, span) => {
+            Statement::Return(elaborate_expr(elaborations, expr)?, span.clone())
+        }
+        Statement::Print(expr, span) => {
+            Statement::Print(elaborate_expr(elaborations, expr)?, span.clone())
+        }
+    })
+}
+
+fn elaborate_arc_expr(elaborations: &Elaborations, expr: &Arc<Expr>) -> Fallible<Arc<Expr>> {
+    Ok(Arc::new(elaborate_expr(elaborations, expr)?))
+}
+
+fn elaborate_exprs(elaborations: &Elaborations, exprs: &[Expr]) -> Fallible<Vec<Expr>> {
+    exprs
+        .iter()
+        .map(|expr| elaborate_expr(elaborations, expr))
+        .collect()
+}
+
+fn elaborate_arguments(elaborations: &Elaborations, arguments: &Arguments) -> Fallible<Arguments> {
+    Ok(arguments.with_exprs(elaborate_exprs(elaborations, &arguments.exprs)?))
+}
+
+/// Elaborate `expr` and its subexpressions. The parameters of `expr` are looked up
+/// before its subexpressions are elaborated, as that is the form in which it was typed.
+fn elaborate_expr(elaborations: &Elaborations, expr: &Expr) -> Fallible<Expr> {
+    let mut inferred = elaborations.get(expr).into_iter().flatten();
+    let parameters = match (inferred.next(), inferred.next()) {
+        (None, _) => written_parameters(expr).to_vec(),
+        (Some(parameters), None) => parameters.clone(),
+        (Some(_), Some(_)) => bail!(
+            "the parameters inferred for `{expr:?}` differ between the times it is checked, \
+             they must be given explicitly"
+        ),
+    };
+    let e = |expr: &Arc<Expr>| elaborate_arc_expr(elaborations, expr);
+
+    Ok(match expr {
+        Expr::Integer(_)
+        | Expr::String(_)
+        | Expr::Char(_)
+        | Expr::True
+        | Expr::False
+        | Expr::Place(_)
+        | Expr::Clear(_)
+        | Expr::SizeOf(_)
+        | Expr::Panic => expr.clone(),
+        Expr::Block(block) => Expr::Block(elaborate_block(elaborations, block)?),
+        Expr::Or(lhs, rhs) => Expr::Or(e(lhs)?, e(rhs)?),
+        Expr::And(lhs, rhs) => Expr::And(e(lhs)?, e(rhs)?),
+        Expr::Comparison(lhs, op, rhs) => Expr::Comparison(e(lhs)?, op.clone(), e(rhs)?),
+        Expr::Additive(lhs, op, rhs) => Expr::Additive(e(lhs)?, op.clone(), e(rhs)?),
+        Expr::Multiplicative(lhs, op, rhs) => Expr::Multiplicative(e(lhs)?, op.clone(), e(rhs)?),
+        Expr::Unary(op, expr) => Expr::Unary(op.clone(), e(expr)?),
+        Expr::Share(expr) => Expr::Share(e(expr)?),
+        Expr::Await(expr) => Expr::Await(e(expr)?),
+        Expr::Spawn(expr) => Expr::Spawn(e(expr)?),
+        Expr::Closure(inputs, output, body) => Expr::Closure(
+            inputs.clone(),
+            output.clone(),
+            elaborate_block(elaborations, body)?,
+        ),
+        Expr::Tuple(exprs) => Expr::Tuple(elaborate_exprs(elaborations, exprs)?),
+        Expr::Call(receiver, method_name, _, arguments) => Expr::Call(
+            e(receiver)?,
+            method_name.clone(),
+            parameters,
+            elaborate_arguments(elaborations, arguments)?,
+        ),
+        Expr::CallFn(fn_name, _, arguments) => Expr::CallFn(
+            fn_name.clone(),
+            parameters,
+            elaborate_arguments(elaborations, arguments)?,
+        ),
+        Expr::New(class_name, _, arguments) => Expr::New(
+            class_name.clone(),
+            parameters,
+            elaborate_arguments(elaborations, arguments)?,
+        ),
+        Expr::NewVariant(enum_name, _, variant_name, arguments) => Expr::NewVariant(
+            enum_name.clone(),
+            parameters,
+            variant_name.clone(),
+            elaborate_arguments(elaborations, arguments)?,
+        ),
+        Expr::Match(scrutinee, arms) => Expr::Match(
+            e(scrutinee)?,
+            arms.iter()
+                .map(|arm| {
+                    Ok(MatchArm {
+                        body: elaborate_block(elaborations, &arm.body)?,
+                        ..arm.clone()
+                    })
+                })
+                .collect::<Fallible<_>>()?,
+        ),
+        Expr::If(cond, if_true, if_false) => Expr::If(e(cond)?, e(if_true)?, e(if_false)?),
+        Expr::ArrayNew(params, len) => Expr::ArrayNew(params.clone(), e(len)?),
+        Expr::ArrayCapacity(params, array) => Expr::ArrayCapacity(params.clone(), e(array)?),
+        Expr::ArrayGive(params, array, index) => {
+            Expr::ArrayGive(params.clone(), e(array)?, e(index)?)
+        }
+        Expr::ArrayDrop(params, array, from, to) => {
+            Expr::ArrayDrop(params.clone(), e(array)?, e(from)?, e(to)?)
+        }
+        Expr::ArrayWrite(params, array, index, value) => {
+            Expr::ArrayWrite(params.clone(), e(array)?, e(index)?, e(value)?)
+        }
+        Expr::IsLastRef(params, expr) => Expr::IsLastRef(params.clone(), e(expr)?),
+    })
+}
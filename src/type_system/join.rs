@@ -0,0 +1,95 @@
+use anyhow::bail;
+use formality_core::{Fallible, To, Upcast};
+
+use crate::grammar::{ty_impls::PermTy, NamedTy, Parameter, Perm, Ty};
+
+use super::{env::Env, types::check_or_same_category};
+
+/// Compute a type that both `a` and `b` can be upcast to, used when the two branches
+/// of an `if` produce values (or leave variables) of different types.
+///
+/// The two types must have the same shape; only their permissions may differ.
+/// Where they do, the result has the permission `or(perm_a, perm_b)`.
+pub fn join_tys(a: &Ty, b: &Ty) -> Fallible<Ty> {
+    if a == b {
+        return Ok(a.clone());
+    }
+
+    let PermTy(perm_a, ty_a) = a.to();
+    let PermTy(perm_b, ty_b) = b.to();
+
+    let ty = match (&ty_a, &ty_b) {
+        _ if ty_a == ty_b => ty_a.clone(),
+        (Ty::NamedTy(named_a), Ty::NamedTy(named_b))
+            if named_a.name == named_b.name
+                && named_a.parameters.len() == named_b.parameters.len() =>
+        {
+            let parameters = named_a
+                .parameters
+                .iter()
+                .zip(&named_b.parameters)
+                .map(|(pa, pb)| join_parameters(pa, pb))
+                .collect::<Fallible<Vec<_>>>()?;
+            NamedTy {
+                name: named_a.name.clone(),
+                parameters,
+            }
+            .upcast()
+        }
+        _ => bail!("cannot join types `{a:?}` and `{b:?}`"),
+    };
+
+    Ok(PermTy(join_perms(&perm_a, &perm_b), ty).upcast())
+}
+
+fn join_parameters(a: &Parameter, b: &Parameter) -> Fallible<Parameter> {
+    match (a, b) {
+        (Parameter::Ty(a), Parameter::Ty(b)) => Ok(join_tys(a, b)?.upcast()),
+        (Parameter::Perm(a), Parameter::Perm(b)) => Ok(join_perms(a, b).upcast()),
+        _ => bail!("cannot join parameters `{a:?}` and `{b:?}`"),
+    }
+}
+
+/// Join two permissions into `or(a, b)` (see [`Perm::flattened_or`]).
+fn join_perms(a: &Perm, b: &Perm) -> Perm {
+    if a == b {
+        return a.clone();
+    }
+
+    Perm::flattened_or([a.clone(), b.clone()])
+}
+
+/// Check that each `or(..)` permission appearing in `ty` is well-formed
+/// (see [`check_or_same_category`]). A join of a `given` value with a
+/// shared one, for example, has no meaningful permission.
+pub fn check_joined_ty(env: &Env, ty: &Ty) -> Fallible<()> {
+    match ty {
+        Ty::NamedTy(named_ty) => {
+            for parameter in &named_ty.parameters {
+                match parameter {
+                    Parameter::Ty(ty) => check_joined_ty(env, ty)?,
+                    Parameter::Perm(perm) => check_joined_perm(env, perm)?,
+                }
+            }
+            Ok(())
+        }
+        Ty::Var(_) => Ok(()),
+        Ty::ApplyPerm(perm, ty) => {
+            check_joined_perm(env, perm)?;
+            check_joined_ty(env, ty)
+        }
+    }
+}
+
+fn check_joined_perm(env: &Env, perm: &Perm) -> Fallible<()> {
+    match perm {
+        Perm::Or(perms) => check_or_same_category(env, perms),
+        Perm::Apply(l, r) => {
+            check_joined_perm(env, l)?;
+            check_joined_perm(env, r)
+        }
+        Perm::Mv(_) | Perm::Given | Perm::Shared | Perm::Rf(_) | Perm::Mt(_) | Perm::Var(_) => {
+            Ok(())
+        }
+    }
+}
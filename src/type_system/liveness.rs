@@ -9,17 +9,21 @@ use std::sync::Arc;
 
 use formality_core::{cast_impl, Set, SetExt, Upcast};
 
-use crate::grammar::{Block, Expr, Place, PlaceExpr, Statement, Var};
+use crate::grammar::{Arguments, Block, Expr, MatchArm, Place, PlaceExpr, Statement, Var};
 
 /// Tracks the set of live variables at a given point in execution.
 /// The `Default` impl returns an empty set.
-#[derive(Clone, Default, Debug, Ord, Eq, PartialEq, PartialOrd, Hash)]
+#[derive(Clone, Default, Ord, Eq, PartialEq, PartialOrd, Hash)]
 pub struct LivePlaces {
     /// A place `p` is read if it is read from or accessed (e.g., `p.ref`)
     accessed: Set<Place>,
 
     /// A place `p` is traversed if some subpart of it is assigned to (e.g., `p.f = q`)
     traversed: Set<Place>,
+
+    /// When inside of a `loop`, the places live after the innermost enclosing loop.
+    /// These are the places live just before a `break`.
+    loop_exit: Option<Arc<LivePlaces>>,
 }
 
 cast_impl!(LivePlaces);
@@ -37,6 +41,12 @@ impl LivePlaces {
         term.adjust_live_vars(self.clone())
     }
 
+    /// Compute a new set of live-vars just before `term`, which may or may not be evaluated
+    /// (e.g., the right operand of `&&`): places live before `term` or after it.
+    pub fn before_maybe(&self, term: &impl AdjustLiveVars) -> Self {
+        self.before(term).union(self.clone())
+    }
+
     /// Compute a new set of live-vars just before `terms` have been evaluated.
     pub fn before_all(&self, terms: impl IntoIterator<Item = impl AdjustLiveVars>) -> Self {
         terms
@@ -63,12 +73,74 @@ impl LivePlaces {
         self
     }
 
+    /// Union of the places live in `self` and `other`.
+    /// The loop context is taken from `self` unless it has none.
     pub fn union(self, other: LivePlaces) -> Self {
         let accessed = self.accessed.union_with(other.accessed);
         let traversed = self.traversed.union_with(other.traversed);
+        let loop_exit = self.loop_exit.or(other.loop_exit);
         Self {
             accessed,
             traversed,
+            loop_exit,
+        }
+    }
+
+    /// True if these are the places live at a point inside of a `loop`.
+    pub fn in_loop(&self) -> bool {
+        self.loop_exit.is_some()
+    }
+
+    /// Places live just before a `break`: the places live after the innermost enclosing
+    /// loop. The loop context itself is unchanged, since a `break` does not leave the
+    /// loop body syntactically. Outside of a loop, returns `self` unchanged.
+    pub fn at_break(self) -> Self {
+        match &self.loop_exit {
+            Some(loop_exit) => Self {
+                accessed: loop_exit.accessed.clone(),
+                traversed: loop_exit.traversed.clone(),
+                loop_exit: self.loop_exit.clone(),
+            },
+            None => self,
+        }
+    }
+
+    /// Places live just before a `return`: nothing in the method is live after it.
+    pub fn at_return(self) -> Self {
+        Self {
+            accessed: Set::new(),
+            traversed: Set::new(),
+            loop_exit: self.loop_exit,
+        }
+    }
+
+    /// Places live at the end of the body of a loop whose head has live places `self`,
+    /// where `loop_exit` are the places live after the loop. Control flows from the end
+    /// of the body back to the head and from any `break` to the loop exit.
+    pub fn loop_body_exit(&self, loop_exit: &LivePlaces) -> Self {
+        Self {
+            accessed: self.accessed.clone(),
+            traversed: self.traversed.clone(),
+            loop_exit: Some(Arc::new(loop_exit.clone())),
+        }
+    }
+
+    /// Compute the places live at the head of `loop body`, given that `self` are the
+    /// places live after the loop. This is a fixed point: places live at the head
+    /// are live at the end of the body, since the body may execute again.
+    /// We start from nothing live and iterate until no new places are found.
+    pub fn loop_head(&self, body: &Block) -> Self {
+        let mut head = self.clone().at_return();
+        loop {
+            let before_body = body.adjust_live_vars(head.loop_body_exit(self));
+            let next = head.clone().union(Self {
+                loop_exit: None,
+                ..before_body
+            });
+            if next == head {
+                return head;
+            }
+            head = next;
         }
     }
 
@@ -81,6 +153,18 @@ impl LivePlaces {
     }
 }
 
+impl std::fmt::Debug for LivePlaces {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut s = f.debug_struct("LivePlaces");
+        s.field("accessed", &self.accessed);
+        s.field("traversed", &self.traversed);
+        if let Some(loop_exit) = &self.loop_exit {
+            s.field("loop_exit", loop_exit);
+        }
+        s.finish()
+    }
+}
+
 pub trait AdjustLiveVars: std::fmt::Debug {
     fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces;
 }
@@ -108,19 +192,19 @@ impl AdjustLiveVars for Vec<Statement> {
 impl AdjustLiveVars for Statement {
     fn adjust_live_vars(&self, live: LivePlaces) -> LivePlaces {
         match self {
-            Statement::Expr(expr) => expr.adjust_live_vars(live),
-            Statement::Let(var, _ty, expr) => expr.adjust_live_vars(live.overwritten(var)),
-            Statement::Reassign(place, expr) => {
+            Statement::Expr(expr, _) => expr.adjust_live_vars(live),
+            Statement::Let(var, _ty, expr, _) => expr.adjust_live_vars(live.overwritten(var)),
+            Statement::Reassign(place, expr, _) => {
                 // x.f.g will be assigned...
                 let live = live.overwritten(place);
 
                 // ...and computing the expression
                 expr.adjust_live_vars(live)
             }
-            Statement::Loop(expr) => expr.adjust_live_vars(live),
-            Statement::Break => live,
-            Statement::Return(expr) => expr.adjust_live_vars(live),
-            Statement::Print(expr) => expr.adjust_live_vars(live),
+            Statement::Loop(body, _) => live.loop_head(body),
+            Statement::Break(_) => live.at_break(),
+            Statement::Return(expr, _) => expr.adjust_live_vars(live.at_return()),
+            Statement::Print(expr, _) => expr.adjust_live_vars(live),
         }
     }
 }
@@ -133,23 +217,46 @@ impl AdjustLiveVars for Vec<Expr> {
     }
 }
 
+impl AdjustLiveVars for Arguments {
+    fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces {
+        self.exprs.adjust_live_vars(vars)
+    }
+}
+
 impl AdjustLiveVars for Expr {
     fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces {
         match self {
             Expr::Block(block) => block.adjust_live_vars(vars),
-            Expr::Integer(_) | Expr::True | Expr::False => vars,
-            Expr::BinaryOp(lhs, _op, rhs) => {
+            Expr::Integer(_) | Expr::String(_) | Expr::Char(_) | Expr::True | Expr::False => vars,
+            Expr::Comparison(lhs, _, rhs)
+            | Expr::Additive(lhs, _, rhs)
+            | Expr::Multiplicative(lhs, _, rhs) => {
                 let vars = rhs.adjust_live_vars(vars);
                 lhs.adjust_live_vars(vars)
             }
+            // The right operand is only evaluated if the left one does not decide the result.
+            Expr::Or(lhs, rhs) | Expr::And(lhs, rhs) => {
+                lhs.adjust_live_vars(vars.before_maybe(rhs))
+            }
+            Expr::Unary(_op, expr) => expr.adjust_live_vars(vars),
             Expr::Place(place) => place.adjust_live_vars(vars),
             Expr::Tuple(exprs) => exprs.adjust_live_vars(vars),
-            Expr::Share(expr) => expr.adjust_live_vars(vars),
+            Expr::Share(expr) | Expr::Await(expr) | Expr::Spawn(expr) => {
+                expr.adjust_live_vars(vars)
+            }
+            // Creating a closure accesses the places it captures; its body runs
+            // only when it is called, through the closure value.
+            Expr::Closure(inputs, _output, body) => Expr::closure_captures(inputs, body)
+                .iter()
+                .fold(vars, |vars, (var, _access)| vars.accessed(var)),
             Expr::Call(func, _method_name, _parameters, args) => {
                 let vars = args.adjust_live_vars(vars);
                 func.adjust_live_vars(vars)
             }
+            Expr::CallFn(_fn_name, _parameters, args) => args.adjust_live_vars(vars),
             Expr::New(_ty, _parameters, args) => args.adjust_live_vars(vars),
+            Expr::NewVariant(_ty, _parameters, _variant, args) => args.adjust_live_vars(vars),
+            Expr::Match(scrutinee, arms) => scrutinee.adjust_live_vars(vars.before_all(arms)),
             Expr::Clear(_) => vars,
             Expr::If(cond, if_true, if_false) => {
                 let if_true_vars = if_true.adjust_live_vars(vars.clone());
@@ -181,11 +288,26 @@ impl AdjustLiveVars for Expr {
 
 impl AdjustLiveVars for Block {
     fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces {
-        let Block { statements } = self;
+        let Block { statements, .. } = self;
         statements.adjust_live_vars(vars)
     }
 }
 
+impl AdjustLiveVars for MatchArm {
+    fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces {
+        let MatchArm {
+            variant: _,
+            bindings,
+            body,
+        } = self;
+        bindings
+            .iter()
+            .fold(body.adjust_live_vars(vars), |vars, binding| {
+                vars.overwritten(Var::Id(binding.clone()))
+            })
+    }
+}
+
 impl AdjustLiveVars for PlaceExpr {
     fn adjust_live_vars(&self, vars: LivePlaces) -> LivePlaces {
         self.place.adjust_live_vars(vars)
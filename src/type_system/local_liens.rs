@@ -1,7 +1,7 @@
 use formality_core::{judgment_fn, term, Set, SetExt};
 
 use crate::{
-    grammar::{NamedTy, Parameter, Perm, Place, Ty, Variable},
+    grammar::{NamedTy, Parameter, Perm, Place, Ty, TypeName, Variable},
     type_system::{env::Env, predicates::prove_is_copy},
 };
 
@@ -85,12 +85,22 @@ judgment_fn! {
         // (Ty::Var covered under "VARIABLES" below)
 
         (
+            (if !name.is_closure())!
             (let liens_parameters: Set<Lien> = Set::new())
             (for_all(parameter in parameters) with(liens_parameters)
                 (liens(env, parameter) => new_liens)
                 (let liens_parameters: Set<Lien> = (&liens_parameters).union_with(new_liens)))
             ----------------------------------- ("ty-named")
-            (liens(env, NamedTy { name: _, parameters }) => liens_parameters)
+            (liens(env, NamedTy { name, parameters }) => liens_parameters)
+        )
+
+        (
+            // A closure holds the liens of the places it captures for as long as it is live.
+            // Its input and output types describe values that only exist during a call.
+            (let (_inputs, _output, captured) = NamedTy::closure_parameters(&parameters)?)
+            (liens(env, captured) => liens)
+            ----------------------------------- ("ty-closure")
+            (liens(env, NamedTy { name: TypeName::Closure(_), parameters }) => liens)
         )
 
         (
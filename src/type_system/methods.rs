@@ -1,29 +1,98 @@
 use formality_core::judgment_fn;
 
 use crate::grammar::{
-    LocalVariableDecl, MethodBody, MethodDecl, MethodDeclBoundData, NamedTy, ThisDecl, Ty,
-    Var::This, VarianceKind,
+    Async, Binder, FnDecl, FnDeclBoundData, LocalVariableDecl, MethodBody, MethodDecl,
+    MethodDeclBoundData, NamedTy, Predicate, ThisDecl, Ty, UniversalVar, Var::This, VarianceKind,
 };
 
 use super::{
-    env::Env, expressions::can_type_expr_as, liveness::LivePlaces, predicates::check_predicates,
-    types::check_type,
+    env::Env, expressions::type_expr_as, liveness::LivePlaces, predicates::check_predicates,
+    tracked::place_not_abandoned, types::check_type,
 };
 
 // ANCHOR: check_method
 judgment_fn! {
+    /// Check a method of the class `class_ty`, returning the method with the
+    /// parameters inferred in its body written out (see `elaborate_program`).
     pub fn check_method(
         class_ty: NamedTy,
         env: Env,
         decl: MethodDecl,
-    ) => () {
+    ) => MethodDecl {
         debug(decl, class_ty, env)
 
         (
-            (let MethodDecl { name: _, binder } = decl)
+            (let MethodDecl { name: _, is_async, binder, span: _ } = decl)
             (let (env, vars, MethodDeclBoundData { this, inputs, output, predicates, body }) =
                 env.open_universally(binder))
 
+            (let ThisDecl { perm: this_perm } = &this)
+            (let this_ty = Ty::apply_perm(this_perm, class_ty))
+            (let env = env.push_local_variable(This, this_ty)?)
+
+            (let env = match is_async {
+                Async::Yes => env.with_async_body(),
+                Async::No => env.clone(),
+            })
+            (check_signature_and_body(env, vars, inputs, output, predicates, body) => body)
+            (let binder = Binder::new(vars.clone(), MethodDeclBoundData {
+                this: this.clone(),
+                inputs: inputs.clone(),
+                output: output.clone(),
+                predicates: predicates.clone(),
+                body: body.clone(),
+            }))
+            ----------------------------------- ("check_method")
+            (check_method(class_ty, env, decl) => MethodDecl { binder: binder.clone(), ..decl.clone() })
+        )
+    }
+}
+// ANCHOR_END: check_method
+
+// ANCHOR: check_fn
+judgment_fn! {
+    /// Check a function declared at the program level.
+    /// This is the same as [`check_method`] except that there is no `self`.
+    pub fn check_fn(
+        env: Env,
+        decl: FnDecl,
+    ) => FnDecl {
+        debug(decl, env)
+
+        (
+            (let FnDecl { name: _, binder, span: _ } = decl)
+            (let (env, vars, FnDeclBoundData { inputs, output, predicates, body }) =
+                env.open_universally(binder))
+            (check_signature_and_body(env, vars, inputs, output, predicates, body) => body)
+            (let binder = Binder::new(vars.clone(), FnDeclBoundData {
+                inputs: inputs.clone(),
+                output: output.clone(),
+                predicates: predicates.clone(),
+                body: body.clone(),
+            }))
+            ----------------------------------- ("check_fn")
+            (check_fn(env, decl) => FnDecl { binder: binder.clone(), ..decl.clone() })
+        )
+    }
+}
+// ANCHOR_END: check_fn
+
+// ANCHOR: check_signature_and_body
+judgment_fn! {
+    /// The steps shared by [`check_method`] and [`check_fn`] once the generic
+    /// parameters are opened as `vars` (and `self`, if any, is in scope):
+    /// check the signature, then the body with the inputs in scope.
+    fn check_signature_and_body(
+        env: Env,
+        vars: Vec<UniversalVar>,
+        inputs: Vec<LocalVariableDecl>,
+        output: Ty,
+        predicates: Vec<Predicate>,
+        body: MethodBody,
+    ) => MethodBody {
+        debug(body, inputs, output, predicates, vars, env)
+
+        (
             // Methods don't really care about variance, so they can assume all their
             // parameters are relative/atomic for purposes of WF checking.
             (let env = env.add_assumptions(
@@ -35,10 +104,6 @@ judgment_fn! {
             (check_predicates(env, predicates) => ())
             (let env = env.add_assumptions(predicates))
 
-            (let ThisDecl { perm: this_perm } = &this)
-            (let this_ty = Ty::apply_perm(this_perm, class_ty))
-            (let env = env.push_local_variable(This, this_ty)?)
-
             (let env = env.push_local_variable_decls(inputs)?)
 
             (for_all(input in inputs)
@@ -47,33 +112,43 @@ judgment_fn! {
 
             (check_type(env, output) => ())
 
-            (check_body(env, output, body) => ())
-            ----------------------------------- ("check_method")
-            (check_method(class_ty, env, decl) => ())
+            (check_body(env, output, body) => body)
+            ----------------------------------- ("check_signature_and_body")
+            (check_signature_and_body(env, vars, inputs, output, predicates, body) => body)
         )
     }
 }
-// ANCHOR_END: check_method
+// ANCHOR_END: check_signature_and_body
 
 // ANCHOR: check_body
 judgment_fn! {
+    /// Check that `body` can be typed as `output`, returning it with the
+    /// parameters inferred within it written out.
     fn check_body(
         env: Env,
         output: Ty,
         body: MethodBody,
-    ) => () {
+    ) => MethodBody {
         debug(body, output, env)
 
         (
             ----------------------------------- ("trusted")
-            (check_body(_env, _output, MethodBody::Trusted) => ())
+            (check_body(_env, _output, MethodBody::Trusted) => MethodBody::Trusted)
         )
 
         (
             (let live_after = LivePlaces::default())
-            (can_type_expr_as(env, live_after, block, output) => ())
+            (let env = env.with_output_ty(output))
+
+            // Inputs that are never used are dropped on entry.
+            (let live_on_entry = live_after.before(&block))
+            (for_all(var in env.local_variable_names())
+                (place_not_abandoned(env, &live_on_entry, var) => ()))
+
+            (type_expr_as(env, live_after, &block, output) => env)
+            (let block = env.elaborate_block(&block)?)
             ----------------------------------- ("block")
-            (check_body(env, output, MethodBody::Block(block)) => ())
+            (check_body(env, output, MethodBody::Block(block)) => MethodBody::Block(block.clone()))
         )
     }
 }
@@ -1,7 +1,10 @@
 use formality_core::{Fallible, Upcast};
 
 use crate::{
-    grammar::{ClassDeclBoundData, FieldDecl, NamedTy, Place, Projection, Ty, TypeName},
+    grammar::{
+        Atomic, ClassDeclBoundData, EnumDeclBoundData, FieldDecl, NamedTy, Place, Projection,
+        Ty, TypeName, ValueId,
+    },
     type_system::env::Env,
 };
 
@@ -46,6 +49,19 @@ impl Env {
         Ok((Some((owner_place, owner_ty)), proj_ty))
     }
 
+    /// True if `place` is a field declared `atomic`, which can be written
+    /// even through a permission that is not unique (e.g., `shared` or `ref`).
+    pub fn is_atomic_field(&self, place: &Place) -> Fallible<bool> {
+        let Some(Projection::Field(field_id)) = place.projections.last() else {
+            return Ok(false);
+        };
+        let owner_place = place.owner().unwrap();
+        let fields = self.place_fields(&owner_place)?;
+        Ok(fields
+            .iter()
+            .any(|field| field.name == *field_id && field.atomic == Atomic::Yes))
+    }
+
     fn type_projections(
         &self,
         place: &Place,
@@ -83,6 +99,10 @@ impl Env {
                 name: TypeName::Id(id),
                 parameters,
             }) => {
+                // The fields of an enum belong to its variants and can only be reached with `match`.
+                if self.program().enum_named(&id).is_ok() {
+                    return Ok(vec![]);
+                }
                 let class_decl = self.program().class_named(&id)?;
                 let ClassDeclBoundData {
                     predicates: _,
@@ -97,11 +117,11 @@ impl Env {
                 parameters: _,
             }) => anyhow::bail!("tuple fields not implemented"),
             Ty::NamedTy(NamedTy {
-                name: TypeName::Int | TypeName::Bool,
+                name: TypeName::Int | TypeName::Bool | TypeName::Char | TypeName::String,
                 parameters: _,
             }) => Ok(vec![]),
             Ty::NamedTy(NamedTy {
-                name: TypeName::Array,
+                name: TypeName::Array | TypeName::Future | TypeName::Closure(_),
                 parameters: _,
             }) => Ok(vec![]),
             Ty::Var(_) => Ok(vec![]),
@@ -119,4 +139,32 @@ impl Env {
             }
         }
     }
+
+    /// Returns the fields of the variant `variant` of the enum type `ty`,
+    /// with types adjusted due to the permissions on `ty` (as with [`Env::fields`]).
+    pub fn variant_fields(&self, ty: &Ty, variant: &ValueId) -> Fallible<Vec<FieldDecl>> {
+        match ty {
+            Ty::NamedTy(NamedTy {
+                name: TypeName::Id(id),
+                parameters,
+            }) => {
+                let enum_decl = self.program().enum_named(id)?;
+                let data: EnumDeclBoundData = enum_decl.binder.instantiate_with(parameters)?;
+                let (_, variant_decl) = data.variant_named(variant)?;
+                Ok(variant_decl.fields.clone())
+            }
+            Ty::NamedTy(_) | Ty::Var(_) => anyhow::bail!("cannot match on a value of type `{ty:?}`"),
+            Ty::ApplyPerm(perm, ty) => {
+                let fields = self.variant_fields(ty, variant)?;
+                Ok(fields
+                    .into_iter()
+                    .map(|field| FieldDecl {
+                        ty: Ty::apply_perm(perm, field.ty),
+                        atomic: field.atomic,
+                        name: field.name,
+                    })
+                    .collect())
+            }
+        }
+    }
 }
@@ -0,0 +1,263 @@
+//! Rewriting types that mention variables about to go out of scope
+//! so that they refer only to variables that remain in scope.
+//! See `md/wip/var-pop-normalization.md` for the design.
+
+use formality_core::{judgment_fn, Fallible, Set, To, Upcast};
+
+use crate::grammar::{ty_impls::PermTy, NamedTy, Parameter, Perm, Place, Ty, Var};
+
+use super::{
+    env::Env,
+    in_flight::{InFlight, Transform},
+    liveness::LivePlaces,
+    predicates::{prove_is_mut, prove_is_shareable},
+    redperms::{red_perm, Head, RedChain, RedLink, Tail},
+    subtypes::sub,
+};
+
+impl Env {
+    /// Removes the local variables `popped_vars` from the environment (e.g., when exiting the block
+    /// that declared them), normalizing `ty` and the types of the remaining variables so that
+    /// they no longer mention the popped variables (see [`normalize_ty_for_pop`]).
+    ///
+    /// A variable that is not live in `live_after` will never be read again, so its type is
+    /// left as is if it cannot be normalized; the same failure for a live variable
+    /// (or for `ty`) is a dangling reference and hence an error.
+    pub fn pop_local_variables_normalizing(
+        &self,
+        live_after: &LivePlaces,
+        popped_vars: &[Var],
+        ty: &Ty,
+    ) -> Fallible<(Env, Ty)> {
+        let ty = normalize_ty_for_pop(self, live_after, ty, popped_vars)?;
+        let env = self.with_local_variable_tys(|var, var_ty| {
+            if popped_vars.contains(var) {
+                return Ok(var_ty.clone());
+            }
+            match normalize_ty_for_pop(self, live_after, var_ty, popped_vars) {
+                Ok(var_ty) => Ok(var_ty),
+                Err(_) if !live_after.is_live(var) => Ok(var_ty.clone()),
+                Err(e) => Err(e),
+            }
+        })?;
+        let env = env.without_local_variables(popped_vars.to_vec())?;
+        Ok((env, ty))
+    }
+}
+
+judgment_fn! {
+    /// Normalizes the output type of a call before the temporaries holding its arguments
+    /// are popped (see [`normalize_ty_for_pop`]). `input_vars` are the inputs of the callee
+    /// that each temporary was passed as, in the same order, so that a dangling reference
+    /// can be reported in terms of the callee's signature rather than the temporaries.
+    pub fn normalize_output_for_pop(
+        env: Env,
+        live_after: LivePlaces,
+        output: Ty,
+        popped_vars: Vec<Var>,
+        input_vars: Vec<Var>,
+    ) => Ty {
+        debug(output, popped_vars, input_vars, env, live_after)
+
+        (
+            (let normalized = normalize_ty_for_pop(&env, &live_after, &output, &popped_vars)
+                .map_err(|error| match error.downcast_ref::<DanglingReference>() {
+                    Some(dangling) => dangling.renamed(&popped_vars, &input_vars).into(),
+                    None => error,
+                })?)
+
+            // Normalization only weakens the output type, so it must be a subtype of the result.
+            // If not, the normalization rules are buggy: this is not a user error, but we fail
+            // the judgment rather than panic so that the failure shows up in the proof tree.
+            (sub(env, live_after, output, normalized) => ())
+            --- ("normalize")
+            (normalize_output_for_pop(env, live_after, output, popped_vars, _input_vars) => normalized)
+        )
+    }
+}
+
+/// A permission that cannot be expressed without the variables being popped,
+/// e.g., a `ref` to a variable whose value is dropped as it goes out of scope.
+#[derive(Debug)]
+pub struct DanglingReference {
+    perm: Perm,
+    popped_vars: Vec<Var>,
+}
+
+impl DanglingReference {
+    /// The same error with each variable in `from` renamed to the one at the same index in `to`.
+    fn renamed(&self, from: &[Var], to: &[Var]) -> Self {
+        let rename = |var: &Var| match from.iter().position(|v| v == var) {
+            Some(index) => to[index].clone(),
+            None => var.clone(),
+        };
+        DanglingReference {
+            perm: self
+                .perm
+                .with_places_transformed(Transform::Rename(from, to)),
+            popped_vars: self.popped_vars.iter().map(rename).collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for DanglingReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "dangling reference: `{:?}` cannot be expressed without `{:?}`",
+            self.perm, self.popped_vars
+        )
+    }
+}
+
+impl std::error::Error for DanglingReference {}
+
+/// Rewrites `ty` so that it no longer mentions `popped_vars`.
+/// `env` must still contain the popped variables, since their types are
+/// needed to resolve permissions that refer to them.
+pub fn normalize_ty_for_pop(
+    env: &Env,
+    live_after: &LivePlaces,
+    ty: &Ty,
+    popped_vars: &[Var],
+) -> Fallible<Ty> {
+    let PermTy(perm, ty) = ty.to();
+    let perm = normalize_perm_for_pop(env, live_after, &perm, popped_vars)?;
+    let ty: Ty = match &ty {
+        Ty::NamedTy(NamedTy { name, parameters }) => {
+            let parameters = parameters
+                .iter()
+                .map(|parameter| match parameter {
+                    Parameter::Ty(ty) => {
+                        Ok(normalize_ty_for_pop(env, live_after, ty, popped_vars)?.upcast())
+                    }
+                    Parameter::Perm(perm) => {
+                        Ok(normalize_perm_for_pop(env, live_after, perm, popped_vars)?.upcast())
+                    }
+                })
+                .collect::<Fallible<Vec<Parameter>>>()?;
+            NamedTy {
+                name: name.clone(),
+                parameters,
+            }
+            .upcast()
+        }
+        Ty::Var(_) | Ty::ApplyPerm(..) => ty.clone(),
+    };
+    Ok(PermTy(perm, ty).upcast())
+}
+
+/// Rewrites `perm` so that it no longer mentions `popped_vars`.
+/// The permission is reduced to its chains (see [`red_perm`]), which resolves
+/// `given_from` links and extends `ref`/`mut` links through the types of the places they borrow from.
+/// Dead links to popped variables that remain are stripped (see [`strip_popped_dead_links`])
+/// and the resulting chains are combined with `or(..)`.
+pub fn normalize_perm_for_pop(
+    env: &Env,
+    live_after: &LivePlaces,
+    perm: &Perm,
+    popped_vars: &[Var],
+) -> Fallible<Perm> {
+    if !perm_mentions(perm, popped_vars) {
+        return Ok(perm.clone());
+    }
+
+    let (red_perm, _) = red_perm(env, live_after, perm).into_singleton()?;
+    let mut perms: Set<Perm> = Set::new();
+    for chain in red_perm.chains {
+        let Ok((chain, _)) = strip_popped_dead_links(env, popped_vars, chain).into_singleton()
+        else {
+            return Err(DanglingReference {
+                perm: perm.clone(),
+                popped_vars: popped_vars.to_vec(),
+            }
+            .into());
+        };
+        perms.insert(chain.upcast());
+    }
+    Ok(Perm::flattened_or(perms))
+}
+
+fn perm_mentions(perm: &Perm, popped_vars: &[Var]) -> bool {
+    match perm {
+        Perm::Mv(places) | Perm::Rf(places) | Perm::Mt(places) => {
+            places.iter().any(|place| popped_vars.contains(&place.var))
+        }
+        Perm::Apply(l, r) => perm_mentions(l, popped_vars) || perm_mentions(r, popped_vars),
+        Perm::Or(perms) => perms.iter().any(|p| perm_mentions(p, popped_vars)),
+        Perm::Given | Perm::Shared | Perm::Var(_) => false,
+    }
+}
+
+fn link_mentions(link: &RedLink, popped_vars: &[Var]) -> bool {
+    match link {
+        RedLink::Rfl(place)
+        | RedLink::Rfd(place)
+        | RedLink::Mtl(place)
+        | RedLink::Mtd(place)
+        | RedLink::Mv(place) => popped_vars.contains(&place.var),
+        RedLink::Shared | RedLink::Var(_) => false,
+    }
+}
+
+fn chain_mentions(chain: &RedChain, popped_vars: &[Var]) -> bool {
+    chain
+        .links
+        .iter()
+        .any(|link| link_mentions(link, popped_vars))
+}
+
+fn is_popped(place: &Place, popped_vars: &[Var]) -> bool {
+    popped_vars.contains(&place.var)
+}
+
+judgment_fn! {
+    /// Removes the links to `popped_vars` from `chain`, mirroring the weakenings
+    /// permitted by subtyping (see `red_chain_sub_chain`), so the result is a supertype of `chain`.
+    /// Fails if some link cannot be removed: this is a dangling borrow, e.g. a `ref`
+    /// to a popped variable whose value is owned (and hence dropped as it goes out of scope).
+    pub fn strip_popped_dead_links(
+        env: Env,
+        popped_vars: Vec<Var>,
+        chain: RedChain,
+    ) => RedChain {
+        debug(chain, popped_vars, env)
+
+        (
+            (if !chain_mentions(&chain, &popped_vars))!
+            --- ("unaffected")
+            (strip_popped_dead_links(_env, popped_vars, chain) => chain)
+        )
+
+        (
+            (if !link_mentions(&link, &popped_vars))!
+            (if chain_mentions(&tail, &popped_vars))
+            (strip_popped_dead_links(env, popped_vars, tail) => tail)
+            --- ("keep link")
+            (strip_popped_dead_links(env, popped_vars, Head(link, Tail(tail))) => Head(link.clone(), tail))
+        )
+
+        (
+            // As in subtyping, a dead `mut[p]` link can only be dropped if `p`'s type is
+            // shareable (see the guard pattern) and the rest of the chain is mut-based.
+            (if is_popped(&place, &popped_vars))!
+            (let ty_dead = env.place_ty(&place)?)
+            (prove_is_shareable(env, ty_dead) => ())
+            (prove_is_mut(env, tail) => ())
+            (strip_popped_dead_links(env, popped_vars, tail) => tail)
+            --- ("drop mut-dead")
+            (strip_popped_dead_links(env, popped_vars, Head(RedLink::Mtd(place), Tail(tail))) => tail)
+        )
+
+        (
+            // Similarly, a dead `ref[p]` link can be weakened to `shared`.
+            (if is_popped(&place, &popped_vars))!
+            (let ty_dead = env.place_ty(&place)?)
+            (prove_is_shareable(env, ty_dead) => ())
+            (prove_is_mut(env, tail) => ())
+            (strip_popped_dead_links(env, popped_vars, tail) => tail)
+            --- ("ref-dead to shared")
+            (strip_popped_dead_links(env, popped_vars, Head(RedLink::Rfd(place), Tail(tail))) => Head(RedLink::Shared, tail))
+        )
+    }
+}
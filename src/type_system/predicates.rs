@@ -3,7 +3,7 @@ use crate::{
     dada_lang::grammar::UniversalVar,
     grammar::{
         ClassPredicate, NamedTy, Parameter, ParameterPredicate, Perm, Place, Predicate, Ty,
-        TypeName, VarianceKind,
+        TypeName, ValueId, VarianceKind,
     },
 };
 use formality_core::{judgment::ProofTree, judgment_fn, Downcast, ProvenSet, Upcast};
@@ -42,6 +42,13 @@ judgment_fn! {
             ----------------------- ("variance")
             (check_predicate(env, Predicate::Variance(_kind, parameter)) => ())
         )
+
+        (
+            (let _trait_decl = env.program().trait_named(&trait_name)?)
+            (check_predicate_parameter(env, parameter) => ())
+            ----------------------- ("implements")
+            (check_predicate(env, Predicate::Implements(trait_name, parameter)) => ())
+        )
     }
 }
 
@@ -214,6 +221,38 @@ judgment_fn! {
     }
 }
 
+judgment_fn! {
+    /// A value of type `a` can be dropped implicitly, i.e., it does not own a value
+    /// of a `tracked class` (see [`Env::owns_tracked_value`]). Copy and `mut` values
+    /// never own one, whatever the type of the value they refer to.
+    pub fn prove_is_droppable(
+        env: Env,
+        a: Ty,
+    ) => () {
+        debug(a, env)
+
+        (
+            (if let false = env.owns_tracked_value(&a)?)
+            ---------------------------- ("untracked")
+            (prove_is_droppable(env, a) => ())
+        )
+
+        (
+            (if let true = env.owns_tracked_value(&a)?)
+            (prove_is_copy(env, a) => ())
+            ---------------------------- ("copy")
+            (prove_is_droppable(env, a) => ())
+        )
+
+        (
+            (if let true = env.owns_tracked_value(&a)?)
+            (prove_is_mut(env, a) => ())
+            ---------------------------- ("mut")
+            (prove_is_droppable(env, a) => ())
+        )
+    }
+}
+
 pub fn prove_is_move_if_some(
     env: impl Upcast<Env>,
     a: impl Upcast<Option<(Place, Parameter)>>,
@@ -312,6 +351,12 @@ judgment_fn! {
             ---------------------------- ("variance")
             (prove_predicate(env, Predicate::Variance(kind, parameter)) => ())
         )
+
+        (
+            (prove_implements_predicate(env, trait_name, p) => ())
+            ---------------------------- ("implements")
+            (prove_predicate(env, Predicate::Implements(trait_name, p)) => ())
+        )
     }
 }
 
@@ -816,6 +861,20 @@ judgment_fn! {
             (prove_boxed_predicate(env, NamedTy { name: TypeName::Array, .. }) => ())
         )
 
+        // Strings are boxed like arrays.
+        (
+            ----------------------------- ("boxed string")
+            (prove_boxed_predicate(env, NamedTy { name: TypeName::String, .. }) => ())
+        )
+
+        // So are classes declared `boxed class`.
+        (
+            (if let TypeName::Id(_) = &name)
+            (if let true = env.is_boxed_ty(&name)?)
+            ----------------------------- ("boxed class")
+            (prove_boxed_predicate(env, NamedTy { name, .. }) => ())
+        )
+
         // Perms don't matter.
         (
             (prove_boxed_predicate(env, &**ty) => ())
@@ -825,6 +884,35 @@ judgment_fn! {
     }
 }
 
+// --- Implements ---
+
+judgment_fn! {
+    fn prove_implements_predicate(
+        env: Env,
+        trait_name: ValueId,
+        p: Parameter,
+    ) => () {
+        debug(trait_name, p, env)
+
+        // A class implements a trait if an impl of the trait applies to it
+        // and the where-clauses of that impl hold.
+        (
+            (let impls = env.program().impls_for(&named_ty)?)
+            (impl_data in impls.into_iter().filter(|i| i.trait_name == *trait_name))
+            (prove_predicates(env, impl_data.predicates) => ())
+            ----------------------------- ("impl")
+            (prove_implements_predicate(env, trait_name, named_ty: NamedTy) => ())
+        )
+
+        // Perms don't matter.
+        (
+            (prove_predicate(env, Predicate::implements(&trait_name, &**ty)) => ())
+            ----------------------------- ("apply-perm")
+            (prove_implements_predicate(env, trait_name, Ty::ApplyPerm(_perm, ty)) => ())
+        )
+    }
+}
+
 // =========================================================================
 // Variance
 // =========================================================================
@@ -81,11 +81,11 @@ pub struct Given();
 /// Pattern-match helper for destructuring a chain into its first link and the rest.
 /// Used in `judgment_fn!` rules to match chain shapes like `(Shared :: Mtl(p) :: tail)`.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Head<H, T>(H, T);
+pub struct Head<H, T>(pub H, pub T);
 
 /// Pattern-match helper: the tail portion of a chain after a `Head` match.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Tail<T>(T);
+pub struct Tail<T>(pub T);
 
 judgment_fn! {
     /// Reduces `perm_a` and `perm_b` and then checks that `sub_perms` holds.
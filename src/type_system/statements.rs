@@ -1,13 +1,17 @@
 use formality_core::{judgment_fn, Cons};
 
 use crate::{
-    grammar::{Access, Ascription, Statement, Ty},
+    grammar::{Access, Ascription, Block, Statement, Ty},
     type_system::{
-        accesses::{env_permits_access, parameter_permits_access},
+        accesses::{env_permits_access, owners_permit_mutation, parameter_permits_access},
+        blocks::type_block,
         env::Env,
         expressions::{type_expr, type_expr_as},
         in_flight::InFlight,
-        predicates::prove_is_move_if_some,
+        predicates::{prove_is_copy, prove_is_droppable, prove_is_move_if_some},
+        tracked::{
+            overwritten_value_not_abandoned, place_not_abandoned, statement_value_not_abandoned,
+        },
         types::check_type,
     },
 };
@@ -47,6 +51,8 @@ judgment_fn! {
         (
             (let live = live_after.before(statements))
             (type_statement(env, live, statement) => (env, ty))
+            (statement_value_not_abandoned(env, ty, &statements) => ())
+            (let () = env.debug_check_consistency(&live, || format!("type_statement({statement:?})"))?)
             (type_statements_with_final_ty(env, live_after, statements, ty) => (env, ty))
             ----------------------------------- ("cons")
             (type_statements_with_final_ty(env, live_after, Cons(statement, statements), _ty) => (env, ty))
@@ -69,15 +75,16 @@ judgment_fn! {
             (parameter_permits_access(env, ty, Access::Drop, temp) => env)
             (let env = env.pop_fresh_variable(temp))
             ----------------------------------- ("expr")
-            (type_statement(env, live_after, Statement::Expr(expr)) => (env, ty))
+            (type_statement(env, live_after, Statement::Expr(expr, _)) => (env, ty))
         )
 
         (
             (type_expr(env, live_after.clone().overwritten(id), &**expr) => (env, ty)) // [1]
             (let env = env.push_local_variable(id, ty)?)
             (let env = env.with_in_flight_stored_to(id))
+            (place_not_abandoned(env, live_after, id) => ())
             ----------------------------------- ("let")
-            (type_statement(env, live_after, Statement::Let(id, Ascription::NoTy, expr)) => (env, Ty::unit()))
+            (type_statement(env, live_after, Statement::Let(id, Ascription::NoTy, expr, _)) => (env, Ty::unit()))
         )
 
         (
@@ -85,8 +92,9 @@ judgment_fn! {
             (type_expr_as(env, live_after.clone().overwritten(id), &**expr, ty) => env) // [1]
             (let env = env.push_local_variable(id, ty)?)
             (let env = env.with_in_flight_stored_to(id))
+            (place_not_abandoned(env, live_after, id) => ())
             ----------------------------------- ("let")
-            (type_statement(env, live_after, Statement::Let(id, Ascription::Ty(ty), expr)) => (env, Ty::unit()))
+            (type_statement(env, live_after, Statement::Let(id, Ascription::Ty(ty), expr, _)) => (env, Ty::unit()))
         )
 
         // [1] Subtle: The set of variables live after `let x = <expr>` may include `x`,
@@ -97,17 +105,115 @@ judgment_fn! {
             (type_expr_as(env, live_after.clone().overwritten(place), expr, field_ty) => env)
             (let (env, temp) = env.push_fresh_variable_with_in_flight(field_ty))
             (prove_is_move_if_some(env, owner_ty) => ())
+            (owners_permit_mutation(env, place) => ())
             (env_permits_access(env, live_after, Access::Mt, place) => env)
+            (overwritten_value_not_abandoned(env, place) => ())
             (let env = env.with_var_stored_to(temp, place))
             (let env = env.pop_fresh_variable(temp))
+            (place_not_abandoned(env, live_after, place) => ())
             ----------------------------------- ("reassign")
-            (type_statement(env, live_after, Statement::Reassign(place, expr)) => (env, Ty::unit()))
+            (type_statement(env, live_after, Statement::Reassign(place, expr, _)) => (env, Ty::unit()))
+        )
+
+        (
+            // An `atomic` field of a copy type can also be written through a permission that
+            // is not unique, such as `shared` or `ref`: like reading the field, this only
+            // requires that nothing else has unique access to it.
+            (if let true = env.is_atomic_field(&place)?)!
+            (let (_owner_ty, field_ty) = env.owner_and_field_ty(place)?)
+            (type_expr_as(env, live_after.clone().overwritten(place), expr, field_ty) => env)
+            (let (env, temp) = env.push_fresh_variable_with_in_flight(field_ty))
+            (prove_is_copy(env, field_ty) => ())
+            (env_permits_access(env, live_after, Access::Rf, place) => env)
+            (let env = env.with_var_stored_to(temp, place))
+            (let env = env.pop_fresh_variable(temp))
+            ----------------------------------- ("reassign atomic")
+            (type_statement(env, live_after, Statement::Reassign(place, expr, _)) => (env, Ty::unit()))
         )
 
         (
             (type_expr(env, live_after, expr) => (env, _ty))
             ----------------------------------- ("print")
-            (type_statement(env, live_after, Statement::Print(expr)) => (env, Ty::unit()))
+            (type_statement(env, live_after, Statement::Print(expr, _)) => (env, Ty::unit()))
+        )
+
+        (
+            (let live_head = live_after.loop_head(&body))
+            (type_loop_body(env.entering_loop(), live_head.loop_body_exit(&live_after), body) => env)
+            ----------------------------------- ("loop")
+            (type_statement(env, live_after, Statement::Loop(body, _)) => (env, Ty::unit()))
+        )
+
+        (
+            (if live_after.in_loop())
+            (let env = env.with_break(&live_after.clone().at_break())?)
+            ----------------------------------- ("break")
+            (type_statement(env, live_after, Statement::Break(_)) => (env, Ty::unit()))
+        )
+
+        (
+            (let output = env.output_ty()?.clone())
+            (type_expr_as(env, live_after.clone().at_return(), expr, output) => env)
+            ----------------------------------- ("return")
+            (type_statement(env, live_after, Statement::Return(expr, _)) => (env, Ty::unit()))
+        )
+    }
+}
+
+judgment_fn! {
+    /// Type the body of a loop. The environment at the end of the body flows back
+    /// to the loop head, so we iterate until the environment at the head reaches
+    /// a fixed point. Variables declared within the body go out of scope at the end
+    /// of each iteration.
+    ///
+    /// Moves within the body are checked against the next iteration by liveness:
+    /// `live_after_body` includes everything live at the loop head.
+    ///
+    /// The result is the environment after the loop: like the branches of an `if`,
+    /// the environments at each `break` are joined (see [`Env::with_break`]).
+    fn type_loop_body(
+        env: Env,
+        live_after_body: LivePlaces,
+        body: Block,
+    ) => Env {
+        debug(body, env, live_after_body)
+
+        (
+            (type_block(env, live_after_body, body) => (env_body, ty))
+            (prove_is_droppable(env_body, ty) => ())
+            (let env_next = env_body.retain_local_variables_of(&env))
+            (type_loop_next(env, env_next, live_after_body, body) => env)
+            ----------------------------------- ("loop body")
+            (type_loop_body(env, live_after_body, body) => env)
+        )
+    }
+}
+
+judgment_fn! {
+    /// Given the environment `env` at the head of the loop and the environment
+    /// `env_next` at the end of the body, either we have reached a fixed point,
+    /// and the `break`s typed in this last iteration give the environment after the loop,
+    /// or we type the body again starting from `env_next`.
+    fn type_loop_next(
+        env: Env,
+        env_next: Env,
+        live_after_body: LivePlaces,
+        body: Block,
+    ) => Env {
+        debug(body, env, env_next, live_after_body)
+
+        (
+            (if env_next.at_loop_head().eq(&env))!
+            (let env = env_next.after_loop())
+            ----------------------------------- ("fixed point")
+            (type_loop_next(env, env_next, _live_after_body, _body) => env)
+        )
+
+        (
+            (if !env_next.at_loop_head().eq(&env))!
+            (type_loop_body(env_next.at_loop_head(), live_after_body, body) => env)
+            ----------------------------------- ("iterate")
+            (type_loop_next(env, env_next, live_after_body, body) => env)
         )
     }
 }
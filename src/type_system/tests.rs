@@ -2,21 +2,37 @@ use formality_core::test;
 
 mod array_ops;
 mod assignment;
+mod async_fns;
+mod block_scope;
+mod boxed_classes;
 mod cancellation;
+mod closures;
+mod diagnostics;
 mod drop_body;
+mod enums;
 mod class_defn_wf;
+mod consistency;
 mod fn_calls;
+mod free_fns;
 mod given_classes;
+mod if_join;
+mod inference;
+mod loops;
 mod mdbook;
 mod move_check;
 mod move_tracking;
 mod new_with_self_references;
+mod operators;
 mod permission_check;
 mod predicate_quantifiers;
 mod shared_classes_permissions;
 mod shared_classes_subtyping;
 mod subpermission;
+mod strings;
 mod subtyping;
+mod tasks;
+mod tracked_classes;
+mod traits;
 mod normalization;
 mod or_perm;
 mod type_check;
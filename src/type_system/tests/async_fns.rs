@@ -0,0 +1,170 @@
+use formality_core::test;
+
+/// Calling an `async fn` yields a future, which is awaited for the result.
+#[test]
+fn await_future() {
+    crate::assert_ok!({
+        class Counter {
+            count: Int;
+
+            async fn get(ref self) -> Int {
+                self.count.give;
+            }
+        }
+        class Main {
+            async fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = c.ref.get();
+                f.give.await;
+            }
+        }
+    });
+}
+
+/// The place referenced by the future can be used again once it has been awaited.
+#[test]
+fn mutate_after_await() {
+    crate::assert_ok!({
+        class Counter {
+            count: Int;
+
+            async fn get(ref self) -> Int {
+                self.count.give;
+            }
+        }
+        class Main {
+            async fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = c.ref.get();
+                let n = f.give.await;
+                c.count = 23;
+                n.give;
+            }
+        }
+    });
+}
+
+/// A future that captures `ref self` keeps its lien on the receiver until it is awaited.
+#[test]
+fn mutate_while_future_live() {
+    crate::assert_diagnostic!(
+        "
+class Counter {
+    count: Int;
+
+    async fn get(ref self) -> Int {
+        self.count.give;
+    }
+}
+class Main {
+    async fn main(given self) -> Int {
+        let c = new Counter(22);
+        let f = c.ref.get();
+        c.count = 23;
+        f.give.await;
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot mutate `c.count` because it is referenced by `f` (`ref[c]`, live here)
+            input:12:9
+               |
+            12 |         c.count = 23;
+               |         ^^^^^^^^^^^^^
+            note: `ref[c]` is created here
+            input:11:17
+               |
+            11 |         let f = c.ref.get();
+               |                 ^^^^^"#]]
+    );
+}
+
+/// A future that captures a given value owns it, so the original place cannot be used.
+#[test]
+fn future_owns_given_self() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+
+            async fn take(given self) -> Int {
+                self.count.give;
+            }
+        }
+        class Main {
+            async fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = c.give.take();
+                c.count.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {c . count}, traversed: {} }
+            place = c"#]]);
+}
+
+/// `await` is only permitted in the body of an `async fn`.
+#[test]
+fn await_outside_async_fn() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+
+            async fn get(ref self) -> Int {
+                self.count.give;
+            }
+        }
+        class Main {
+            fn main(given self) -> Int {
+                let c = new Counter(22);
+                c.ref.get().await;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "await" at (expressions.rs) failed because
+          `await` outside of an `async fn`"#]]);
+}
+
+/// Only an owned future can be awaited, since awaiting consumes it.
+#[test]
+fn await_borrowed_future() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+
+            async fn get(ref self) -> Int {
+                self.count.give;
+            }
+        }
+        class Main {
+            async fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = c.ref.get();
+                f.ref.await;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "await" at (expressions.rs) failed because
+          cannot await a value of type `ref [f] Future[Int, (ref [c] Counter)]`: only an owned future can be awaited"#]]);
+}
+
+/// An impl method is `async` if and only if the trait method is.
+#[test]
+fn impl_method_async_mismatch() {
+    crate::assert_err!({
+        trait Describe {
+            async fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_impl" at (traits.rs) failed because
+          method `describe` does not match its signature in trait `Describe`"#]]);
+}
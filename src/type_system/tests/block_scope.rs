@@ -0,0 +1,89 @@
+use formality_core::test;
+
+/// Variables declared in a block go out of scope at its end,
+/// so a later block can declare a variable with the same name.
+#[test]
+fn sibling_blocks_reuse_name() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self) {
+                { let x = new Data(); (); };
+                { let x = new Data(); (); };
+                ();
+            }
+        }
+    });
+}
+
+/// A variable declared in a block cannot be used after it.
+#[test]
+fn block_variable_out_of_scope() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self) -> given Data {
+                { let x = new Data(); (); };
+                x.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give place" at (expressions.rs) failed because
+          no variable named `x`"#]]);
+}
+
+/// A value given from a block-local variable is owned once the variable goes out of scope.
+#[test]
+fn block_returns_given_from_local() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self) -> given Data {
+                let d = {
+                    let x = new Data();
+                    let y: given_from[x] Data = x.give;
+                    y.give;
+                };
+                d.give;
+            }
+        }
+    });
+}
+
+/// A reference to a value owned by a block-local variable would dangle.
+#[test]
+fn block_returns_ref_to_local() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self) {
+                let r = {
+                    let x = new Data();
+                    x.ref;
+                };
+                r.give;
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "place" at (blocks.rs) failed because
+          dangling reference: `ref [x]` cannot be expressed without `[x]`"#]]);
+}
+
+/// A reference through a block-local `mut` borrow is weakened to `shared mut[d]`.
+#[test]
+fn block_returns_ref_through_mut_local() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self, d: given Data) {
+                let r: shared mut[d] Data = {
+                    let m = d.mut;
+                    m.ref;
+                };
+                r.give;
+                ();
+            }
+        }
+    });
+}
@@ -0,0 +1,87 @@
+use formality_core::test;
+
+// =============================================================================
+// recursive types
+// =============================================================================
+
+/// A `boxed class` is stored behind a pointer, so it can contain itself.
+#[test]
+fn boxed_recursive_list() {
+    crate::assert_ok!({
+        enum Option[ty T] {
+            None { }
+            Some { value: T; }
+        }
+        boxed class List {
+            value: Int;
+            next: Option[List];
+        }
+        class Main {
+            fn main(given self) -> () {
+                let l = new List(1, new Option[List]::None());
+                let m = new List(2, new Option[List]::Some(l.give));
+                let v = m.value.give;
+                ();
+            }
+        }
+    });
+}
+
+/// Recursion through an `Array` needs no `boxed` declaration, arrays are already boxed.
+#[test]
+fn recursion_through_array() {
+    crate::assert_ok!({
+        class Tree {
+            children: Array[Tree];
+        }
+    });
+}
+
+/// A regular class cannot contain itself directly...
+#[test]
+fn unboxed_recursive_class() {
+    crate::assert_err!({
+        class List {
+            value: Int;
+            next: List;
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_class" at (classes.rs) failed because
+          class `List` contains itself without indirection; declare it as a `boxed class`"#]]);
+}
+
+/// ...nor through an enum or another class stored inline.
+#[test]
+fn unboxed_recursive_class_through_enum() {
+    crate::assert_err!({
+        enum Option[ty T] {
+            None { }
+            Some { value: T; }
+        }
+        class Pair[ty A, ty B] {
+            a: A;
+            b: B;
+        }
+        class List {
+            value: Int;
+            next: Option[Pair[Int, List]];
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_class" at (classes.rs) failed because
+          class `List` contains itself without indirection; declare it as a `boxed class`"#]]);
+}
+
+/// Mutual recursion is fine as long as one of the classes is boxed.
+#[test]
+fn mutually_recursive_one_boxed() {
+    crate::assert_ok!({
+        class Tree {
+            value: Int;
+            forest: Forest;
+        }
+        boxed class Forest {
+            trees: Array[Tree];
+            rest: Tree;
+        }
+    });
+}
@@ -0,0 +1,151 @@
+use formality_core::test;
+
+/// A closure that only reads a place captures it by `ref` and can be called through `ref`.
+#[test]
+fn shared_closure_captures_ref() {
+    crate::assert_ok!({
+        class Counter {
+            count: Int;
+        }
+        class Main {
+            fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = |x: Int| -> Int { c.count.give + x.give; };
+                let a = f.ref.call(1);
+                let b = f.ref.call(2);
+                c.count.give + a.give + b.give;
+            }
+        }
+    });
+}
+
+/// A closure that assigns to a place captures it by `mut` and is called through `mut`.
+#[test]
+fn mut_closure_captures_mut() {
+    crate::assert_ok!({
+        class Counter {
+            count: Int;
+        }
+        class Main {
+            fn main(given self) -> Int {
+                let c = new Counter(0);
+                let f = || -> () { c.count = c.count.give + 1; (); };
+                f.mut.call();
+                f.mut.call();
+                c.count.give;
+            }
+        }
+    });
+}
+
+/// A `mut` capture is a lien on the captured place for as long as the closure is live.
+#[test]
+fn use_while_mut_closure_live() {
+    crate::assert_diagnostic!(
+        "
+class Counter {
+    count: Int;
+}
+class Main {
+    fn main(given self) -> () {
+        let c = new Counter(0);
+        let f = || -> () { c.count = c.count.give + 1; (); };
+        let r = c.ref;
+        f.mut.call();
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot reference `c` because it is leased by `f` (`mut[c]`, live here)
+            input:9:17
+              |
+            9 |         let r = c.ref;
+              |                 ^^^^^
+            note: `mut[c]` is created here
+            input:8:9
+              |
+            8 |         let f = || -> () { c.count = c.count.give + 1; (); };
+              |         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"#]]
+    );
+}
+
+/// A closure that mutates what it captured cannot be called through `ref`.
+#[test]
+fn call_mut_closure_through_ref() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let c = new Counter(0);
+                let f = || -> () { c.count = c.count.give + 1; (); };
+                f.ref.call();
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "sub-named" at (subtypes.rs) failed because
+          judgment `sub_perms { a: ref [f], b: mut [f], env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: given Main, @ fresh(0): ref [f] Closure mut [(), (), (mut [c] Counter)], c: given Counter, f: given Closure mut [(), (), (mut [c] Counter)]}, assumptions: {}, fresh: 1 }, live_after: LivePlaces { accessed: {}, traversed: {} } }` failed at the following rule(s):
+            the rule "sub_perms" at (subperms.rs) failed because
+              ref [f] is not a subpermission of mut [f]"#]]);
+}
+
+/// A closure that gives away what it captured owns it, so the original place cannot be used.
+#[test]
+fn once_closure_owns_capture() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+        }
+        class Main {
+            fn main(given self) -> Int {
+                let c = new Counter(22);
+                let f = || -> Counter { c.give; };
+                c.count.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {c . count}, traversed: {} }
+            place = c"#]]);
+}
+
+/// A closure that gives away what it captured is called through `given`, consuming it.
+#[test]
+fn once_closure_called_twice() {
+    crate::assert_err!({
+        class Counter {
+            count: Int;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let c = new Counter(22);
+                let f = || -> Counter { c.give; };
+                let a = f.give.call();
+                let b = f.give.call();
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {f}, traversed: {} }
+            place = f"#]]);
+}
+
+/// A closure that only captures copy values by `give` can still be called through `ref`.
+#[test]
+fn closure_giving_copy_capture_is_shared() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                let n = 22;
+                let f = |x: Int| -> Int { n.give + x.give; };
+                f.ref.call(1) + f.ref.call(2);
+            }
+        }
+    });
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+
+use formality_core::test;
+
+use crate::{
+    dada_lang::term,
+    grammar::{Block, Program, Ty, Var},
+    type_system::{
+        blocks::type_block,
+        env::{with_consistency_checks, Env},
+        in_flight::InFlight,
+        liveness::LivePlaces,
+    },
+};
+
+fn env_with(variables: &[(&str, &str)]) -> Env {
+    let program: Arc<Program> = term("class Data { }");
+    let mut env = Env::new(program);
+    for (var, ty) in variables {
+        env = env
+            .push_local_variable(term::<Var>(var), term::<Ty>(ty))
+            .unwrap();
+    }
+    env
+}
+
+fn live(vars: &[&str]) -> LivePlaces {
+    vars.iter().fold(LivePlaces::default(), |live, var| {
+        live.accessed(term::<Var>(var))
+    })
+}
+
+/// A live variable may borrow from a variable in scope.
+#[test]
+fn consistent_lien_in_scope() {
+    let env = env_with(&[("d", "Data"), ("r", "ref[d] Data")]);
+    env.check_consistency(&live(&["r"])).unwrap();
+}
+
+/// A live variable borrowing from a variable no longer in scope is inconsistent.
+#[test]
+fn inconsistent_live_lien_out_of_scope() {
+    let env = env_with(&[("r", "ref[d] Data")]);
+    let error = env.check_consistency(&live(&["r"])).unwrap_err();
+    expect_test::expect!["type `ref [d] Data` of `r` refers to `d`, which is not in scope"]
+        .assert_eq(&error.to_string());
+}
+
+/// A live variable borrowing from a variable that was given away is inconsistent.
+#[test]
+fn inconsistent_live_lien_on_moved_place() {
+    let env = env_with(&[("d", "Data")])
+        .with_place_in_flight(term::<Var>("d"))
+        .push_local_variable(term::<Var>("r"), term::<Ty>("ref[d] Data"))
+        .unwrap();
+    let error = env.check_consistency(&live(&["r"])).unwrap_err();
+    expect_test::expect!["type `ref [d] Data` of `r` refers to `d`, but `d` was moved"]
+        .assert_eq(&error.to_string());
+}
+
+/// Storing into a place that was given away reinitializes it.
+#[test]
+fn consistent_live_lien_on_reassigned_place() {
+    let env = env_with(&[("d", "Data")])
+        .with_place_in_flight(term::<Var>("d"))
+        .with_in_flight_stored_to(term::<Var>("d"))
+        .push_local_variable(term::<Var>("r"), term::<Ty>("ref[d] Data"))
+        .unwrap();
+    env.check_consistency(&live(&["r"])).unwrap();
+}
+
+/// The types of dead variables are not checked.
+#[test]
+fn consistent_dead_lien_out_of_scope() {
+    let env = env_with(&[("r", "ref[d] Data")]);
+    env.check_consistency(&live(&[])).unwrap();
+}
+
+/// Fresh variables must be popped in stack order.
+#[test]
+fn inconsistent_fresh_variables() {
+    let (env, temp) = env_with(&[]).push_fresh_variable(term::<Ty>("Int"));
+    let env = env.without_local_variables(vec![temp]).unwrap();
+    let error = env.check_consistency(&live(&[])).unwrap_err();
+    expect_test::expect!["fresh variable `@ fresh(0)` is not in scope"]
+        .assert_eq(&error.to_string());
+}
+
+/// An `or(..)` permission whose branches are all copy is consistent.
+#[test]
+fn consistent_or_of_refs() {
+    let env = env_with(&[
+        ("d1", "Data"),
+        ("d2", "Data"),
+        ("r", "or(ref[d1], ref[d2]) Data"),
+    ]);
+    env.check_consistency(&live(&["r"])).unwrap();
+}
+
+/// An `or(..)` permission mixing a given branch with a copy one is inconsistent.
+#[test]
+fn inconsistent_or_of_mixed_categories() {
+    let env = env_with(&[("d", "Data"), ("r", "or(given, ref[d]) Data")]);
+    let error = env.check_consistency(&live(&["r"])).unwrap_err();
+    expect_test::expect!["ill-formed `or(...)`: branches have mixed permission categories (must all be given, all mut, or all copy)"]
+        .assert_eq(&error.root_cause().to_string());
+}
+
+/// With consistency checks enabled, as by `check --check-env`, typing fails at the first
+/// statement after which the environment is inconsistent, even if nothing else is wrong.
+#[test]
+fn inconsistent_while_typing_reports_first_statement() {
+    let env = env_with(&[("r", "ref[d] Data")]);
+    let block: Block = term("{ let x = 22; let y = 23; r.give; }");
+    let error: anyhow::Error = with_consistency_checks(|| {
+        type_block(&env, LivePlaces::default(), &block)
+            .into_singleton()
+            .unwrap_err()
+            .into()
+    });
+    let message = format!("{error:?}");
+    assert!(
+        message.contains("inconsistent environment after `type_statement(let x = 22 ;)`"),
+        "{message}"
+    );
+    assert!(!message.contains("let y = 23 ;)`"), "{message}");
+}
+
+/// Typing a program with blocks, calls and joined branches keeps the environment consistent.
+#[test]
+fn consistent_while_typing() {
+    with_consistency_checks(|| {
+        crate::assert_ok!({
+            class Data { }
+            class Funcs {
+                fn either[perm P, perm Q](given self, x: P Data, y: Q Data) -> ref[x, y] Data
+                where P is copy, Q is copy
+                {
+                    x.ref;
+                }
+            }
+            class Main {
+                fn main(given self, b: Bool) {
+                    let d1 = new Data();
+                    let d2 = new Data();
+                    let r = {
+                        let f = new Funcs();
+                        f.give.either[ref[d1], ref[d2]](d1.ref, d2.ref);
+                    };
+                    let s = if b.give { d1.ref; } else { r.give; };
+                    s.give;
+                    ();
+                }
+            }
+        });
+    });
+}
+
+/// Consistency checks are turned off again even if the checked operation panics.
+#[test]
+fn consistency_checks_restored_after_panic() {
+    let result = std::panic::catch_unwind(|| with_consistency_checks(|| panic!("boom")));
+    assert!(result.is_err());
+
+    let env = env_with(&[("r", "ref[d] Data")]);
+    env.debug_check_consistency(&live(&["r"]), || "test".to_string())
+        .unwrap();
+}
@@ -0,0 +1,218 @@
+use formality_core::test;
+
+/// Giving away a place that is used later is explained and points at the place expression.
+#[test]
+fn give_of_live_place() {
+    crate::assert_diagnostic!(
+        "
+class Data { }
+class Main {
+    fn main(given self) -> () {
+        let d = new Data();
+        let e = d.give;
+        d.give;
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot give `d` because it is used later
+            input:6:17
+              |
+            6 |         let e = d.give;
+              |                 ^^^^^^"#]]
+    );
+}
+
+/// A failing statement with no nested expression points at the statement.
+#[test]
+fn break_outside_loop() {
+    crate::assert_diagnostic!(
+        "
+class Main {
+    fn main(given self) -> () {
+        break;
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: input:4:9
+              |
+            4 |         break;
+              |         ^^^^^^
+            the rule "break" at (statements.rs) failed because
+              condition evaluted to false: `live_after.in_loop()`
+                live_after = LivePlaces { accessed: {}, traversed: {} }"#]]
+    );
+}
+
+/// Mutating a place while a live variable references part of it
+/// names the variable and where its lien is created.
+#[test]
+fn mutate_while_shared() {
+    crate::assert_diagnostic!(
+        "
+class Data {}
+class List { value: Data; next: List; }
+class Main {
+    fn main(given self, list: given List) -> () {
+        let p = list.mut;
+        let q = p.next.mut;
+        let v = p.value.ref;
+        p = q.give;
+        v.give;
+        p.value = new Data();
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot mutate `p` because it is referenced by `v` (`ref[p.value]`, live here)
+            input:9:9
+              |
+            9 |         p = q.give;
+              |         ^^^^^^^^^^^
+            note: `ref[p.value]` is created here
+            input:8:17
+              |
+            8 |         let v = p.value.ref;
+              |                 ^^^^^^^^^^^"#]]
+    );
+}
+
+/// Of two identical statements, the one that fails is reported.
+#[test]
+fn duplicate_statement() {
+    crate::assert_diagnostic!(
+        "
+class Data { x: Int; }
+class Main {
+    fn main(given self) -> () {
+        let d = new Data(1);
+        d.x = 2;
+        let v = d.ref;
+        d.x = 2;
+        v.give;
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot mutate `d.x` because it is referenced by `v` (`ref[d]`, live here)
+            input:8:9
+              |
+            8 |         d.x = 2;
+              |         ^^^^^^^^
+            note: `ref[d]` is created here
+            input:7:17
+              |
+            7 |         let v = d.ref;
+              |                 ^^^^^"#]]
+    );
+}
+
+/// Mutating through `P self` when `P` may be shared suggests a `where` clause.
+#[test]
+fn mutate_through_perm_param() {
+    crate::assert_diagnostic!(
+        "
+class Inner { x: Int; }
+class Outer {
+    inner: Inner;
+
+    fn set[perm P](P self) -> () {
+        self.inner.x = 1;
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot mutate through `self` because its permission `P` may be shared
+            input:7:9
+              |
+            7 |         self.inner.x = 1;
+              |         ^^^^^^^^^^^^^^^^^
+            help: add a `where` clause requiring `P` to be `mut`
+            input:6:5
+              |
+            6 |     fn set[perm P](P self) -> () {
+              |     ^^^^^^"#]]
+    );
+}
+
+/// Writing to an array through `P self` when `P` may be shared is rejected the same way.
+#[test]
+fn array_write_through_perm_param() {
+    crate::assert_diagnostic!(
+        "
+class Buffer {
+    data: Array[Int];
+
+    fn set[perm P](P self) -> () {
+        array_write[Int, mut[self.data]](self.data.mut, 0, 1);
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: cannot mutate through `self` because its permission `P` may be shared
+            input:6:9
+              |
+            6 |         array_write[Int, mut[self.data]](self.data.mut, 0, 1);
+              |         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+            help: add a `where` clause requiring `P` to be `mut`
+            input:5:5
+              |
+            5 |     fn set[perm P](P self) -> () {
+              |     ^^^^^^"#]]
+    );
+}
+
+/// A tracked value that is never consumed is reported at the statement that drops it.
+#[test]
+fn tracked_value_unused() {
+    crate::assert_diagnostic!(
+        "
+tracked class Tx {
+    fn commit(given self) -> () { (); }
+}
+class Main {
+    fn main(given self) -> () {
+        let t = new Tx();
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: `t` holds a tracked value, which is dropped here without being given away
+            input:7:9
+              |
+            7 |         let t = new Tx();
+              |         ^^^^^^^^^^^^^^^^^"#]]
+    );
+}
+
+/// A tracked value consumed on only one branch is dropped on the other.
+#[test]
+fn tracked_value_consumed_on_one_branch() {
+    crate::assert_diagnostic!(
+        "
+tracked class Tx {
+    fn commit(given self) -> () { (); }
+}
+class Main {
+    fn main(given self, b: Bool) -> () {
+        let t = new Tx();
+        if b.give { t.give.commit(); } else { (); };
+        ();
+    }
+}
+",
+        expect_test::expect![[r#"
+            error: `t` holds a tracked value, which is dropped here without being given away
+            input:8:9
+              |
+            8 |         if b.give { t.give.commit(); } else { (); };
+              |         ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"#]]
+    );
+}
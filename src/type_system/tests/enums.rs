@@ -0,0 +1,231 @@
+use formality_core::test;
+
+// =============================================================================
+// enum declarations
+// =============================================================================
+
+/// A generic enum with a payload-carrying variant and an empty one.
+#[test]
+fn enum_declaration() {
+    crate::assert_ok!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+    });
+}
+
+/// Variant names must be unique.
+#[test]
+fn enum_duplicate_variant() {
+    crate::assert_err!({
+        enum Choice {
+            A { }
+            A { }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_enum" at (enums.rs) failed because
+          variant `A` is declared more than once"#]]);
+}
+
+/// A `shared enum` can only carry shared values.
+#[test]
+fn shared_enum_with_given_payload() {
+    crate::assert_err!({
+        class Data { }
+        shared enum Choice {
+            A { data: Data; }
+            B { }
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: class Data { } shared enum Choice { A { data : Data ; } B { } } }`"]);
+}
+
+// =============================================================================
+// constructing variants
+// =============================================================================
+
+/// Constructing each variant of a generic enum.
+#[test]
+fn new_variant() {
+    crate::assert_ok!({
+        class Data { }
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let a = new Option[Data]::Some(new Data());
+                let b = new Option[Data]::None();
+                ();
+            }
+        }
+    });
+}
+
+/// Matching on a given enum moves the payload into the binding.
+#[test]
+fn match_given_moves_payload() {
+    crate::assert_ok!({
+        class Data { }
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Data]) -> () {
+                match o.give {
+                    Some(d) => { let e: given Data = d.give; }
+                    None() => { }
+                };
+                ();
+            }
+        }
+    });
+}
+
+/// Matching on a reference yields references to the payload.
+#[test]
+fn match_ref_binds_refs() {
+    crate::assert_ok!({
+        class Data { }
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Data]) -> () {
+                match o.ref {
+                    Some(d) => { let e: ref[o] Data = d.give; }
+                    None() => { }
+                };
+                let p = o.give;
+                ();
+            }
+        }
+    });
+}
+
+/// Every arm of a match must produce a value of the match's type.
+#[test]
+fn match_produces_value() {
+    crate::assert_ok!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Int]) -> Int {
+                match o.give {
+                    Some(v) => { v.give; }
+                    None() => { 0; }
+                };
+            }
+        }
+    });
+}
+
+/// Each arm starts from the environment after the scrutinee, so every arm
+/// can give away the same variable.
+#[test]
+fn match_arms_typed_independently() {
+    crate::assert_ok!({
+        class Data { }
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Int], d: Data) -> Data {
+                match o.give {
+                    Some(v) => { d.give; }
+                    None() => { d.give; }
+                };
+            }
+        }
+    });
+}
+
+/// Every variant must be covered.
+#[test]
+fn match_non_exhaustive() {
+    crate::assert_err!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Int]) -> () {
+                match o.give {
+                    Some(v) => { }
+                };
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "match" at (expressions.rs) failed because
+          non-exhaustive match: variant `None` is not covered"#]]);
+}
+
+/// A variant cannot be matched twice.
+#[test]
+fn match_duplicate_arm() {
+    crate::assert_err!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Int]) -> () {
+                match o.give {
+                    Some(v) => { }
+                    Some(w) => { }
+                    None() => { }
+                };
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "match" at (expressions.rs) failed because
+          variant `Some` is matched more than once"#]]);
+}
+
+/// An arm must bind each field of its variant.
+#[test]
+fn match_wrong_binding_count() {
+    crate::assert_err!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self, o: Option[Int]) -> () {
+                match o.give {
+                    Some() => { }
+                    None() => { }
+                };
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "match" at (expressions.rs) failed because
+          variant `Some` has 1 field(s) but the arm binds 0"#]]);
+}
+
+/// Only enums can be matched on.
+#[test]
+fn match_on_class() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self, d: Data) -> () {
+                match d.give {
+                    Some(v) => { }
+                };
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "match" at (expressions.rs) failed because
+          no enum named `Data`"#]]);
+}
@@ -0,0 +1,91 @@
+use formality_core::test;
+
+/// A function can be called from a method and from another function.
+#[test]
+fn call_fn() {
+    crate::assert_ok!({
+        class Data { }
+
+        fn make() -> Data {
+            new Data();
+        }
+
+        fn consume(d: given Data) -> () {
+            ();
+        }
+
+        class Main {
+            fn main(given self) -> () {
+                let d = make();
+                consume(d.give);
+            }
+        }
+    });
+}
+
+/// Functions can be generic; the parameters may be given or inferred.
+#[test]
+fn call_generic_fn() {
+    crate::assert_ok!({
+        class Data { }
+
+        fn id[ty T](t: T) -> T {
+            t.give;
+        }
+
+        fn main() -> () {
+            let d = new Data();
+            let e: Data = id[Data](d.give);
+            let f: Data = id(e.give);
+            ();
+        }
+    });
+}
+
+/// Arguments are checked against the declared input types.
+#[test]
+fn call_fn_wrong_argument_type() {
+    crate::assert_err!({
+        class Data { }
+
+        fn consume(d: given Data) -> () {
+            ();
+        }
+
+        fn main() -> () {
+            consume(22);
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: class Data { } fn consume (d : given Data) -> () { () ; } fn main () -> () { consume (22) ; } }`"]);
+}
+
+/// Moving a value into a function call means it cannot be used again.
+#[test]
+fn call_fn_moves_argument() {
+    crate::assert_err!({
+        class Data { }
+
+        fn consume(d: given Data) -> () {
+            ();
+        }
+
+        fn main() -> () {
+            let d = new Data();
+            consume(d.give);
+            consume(d.give);
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {d}, traversed: {} }
+            place = d"#]]);
+}
+
+/// The body of a function is checked against its declared output type.
+#[test]
+fn fn_body_is_checked() {
+    crate::assert_err!({
+        fn main() -> Int {
+            true;
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: fn main () -> Int { true ; } }`"]);
+}
@@ -0,0 +1,110 @@
+use formality_core::test;
+
+/// An `if` evaluates to the value of its branches.
+#[test]
+fn if_produces_value() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self, b: Bool) -> Int {
+                let x = if b.give { 1; } else { 2; };
+                x.give;
+            }
+        }
+    });
+}
+
+/// A branch that returns does not contribute to the type of the `if`.
+#[test]
+fn if_with_diverging_branch() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool, d: given Data) -> given Data {
+                let x = if b.give { return d.give; } else { 1; };
+                x.give;
+                d.give;
+            }
+        }
+    });
+}
+
+/// Branches referencing different places are joined into an `or(..)` permission.
+#[test]
+fn if_joins_refs() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn pick(given self, b: Bool, d1: given Data, d2: given Data) -> ref[d1, d2] Data {
+                if b.give { d1.ref; } else { d2.ref; };
+            }
+        }
+    });
+}
+
+/// The joined result borrows from both places, so neither can be moved while it is live.
+#[test]
+fn if_joined_refs_block_give() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool, d1: given Data, d2: given Data) {
+                let result: or(ref[d1], ref[d2]) Data = if b.give { d1.ref; } else { d2.ref; };
+                d2.give;
+                result.give;
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "share-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, shared_place)`
+            accessed_place = d2
+            shared_place = d2"#]]);
+}
+
+/// A place moved in one branch cannot be used after the `if`.
+#[test]
+fn if_move_in_one_branch_then_use() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool, d: given Data) -> given Data {
+                if b.give { let e = d.give; (); } else { (); };
+                d.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {d}, traversed: {} }
+            place = d"#]]);
+}
+
+/// Each branch starts from the environment before the `if`,
+/// so both branches may move the same place.
+#[test]
+fn if_move_in_both_branches() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool, d: given Data) -> given Data {
+                if b.give { d.give; } else { d.give; };
+            }
+        }
+    });
+}
+
+/// Joining an owned value with a shared one has no well-formed permission.
+#[test]
+fn if_join_mixed_categories() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool, d1: given Data, d2: given Data) {
+                let x = if b.give { d1.give; } else { d2.ref; };
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "if" at (expressions.rs) failed because
+          ill-formed `or(...)`: branches have mixed permission categories (must all be given, all mut, or all copy)"#]]);
+}
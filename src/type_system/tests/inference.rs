@@ -0,0 +1,212 @@
+use formality_core::test;
+
+// =============================================================================
+// class parameters
+// =============================================================================
+
+/// The parameters of a class are inferred from the field values.
+#[test]
+fn infer_class_parameters() {
+    crate::assert_ok!({
+        class Data { }
+        class Pair[ty A, ty B] {
+            a: A;
+            b: B;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let p = new Pair(new Data(), 22);
+                let q: Pair[Data, Int] = p.give;
+                ();
+            }
+        }
+    });
+}
+
+/// Inferring a parameter from a reference yields the reference type.
+#[test]
+fn infer_class_parameter_ref() {
+    crate::assert_ok!({
+        class Data { }
+        class Pair[ty T] {
+            a: T;
+            b: T;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                let p = new Pair(d.ref, d.ref);
+                let q: Pair[ref[d] Data] = p.give;
+                ();
+            }
+        }
+    });
+}
+
+/// References to the same type from different places are merged.
+#[test]
+fn infer_class_parameter_merges_refs() {
+    crate::assert_ok!({
+        class Data { }
+        class Pair[ty T] {
+            a: T;
+            b: T;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                let e = new Data();
+                let p = new Pair(d.ref, e.ref);
+                let q: Pair[ref[d, e] Data] = p.give;
+                ();
+            }
+        }
+    });
+}
+
+/// Arguments of unrelated types cannot both determine the same parameter.
+#[test]
+fn infer_class_parameter_conflict() {
+    crate::assert_err!({
+        class Data { }
+        class Pair[ty T] {
+            a: T;
+            b: T;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let p = new Pair(new Data(), 22);
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "inferred" at (inference.rs) failed because
+          ambiguous type parameter 0 of class `Pair`: could be `Data` or `Int`"#]]);
+}
+
+/// Inferred parameters are still checked against the class predicates.
+#[test]
+fn infer_class_parameter_checks_predicates() {
+    crate::assert_err!({
+        class Data { }
+        class Boxed[ty T]
+        where
+            T is copy,
+        {
+            value: T;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let b = new Boxed(new Data());
+                ();
+            }
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: class Data { } class Boxed [ty] where ^ty0_0 is copy { value : ^ty0_0 ; } class Main { fn main (given self) -> () { let b = new Boxed (new Data ()) ; () ; } } }`"]);
+}
+
+// =============================================================================
+// enum parameters
+// =============================================================================
+
+/// The parameters of an enum are inferred from the fields of the variant.
+#[test]
+fn infer_variant_parameters() {
+    crate::assert_ok!({
+        class Data { }
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let o = new Option::Some(new Data());
+                let p: Option[Data] = o.give;
+                ();
+            }
+        }
+    });
+}
+
+/// A variant whose fields do not mention a parameter cannot determine it.
+#[test]
+fn infer_variant_parameters_ambiguous() {
+    crate::assert_err!({
+        enum Option[ty T] {
+            Some { value: T; }
+            None { }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let o = new Option::None();
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "inferred" at (inference.rs) failed because
+          cannot infer type parameter 0 of variant `Option::None`, it must be given explicitly"#]]);
+}
+
+// =============================================================================
+// method parameters
+// =============================================================================
+
+/// The permission of `self` is inferred from the receiver.
+#[test]
+fn infer_method_self_perm() {
+    crate::assert_ok!({
+        class Data {
+            fn read[perm P](P self) -> ()
+            where
+                P is copy,
+            {
+                ();
+            }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                d.ref.read();
+                d.give.read[given]();
+                ();
+            }
+        }
+    });
+}
+
+/// Method type parameters are inferred from the arguments.
+#[test]
+fn infer_method_type_parameter() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn id[ty T](given self, t: T) -> T {
+                t.give;
+            }
+
+            fn main(given self) -> () {
+                let d = new Data();
+                let e: Data = self.give.id(d.give);
+                ();
+            }
+        }
+    });
+}
+
+/// A parameter that does not appear in the inputs cannot be inferred.
+#[test]
+fn infer_method_type_parameter_ambiguous() {
+    crate::assert_err!({
+        class Main {
+            fn make[ty T](given self) -> () {
+                ();
+            }
+
+            fn main(given self) -> () {
+                let e = self.give.make();
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "inferred" at (inference.rs) failed because
+          cannot infer type parameter 0 of method `make` of `Main`, it must be given explicitly"#]]);
+}
@@ -0,0 +1,207 @@
+use formality_core::test;
+
+// =============================================================================
+// loop / break
+// =============================================================================
+
+/// A loop that counts up and breaks out.
+#[test]
+fn loop_with_break() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                let i = 0;
+                loop {
+                    if i.give >= 10 { break; } else { i = i.give + 1; };
+                }
+                i.give;
+            }
+        }
+    });
+}
+
+/// `break` outside of a loop is an error.
+#[test]
+fn break_outside_loop() {
+    crate::assert_err!({
+        class Main {
+            fn main(given self) -> () {
+                break;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "break" at (statements.rs) failed because
+          condition evaluted to false: `live_after.in_loop()`
+            live_after = LivePlaces { accessed: {}, traversed: {} }"#]]);
+}
+
+/// Moving a value that is not used again after the loop is fine
+/// as long as the loop body does not use it again either.
+#[test]
+fn loop_move_then_break() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                loop {
+                    let e = d.give;
+                    break;
+                }
+                ();
+            }
+        }
+    });
+}
+
+/// Moving a value in the loop body is an error if the next iteration
+/// may use it again.
+#[test]
+fn loop_move_in_body_used_next_iteration() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                loop {
+                    let e = d.give;
+                }
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {d}, traversed: {}, loop_exit: LivePlaces { accessed: {}, traversed: {} } }
+            place = d"#]]);
+}
+
+/// Values used after the loop are live at every `break`.
+#[test]
+fn loop_move_before_break_used_after() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self) -> () {
+                let d = new Data();
+                loop {
+                    let e = d.give;
+                    break;
+                }
+                d.give;
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {d}, traversed: {}, loop_exit: LivePlaces { accessed: {d}, traversed: {} } }
+            place = d"#]]);
+}
+
+/// A value moved just before a `break` into a variable declared outside of the loop
+/// can be used after the loop.
+#[test]
+fn loop_move_before_break_then_use_after() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self) -> given Data {
+                let d = new Data();
+                let e = new Data();
+                loop {
+                    e = d.give;
+                    break;
+                }
+                e.give;
+            }
+        }
+    });
+}
+
+/// The environment after the loop is the one at the `break`, not the one at the
+/// end of the body: moving `d` before the `break` renamed the reference `s` to `e`,
+/// so `e` cannot be moved while `s` is live.
+#[test]
+fn loop_move_before_break_renames_ref() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self, b: Bool) -> () {
+                let d = new Data();
+                let s = d.ref;
+                let e = new Data();
+                loop {
+                    if b.give { e = d.give; break; } else { };
+                }
+                e.give;
+                s.give;
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "share-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, shared_place)`
+            accessed_place = e
+            shared_place = e"#]]);
+}
+
+// =============================================================================
+// return
+// =============================================================================
+
+/// `return` at the end of a method body.
+#[test]
+fn return_at_end_of_body() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                return 22;
+            }
+        }
+    });
+}
+
+/// `return` from inside of a loop.
+#[test]
+fn return_from_loop() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                let i = 0;
+                loop {
+                    if i.give >= 10 { return i.give; } else { i = i.give + 1; };
+                }
+                0;
+            }
+        }
+    });
+}
+
+/// The returned value must have the declared output type.
+#[test]
+fn return_wrong_type() {
+    crate::assert_err!({
+        class Data { }
+        class Main {
+            fn main(given self) -> Int {
+                return new Data();
+            }
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: class Data { } class Main { fn main (given self) -> Int { return new Data () ; } } }`"]);
+}
+
+/// Moving a value and returning early is fine even if the value
+/// is used on the fall-through path.
+#[test]
+fn return_then_use_on_other_path() {
+    crate::assert_ok!({
+        class Data { }
+        class Main {
+            fn main(given self, d: given Data, b: Bool) -> given Data {
+                if b.give { return d.give; } else { (); };
+                d.give;
+            }
+        }
+    });
+}
@@ -124,3 +124,72 @@ fn give_leased_value() {
                   live_after = LivePlaces { accessed: {bar}, traversed: {} }
                   place = bar"#]])
 }
+
+/// Check giving a field of copy type leaves it initialized, so the field
+/// and then the whole object can be given afterwards.
+#[test]
+#[allow(non_snake_case)]
+fn give_int_field_twice_then_object() {
+    crate::assert_ok!({
+        class Foo {
+            i: Int;
+        }
+
+        class Main {
+            fn main(given self) -> Foo {
+                let foo = new Foo(22);
+                let a = foo.i.give;
+                let b = foo.i.give;
+                foo.give;
+            }
+        }
+    })
+}
+
+/// Check giving a value of a shared class while it is referenced
+/// copies it rather than invalidating the reference.
+#[test]
+#[allow(non_snake_case)]
+fn give_shared_class_while_refd() {
+    crate::assert_ok!({
+        shared class Pair {
+            x: Int;
+            y: Int;
+        }
+
+        class Main {
+            fn main(given self) -> Pair {
+                let p = new Pair(1, 2);
+                let r = p.ref;
+                let q = p.give;
+                r.give;
+                p.give;
+            }
+        }
+    })
+}
+
+/// Check that giving a copy field still reads it, which a live lease forbids.
+#[test]
+#[allow(non_snake_case)]
+fn give_int_field_while_leased() {
+    crate::assert_err!({
+        class Foo {
+            i: Int;
+        }
+
+        class Main {
+            fn main(given self) {
+                let foo = new Foo(22);
+                let m = foo.mut;
+                foo.i.give;
+                m.give;
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "lease-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, leased_place)`
+            accessed_place = foo . i
+            leased_place = foo"#]])
+}
@@ -10,8 +10,6 @@ use formality_core::test;
 // - Dangling borrows (ref from given — should error)
 // - Borrow chaining (ref through ref — should succeed)
 // - Multi-place resolution producing Or
-//
-// All tests should fail until Phase 2b lands.
 // =============================================================================
 
 // ---------------------------------------------------------------------------
@@ -144,7 +142,9 @@ fn dangling_borrow_ref_from_given_self() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "normalize" at (pop_normalize.rs) failed because
+          dangling reference: `ref [self]` cannot be expressed without `[self]`"#]]);
 }
 
 /// Method returns ref[x] where x is a given parameter → dangling borrow.
@@ -165,7 +165,9 @@ fn dangling_borrow_ref_from_given_param() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "normalize" at (pop_normalize.rs) failed because
+          dangling reference: `ref [x]` cannot be expressed without `[x, self]`"#]]);
 }
 
 /// Multi-place ref[x, y] where both x and y are given → dangling borrow.
@@ -188,7 +190,9 @@ fn dangling_borrow_ref_from_two_given_params() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "normalize" at (pop_normalize.rs) failed because
+          dangling reference: `ref [x, y]` cannot be expressed without `[y, x, self]`"#]]);
 }
 
 /// Mixed: ref[x, y] where x is ref (ok) but y is given (dangles).
@@ -213,7 +217,9 @@ fn dangling_borrow_ref_mixed_ref_and_given() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "normalize" at (pop_normalize.rs) failed because
+          dangling reference: `ref [x, y]` cannot be expressed without `[y, x, self]`"#]]);
 }
 
 // ---------------------------------------------------------------------------
@@ -266,7 +272,9 @@ fn perm_dependent_borrow_given_arg_dangles() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "normalize" at (pop_normalize.rs) failed because
+          dangling reference: `ref [x]` cannot be expressed without `[x, self]`"#]]);
 }
 
 // ---------------------------------------------------------------------------
@@ -433,7 +441,11 @@ fn norm_or_ref_blocks_give_d1() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "share-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, shared_place)`
+            accessed_place = @ fresh(0)
+            shared_place = @ fresh(0)"#]]);
 }
 
 /// Normalized or(mut[d1], mut[d2]) should block mutating d1 while result is live.
@@ -461,7 +473,11 @@ fn norm_or_mut_blocks_mut_d1() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "lease-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, leased_place)`
+            accessed_place = d1
+            leased_place = d1"#]]);
 }
 
 /// Normalized or(shared mut[d1], shared mut[d2]) from ref-through-mut
@@ -490,7 +506,11 @@ fn norm_or_shared_mut_blocks_mut_d1() {
                 ();
             }
         }
-    }, expect_test::expect![[""]]);
+    }, expect_test::expect![[r#"
+        the rule "lease-mutation" at (accesses.rs) failed because
+          condition evaluted to false: `place_disjoint_from(accessed_place, leased_place)`
+            accessed_place = d1
+            leased_place = d1"#]]);
 }
 
 /// After normalized or-borrowed result is dead, d1 and d2 should be accessible.
@@ -0,0 +1,62 @@
+use formality_core::test;
+
+/// Arithmetic operators take and produce `Int`; `-` also negates.
+#[test]
+fn int_operators() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                let x = 7 * 6 / 2 % 5 - -1;
+                x.give;
+            }
+        }
+    });
+}
+
+/// Comparisons produce `Bool`, which `&&`, `||` and `!` combine.
+#[test]
+fn bool_operators() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Bool {
+                let x = 22;
+                x.give < 44 && x.give > 0 || !false;
+            }
+        }
+    });
+}
+
+/// The right operand of `||` may give away a place that is not used afterwards.
+#[test]
+fn short_circuit_rhs_gives() {
+    crate::assert_ok!({
+        class Data {}
+        class Main {
+            fn main(given self) -> Bool {
+                let a = new Data();
+                let b = false || { let c = a.give; true; };
+                b.give;
+            }
+        }
+    });
+}
+
+/// The right operand of `&&` may not run, so a place it assigns is still live
+/// after the left operand if it is used after the whole expression.
+#[test]
+fn short_circuit_rhs_may_not_assign() {
+    crate::assert_err!({
+        class Data {}
+        class Main {
+            fn main(given self) -> Data {
+                let a = new Data();
+                let b = { let c = a.give; true; } && { a = new Data(); true; };
+                a.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {a}, traversed: {} }
+            place = a"#]]);
+}
@@ -599,3 +599,43 @@ fn ref_of_mut_is_not_mut() {
         }
     }, expect_test::expect!["judgment had no applicable rules: `check_program { program: class Data { } class Foo { d : Data ; fn needs_mut [perm] (^perm0_0 self) -> () where ^perm0_0 is mut { () ; } } class Main { fn main (given self) -> () { let foo = new Foo (new Data ()) ; let m = foo . mut ; m . ref . needs_mut [ref [m] mut [foo]] () ; } } }`"]);
 }
+
+/// Assigning to a field nested within `P self` is permitted when `P is mut`.
+#[test]
+#[allow(non_snake_case)]
+fn assign_nested_field_through_mut_perm_param() {
+    crate::assert_ok!({
+        class Inner {
+            x: Int;
+        }
+
+        class Outer {
+            inner: Inner;
+
+            fn set[perm P](P self) -> ()
+            where P is mut,
+            {
+                self.inner.x = 1;
+                ();
+            }
+        }
+    });
+}
+
+/// Writing to an array field of `P self` is permitted when `P is mut`.
+#[test]
+#[allow(non_snake_case)]
+fn array_write_through_mut_perm_param() {
+    crate::assert_ok!({
+        class Buffer {
+            data: Array[Int];
+
+            fn set[perm P](P self) -> ()
+            where P is mut,
+            {
+                array_write[Int, mut[self.data]](self.data.mut, 0, 1);
+                ();
+            }
+        }
+    });
+}
@@ -0,0 +1,76 @@
+use formality_core::test;
+
+/// String literals have type `String`, and `len` counts their characters.
+#[test]
+fn string_literal_len() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Int {
+                let s = "hello";
+                s.ref.len();
+            }
+        }
+    });
+}
+
+/// Strings are concatenated and compared through any permission.
+#[test]
+fn concatenate_and_compare_refs() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Bool {
+                let a = "hello, ";
+                let b = "world";
+                let c: String = a.ref + b.ref;
+                let d = a.give + b.give;
+                c.ref == d.ref;
+            }
+        }
+    });
+}
+
+/// Concatenating a given string consumes it.
+#[test]
+fn concatenate_moves_given_string() {
+    crate::assert_err!({
+        class Main {
+            fn main(given self) -> String {
+                let a = "hello";
+                let b = a.give + ", world";
+                a.give;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "give" at (expressions.rs) failed because
+          condition evaluted to false: `!live_after.is_live(place)`
+            live_after = LivePlaces { accessed: {a}, traversed: {} }
+            place = a"#]]);
+}
+
+/// A shared string is copied, like a shared array.
+#[test]
+fn shared_string_is_copy() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> String {
+                let s = "hi".share;
+                let t = s.give;
+                s.give + t.give;
+            }
+        }
+    });
+}
+
+/// Chars are copied and compare like `Int`s.
+#[test]
+fn char_comparison() {
+    crate::assert_ok!({
+        class Main {
+            fn main(given self) -> Bool {
+                let c = 'a';
+                let d = c.give;
+                c.give <= 'b';
+            }
+        }
+    });
+}
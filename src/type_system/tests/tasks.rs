@@ -0,0 +1,82 @@
+use formality_core::test;
+
+/// An `atomic` field can be written through `shared self`.
+#[test]
+fn atomic_write_through_shared() {
+    crate::assert_ok!({
+        boxed class Counter {
+            atomic count: Int;
+
+            fn bump(shared self) -> () {
+                self.count = self.count.give + 1;
+                ();
+            }
+        }
+    });
+}
+
+/// An `atomic` field can be written through a `ref`, while the owner is still live.
+#[test]
+fn atomic_write_through_ref() {
+    crate::assert_ok!({
+        class Counter {
+            atomic count: Int;
+        }
+        class Main {
+            fn main(given self) -> Int {
+                let c = new Counter(0);
+                let r = c.ref;
+                r.count = 1;
+                c.count.give;
+            }
+        }
+    });
+}
+
+/// A task can be spawned from a future that only captures shared data.
+#[test]
+fn spawn_shared_future() {
+    crate::assert_ok!({
+        boxed class Counter {
+            atomic count: Int;
+
+            async fn bump(shared self) -> () {
+                self.count = self.count.give + 1;
+                ();
+            }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let c = new Counter(0).share;
+                spawn(c.give.bump());
+                spawn(c.give.bump());
+                ();
+            }
+        }
+    });
+}
+
+/// Only an owned future can be spawned, since spawning consumes it.
+#[test]
+fn spawn_borrowed_future() {
+    crate::assert_err!({
+        boxed class Counter {
+            atomic count: Int;
+
+            async fn bump(shared self) -> () {
+                self.count = self.count.give + 1;
+                ();
+            }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let c = new Counter(0).share;
+                let f = c.give.bump();
+                spawn(f.ref);
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "spawn" at (expressions.rs) failed because
+          cannot spawn a value of type `ref [f] Future[(), (shared Counter)]`: only an owned future can be spawned"#]]);
+}
@@ -0,0 +1,195 @@
+use formality_core::test;
+
+/// A tracked value given to a `given self` method is consumed.
+#[test]
+fn consumed_by_given_self_method() {
+    crate::assert_ok!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let t = new Tx();
+                t.give.commit();
+                ();
+            }
+        }
+    });
+}
+
+/// A tracked value may be borrowed before it is consumed.
+#[test]
+fn borrowed_then_consumed() {
+    crate::assert_ok!({
+        tracked class Tx {
+            id: Int;
+
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Main {
+            fn main(given self) -> () {
+                let t = new Tx(1);
+                let id = t.id.give;
+                t.give.commit();
+                ();
+            }
+        }
+    });
+}
+
+/// A tracked value may be consumed on both branches of an `if`.
+#[test]
+fn consumed_on_both_branches() {
+    crate::assert_ok!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+            fn abort(given self) -> () {
+                ();
+            }
+        }
+        class Main {
+            fn main(given self, b: Bool) -> () {
+                let t = new Tx();
+                if b.give { t.give.commit(); } else { t.give.abort(); };
+                ();
+            }
+        }
+    });
+}
+
+/// A tracked value may be returned as the value of a block.
+#[test]
+fn returned_from_function() {
+    crate::assert_ok!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Main {
+            fn open(given self) -> Tx {
+                let t = new Tx();
+                t.give;
+            }
+        }
+    });
+}
+
+/// A tracked value may be held by a tracked class, which must then be consumed in turn.
+#[test]
+fn tracked_class_holds_tracked_field() {
+    crate::assert_ok!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        tracked class Session {
+            tx: Tx;
+
+            fn close(given self) -> () {
+                self.tx.give.commit();
+                ();
+            }
+        }
+    });
+}
+
+/// Generic code may drop the values of its type parameters,
+/// so a tracked value cannot be given to it.
+#[test]
+fn tracked_value_given_to_generic_method() {
+    crate::assert_err!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Main {
+            fn forget[ty T](given self, x: T) -> () {
+                ();
+            }
+
+            fn main(given self) -> () {
+                let t = new Tx();
+                self.give.forget(t.give);
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "call" at (expressions.rs) failed because
+          type argument `Tx` owns a tracked value, which generic code may drop"#]]);
+}
+
+/// Likewise a generic class could drop the tracked value it holds.
+#[test]
+fn tracked_value_held_by_generic_class() {
+    crate::assert_err!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Box[ty T] {
+            value: T;
+        }
+        class Main {
+            fn main(given self) -> () {
+                let t = new Tx();
+                let b = new Box[Tx](t.give);
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "new" at (expressions.rs) failed because
+          type argument `Tx` owns a tracked value, which generic code may drop"#]]);
+}
+
+/// A tracked class must have a way to consume its values.
+#[test]
+fn tracked_class_without_given_self_method() {
+    crate::assert_err!({
+        tracked class Tx {
+            fn peek(ref self) -> () {
+                ();
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_class" at (classes.rs) failed because
+          tracked class `Tx` has no method taking `given self` to consume its values"#]]);
+}
+
+/// A regular class could be dropped along with the tracked value it holds.
+#[test]
+fn regular_class_cannot_hold_tracked_field() {
+    crate::assert_err!({
+        tracked class Tx {
+            fn commit(given self) -> () {
+                ();
+            }
+        }
+        class Holder {
+            tx: Tx;
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_field" at (classes.rs) failed because
+          judgment `prove_is_droppable { a: Tx, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }` failed at the following rule(s):
+            the rule "copy" at (predicates.rs) failed because
+              judgment `prove_is_copy { a: Tx, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }` failed at the following rule(s):
+                the rule "is" at (predicates.rs) failed because
+                  judgment `prove_predicate { predicate: Tx is copy, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }` failed at the following rule(s):
+                    the rule "copy" at (predicates.rs) failed because
+                      judgment had no applicable rules: `prove_copy_predicate { p: Tx, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }`
+            the rule "mut" at (predicates.rs) failed because
+              judgment `prove_is_mut { a: Tx, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }` failed at the following rule(s):
+                the rule "is-mut" at (predicates.rs) failed because
+                  judgment `prove_predicate { predicate: Tx is mut, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }` failed at the following rule(s):
+                    the rule "mut" at (predicates.rs) failed because
+                      judgment had no applicable rules: `prove_mut_predicate { p: Tx, env: Env { program: "...", universe: universe(0), in_scope_vars: [], local_variables: {self: Holder}, assumptions: {}, fresh: 0 } }`"#]]);
+}
@@ -0,0 +1,259 @@
+use formality_core::test;
+
+// =============================================================================
+// declaring traits and impls
+// =============================================================================
+
+/// A class implements a trait by giving each of its methods.
+#[test]
+fn impl_trait() {
+    crate::assert_ok!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+    });
+}
+
+/// Trait methods are only signatures.
+#[test]
+fn trait_method_with_body() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_trait_method" at (traits.rs) failed because
+          trait method `describe` must be declared with `...;`"#]]);
+}
+
+/// An impl must give every method of its trait.
+#[test]
+fn impl_missing_method() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data { }
+    }, expect_test::expect![[r#"
+        the rule "check_impl" at (traits.rs) failed because
+          method `describe` of trait `Describe` is not implemented"#]]);
+}
+
+/// The methods of an impl must have the signatures declared in the trait.
+#[test]
+fn impl_method_wrong_signature() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Bool {
+                true;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_impl" at (traits.rs) failed because
+          method `describe` does not match its signature in trait `Describe`"#]]);
+}
+
+/// A trait can be implemented only once for a given class.
+#[test]
+fn impl_trait_twice() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data[ty T] { }
+
+        impl Describe for Data[Int] {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+
+        impl Describe for Data[Bool] {
+            fn describe(given self) -> Int {
+                44;
+            }
+        }
+    }, expect_test::expect![[r#"
+        the rule "check_impl" at (traits.rs) failed because
+          trait `Describe` is implemented more than once for `Data`"#]]);
+}
+
+/// Impl bodies are checked like the bodies of the class's own methods.
+#[test]
+fn impl_method_body_is_checked() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                self.give;
+            }
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: trait Describe { fn describe (given self) -> Int ...; } class Data { } impl Describe for Data { fn describe (given self) -> Int { self . give ; } } }`"]);
+}
+
+/// A `where` clause can only name a declared trait.
+#[test]
+fn bound_on_unknown_trait() {
+    crate::assert_err!({
+        fn describe_it[ty T](t: T) -> () where T is Describe {
+            ();
+        }
+    }, expect_test::expect![[r#"
+        the rule "implements" at (predicates.rs) failed because
+          no trait named `Describe`"#]]);
+}
+
+// =============================================================================
+// calling trait methods
+// =============================================================================
+
+/// Code that is generic over `T is Describe` can call `describe` on a `T`,
+/// and a class that implements `Describe` satisfies the bound.
+#[test]
+fn call_trait_method_on_type_variable() {
+    crate::assert_ok!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+
+        fn describe_it[ty T](t: T) -> Int where T is Describe {
+            t.give.describe();
+        }
+
+        fn main() -> Int {
+            let d = new Data();
+            let e = new Data();
+            describe_it[Data](d.give);
+            describe_it(e.give);
+        }
+    });
+}
+
+/// Without the bound, the trait's methods cannot be called on a `T`.
+#[test]
+fn call_trait_method_without_bound() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        fn describe_it[ty T](t: T) -> Int {
+            t.give.describe();
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: trait Describe { fn describe (given self) -> Int ...; } fn describe_it [ty] (t : ^ty0_0) -> Int { t . give . describe () ; } }`"]);
+}
+
+/// The bound is only satisfied by classes that implement the trait.
+#[test]
+fn trait_bound_not_satisfied() {
+    crate::assert_err!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        fn describe_it[ty T](t: T) -> Int where T is Describe {
+            t.give.describe();
+        }
+
+        fn main() -> Int {
+            let d = new Data();
+            describe_it[Data](d.give);
+        }
+    }, expect_test::expect!["judgment had no applicable rules: `check_program { program: trait Describe { fn describe (given self) -> Int ...; } class Data { } fn describe_it [ty] (t : ^ty0_0) -> Int where ^ty0_0 is Describe { t . give . describe () ; } fn main () -> Int { let d = new Data () ; describe_it [Data] (d . give) ; } }`"]);
+}
+
+/// The methods of an impl can be called directly on the class.
+#[test]
+fn call_impl_method_on_class() {
+    crate::assert_ok!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+
+        fn main() -> Int {
+            let d = new Data();
+            d.give.describe();
+        }
+    });
+}
+
+/// A generic impl applies when its `where` clauses hold.
+#[test]
+fn generic_impl_with_bound() {
+    crate::assert_ok!({
+        trait Describe {
+            fn describe(given self) -> Int ...;
+        }
+
+        class Data { }
+
+        class Pair[ty T] {
+            a: T;
+            b: T;
+        }
+
+        impl Describe for Data {
+            fn describe(given self) -> Int {
+                22;
+            }
+        }
+
+        impl[ty T] Describe for Pair[T] where T is Describe {
+            fn describe(given self) -> Int {
+                self.a.give.describe();
+            }
+        }
+
+        fn describe_it[ty T](t: T) -> Int where T is Describe {
+            t.give.describe();
+        }
+
+        fn main() -> Int {
+            let p = new Pair[Data](new Data(), new Data());
+            describe_it[Pair[Data]](p.give);
+        }
+    });
+}
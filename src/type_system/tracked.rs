@@ -0,0 +1,159 @@
+//! Checks that values of a `tracked class` are never dropped implicitly.
+//!
+//! The type system does not record which places have been moved; instead it relies
+//! on liveness. A place holding a tracked value is dropped wherever it goes from live
+//! to dead without being given away:
+//!
+//! * right after an access that is not a `give` of the place itself (see [`tracked_values_consumed`]);
+//! * right after it is assigned (e.g., `let x = ..` where `x` is never used);
+//! * when it is a field that is overwritten (see [`overwritten_value_not_abandoned`]);
+//! * at the start of a branch (of an `if`, a `match`, or the right operand of `&&` or `||`)
+//!   that does not use it although another branch does (see [`branches_consume_tracked_values`]).
+//!
+//! In each case the type of the place must be droppable (see [`prove_is_droppable`]).
+//! The exception is `self` itself: a method taking `given self` consumes it.
+
+use formality_core::judgment_fn;
+
+use crate::grammar::{Access, Place, Statement, Ty, Var};
+
+use super::{env::Env, liveness::LivePlaces, predicates::prove_is_droppable};
+
+judgment_fn! {
+    /// True if the value in `place` is not a value of a tracked class that is dropped
+    /// here, i.e., `place` is live in `live_after`, is `self`, or has a droppable type.
+    pub fn place_not_abandoned(
+        env: Env,
+        live_after: LivePlaces,
+        place: Place,
+    ) => () {
+        debug(place, env, live_after)
+
+        (
+            (if live_after.is_live(&place) || is_self(&place))!
+            -------------------------------- ("live")
+            (place_not_abandoned(_env, live_after, place) => ())
+        )
+
+        (
+            (if !live_after.is_live(&place) && !is_self(&place))!
+            (prove_is_droppable(env, env.place_ty(&place)?) => ())
+            -------------------------------- ("droppable")
+            (place_not_abandoned(env, live_after, place) => ())
+        )
+    }
+}
+
+judgment_fn! {
+    /// True if accessing `place` does not drop a tracked value: if `place`, or one of
+    /// its owners, is dead after the access, then it must have been given away by it.
+    pub fn tracked_values_consumed(
+        env: Env,
+        live_after: LivePlaces,
+        access: Access,
+        place: Place,
+    ) => () {
+        debug(access, place, env, live_after)
+
+        (
+            (for_all(owner in places_left_behind(access, &place))
+                (place_not_abandoned(env, live_after, owner) => ()))
+            -------------------------------- ("tracked_values_consumed")
+            (tracked_values_consumed(env, live_after, access, place) => ())
+        )
+    }
+}
+
+judgment_fn! {
+    /// True if no branch drops a tracked value used by another branch:
+    /// each local variable live at the start of some branch (as given by `live_at_branches`)
+    /// must be live at the start of all of them, unless its type is droppable.
+    pub fn branches_consume_tracked_values(
+        env: Env,
+        live_at_branches: Vec<LivePlaces>,
+    ) => () {
+        debug(live_at_branches, env)
+
+        (
+            (let vars = vars_live_in_any(&env, &live_at_branches))
+            (for_all(live_at_branch in live_at_branches)
+                (for_all(var in &vars)
+                    (place_not_abandoned(env, live_at_branch, var) => ())))
+            -------------------------------- ("branches")
+            (branches_consume_tracked_values(env, live_at_branches) => ())
+        )
+    }
+}
+
+judgment_fn! {
+    /// True if the value in `place` may be dropped as `place` is assigned.
+    /// The old value of a variable was either given away or already reported
+    /// where the variable was last used, but the old value of a field is dropped
+    /// here and so it must not be a tracked value.
+    pub fn overwritten_value_not_abandoned(
+        env: Env,
+        place: Place,
+    ) => () {
+        debug(place, env)
+
+        (
+            (if place.projections.is_empty())!
+            -------------------------------- ("variable")
+            (overwritten_value_not_abandoned(_env, place) => ())
+        )
+
+        (
+            (if !place.projections.is_empty())!
+            (prove_is_droppable(env, env.place_ty(&place)?) => ())
+            -------------------------------- ("field")
+            (overwritten_value_not_abandoned(env, place) => ())
+        )
+    }
+}
+
+judgment_fn! {
+    /// The value of each statement but the last of a block is discarded,
+    /// so it must not be a tracked value. `rest` are the statements that follow.
+    pub fn statement_value_not_abandoned(
+        env: Env,
+        ty: Ty,
+        rest: Vec<Statement>,
+    ) => () {
+        debug(ty, rest, env)
+
+        (
+            (if rest.is_empty())!
+            -------------------------------- ("last")
+            (statement_value_not_abandoned(_env, _ty, rest) => ())
+        )
+
+        (
+            (if !rest.is_empty())!
+            (prove_is_droppable(env, ty) => ())
+            -------------------------------- ("discarded")
+            (statement_value_not_abandoned(env, ty, rest) => ())
+        )
+    }
+}
+
+/// The places that still hold (some of) their value after `place` is accessed with `access`:
+/// `place` itself unless it is given away, and its owners.
+fn places_left_behind(access: Access, place: &Place) -> Vec<Place> {
+    let mut places = place.strict_prefixes();
+    if access != Access::Gv {
+        places.push(place.clone());
+    }
+    places
+}
+
+fn is_self(place: &Place) -> bool {
+    place.var == Var::This && place.projections.is_empty()
+}
+
+/// The local variables of `env` that are live in at least one of `live_at_branches`.
+fn vars_live_in_any(env: &Env, live_at_branches: &[LivePlaces]) -> Vec<Var> {
+    env.local_variable_names()
+        .into_iter()
+        .filter(|var| live_at_branches.iter().any(|live| live.is_live(var)))
+        .collect()
+}
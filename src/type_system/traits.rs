@@ -0,0 +1,252 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use formality_core::{judgment_fn, Fallible};
+
+use crate::grammar::{
+    Binder, ImplDecl, ImplDeclBoundData, LocalVariableDecl, MethodBody, MethodDecl,
+    MethodDeclBoundData, MethodId, NamedTy, Program, TraitDecl, Ty, TypeName, VarianceKind,
+};
+
+use super::{
+    env::Env, methods::check_method, predicates::check_predicates, some_output, types::check_type,
+};
+
+// ANCHOR: check_trait
+judgment_fn! {
+    pub fn check_trait(
+        program: Arc<Program>,
+        decl: TraitDecl,
+    ) => () {
+        debug(decl, program)
+
+        (
+            (let TraitDecl { name: _, methods } = decl)
+            (let env = Env::new(program))
+            (let () = check_method_names(&methods)?)
+            (for_all(method in methods)
+                (check_trait_method(env, method) => ()))
+            ----------------------------------- ("check_trait")
+            (check_trait(program, decl) => ())
+        )
+    }
+}
+// ANCHOR_END: check_trait
+
+judgment_fn! {
+    /// A trait method is only a signature: there is no class type for `self`,
+    /// so we check its predicates, inputs, and output.
+    fn check_trait_method(
+        env: Env,
+        decl: MethodDecl,
+    ) => () {
+        debug(decl, env)
+
+        (
+            (let MethodDecl { name, is_async: _, binder, span: _ } = decl)
+            (let (env, vars, MethodDeclBoundData { this: _, inputs, output, predicates, body }) =
+                env.open_universally(binder))
+
+            (let () = check_trait_method_body(name, body)?)
+
+            (let env = env.add_assumptions(
+                vars.iter()
+                    .flat_map(|v| vec![VarianceKind::Relative.apply(v), VarianceKind::Atomic.apply(v)])
+                    .collect::<Vec<_>>(),
+            ))
+
+            (check_predicates(env, predicates) => ())
+            (let env = env.add_assumptions(predicates))
+
+            (let env = env.push_local_variable_decls(inputs)?)
+
+            (for_all(input in inputs)
+                (let LocalVariableDecl { name: _, ty } = input)
+                (check_type(env, ty) => ()))
+
+            (check_type(env, output) => ())
+            ----------------------------------- ("check_trait_method")
+            (check_trait_method(env, decl) => ())
+        )
+    }
+}
+
+// ANCHOR: check_impl
+judgment_fn! {
+    pub fn check_impl(
+        program: Arc<Program>,
+        decl: ImplDecl,
+    ) => () {
+        debug(decl, program)
+
+        (
+            (let ImplDecl { binder } = decl)
+            (let env = Env::new(program))
+
+            (let (env, _vars, ImplDeclBoundData { trait_name: _, class_ty, predicates, methods }) =
+                env.open_universally(binder))
+
+            (let env = env.add_assumptions(predicates))
+
+            (check_predicates(env, predicates) => ())
+
+            (check_type(env, Ty::NamedTy(class_ty.clone())) => ())
+
+            (let () = env.check_impl_methods(&decl)?)
+
+            (for_all(method in methods)
+                (check_method(class_ty, env, method) => _))
+
+            ----------------------------------- ("check_impl")
+            (check_impl(program, decl) => ())
+        )
+    }
+}
+// ANCHOR_END: check_impl
+
+/// Returns `decl` with the parameters inferred in its methods written out,
+/// checking them in the environment that [`check_impl`] sets up.
+pub fn elaborate_impl(program: &Arc<Program>, decl: &ImplDecl) -> Fallible<ImplDecl> {
+    let env = Env::new(program);
+    let (env, vars, data) = env.open_universally(&decl.binder);
+    let env = env.add_assumptions(&data.predicates);
+    let methods = data
+        .methods
+        .iter()
+        .map(|method| some_output(check_method(&data.class_ty, &env, method)))
+        .collect::<Fallible<_>>()?;
+    Ok(ImplDecl {
+        binder: Binder::new(vars, ImplDeclBoundData { methods, ..data }),
+    })
+}
+
+fn check_method_names(methods: &[MethodDecl]) -> Fallible<()> {
+    for (index, method) in methods.iter().enumerate() {
+        if methods[..index].iter().any(|m| m.name == method.name) {
+            bail!("method `{:?}` is declared more than once", method.name);
+        }
+    }
+    Ok(())
+}
+
+fn check_trait_method_body(name: &MethodId, body: &MethodBody) -> Fallible<()> {
+    match body {
+        MethodBody::Trusted => Ok(()),
+        MethodBody::Block(_) => bail!("trait method `{name:?}` must be declared with `...;`"),
+    }
+}
+
+impl Env {
+    /// Check that `decl` implements a class, that its methods do not clash with those
+    /// of the class or of its other impls, and that it implements exactly the methods
+    /// of its trait, each with the signature declared in the trait.
+    fn check_impl_methods(&self, decl: &ImplDecl) -> Fallible<()> {
+        let (_, data) = decl.binder.open();
+        let ImplDeclBoundData {
+            trait_name,
+            class_ty,
+            predicates: _,
+            methods,
+        } = &data;
+
+        let NamedTy {
+            name: TypeName::Id(class_name),
+            parameters: _,
+        } = class_ty
+        else {
+            bail!("cannot implement trait `{trait_name:?}` for `{class_ty:?}`: not a class");
+        };
+        // Methods are resolved by name (see `resolve_method`), so each name must
+        // refer to at most one method of the class or of its impls.
+        let (_, class_data) = self.program().class_named(class_name)?.binder.open();
+        for method in methods {
+            if class_data.methods.iter().any(|m| m.name == method.name) {
+                bail!(
+                    "method `{:?}` is already declared in class `{class_name:?}`",
+                    method.name
+                );
+            }
+        }
+
+        let other_impls: Vec<ImplDeclBoundData> = self
+            .program()
+            .decls
+            .iter()
+            .filter_map(|d| d.as_impl_decl())
+            .filter(|other| *other != decl)
+            .map(|other| other.binder.open().1)
+            .filter(|other| other.class_ty.name == class_ty.name)
+            .collect();
+        for other in &other_impls {
+            if other.trait_name == *trait_name {
+                bail!("trait `{trait_name:?}` is implemented more than once for `{class_name:?}`");
+            }
+            if let Some(method) = methods
+                .iter()
+                .find(|m| other.methods.iter().any(|o| o.name == m.name))
+            {
+                bail!(
+                    "method `{:?}` of trait `{trait_name:?}` is also a method of trait `{:?}`",
+                    method.name,
+                    other.trait_name
+                );
+            }
+        }
+
+        let trait_decl = self.program().trait_named(trait_name)?;
+        check_method_names(methods)?;
+
+        for method in methods {
+            if !trait_decl.methods.iter().any(|m| m.name == method.name) {
+                bail!(
+                    "method `{:?}` is not a member of trait `{trait_name:?}`",
+                    method.name
+                );
+            }
+        }
+
+        for trait_method in &trait_decl.methods {
+            let Some(method) = methods.iter().find(|m| m.name == trait_method.name) else {
+                bail!(
+                    "method `{:?}` of trait `{trait_name:?}` is not implemented",
+                    trait_method.name
+                );
+            };
+
+            if !signatures_match(trait_method, method) {
+                bail!(
+                    "method `{:?}` does not match its signature in trait `{trait_name:?}`",
+                    method.name
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// True if `method` is `async` if and only if `trait_method` is, and has the same generic
+/// parameters, `self` permission, input types, output type, and where-clauses.
+fn signatures_match(trait_method: &MethodDecl, method: &MethodDecl) -> bool {
+    if trait_method.is_async != method.is_async {
+        return false;
+    }
+
+    if trait_method.binder.kinds() != method.binder.kinds() {
+        return false;
+    }
+
+    let (vars, data) = method.binder.open();
+    let Ok(trait_data) = trait_method.binder.instantiate_with(&vars) else {
+        return false;
+    };
+
+    let input_tys = |data: &MethodDeclBoundData| -> Vec<Ty> {
+        data.inputs.iter().map(|input| input.ty.clone()).collect()
+    };
+
+    trait_data.this == data.this
+        && input_tys(&trait_data) == input_tys(&data)
+        && trait_data.output == data.output
+        && trait_data.predicates == data.predicates
+}
@@ -49,6 +49,11 @@ judgment_fn! {
                 (prove_predicate(env, predicate) => ()))
             (for_all(parameter in parameters)
                 (check_parameter(env, parameter) => ()))
+            // Built-in types such as tuples and arrays are not implemented by generic code.
+            (let () = match name {
+                TypeName::Id(_) => env.check_type_arguments_untracked(&parameters),
+                _ => Ok(()),
+            }?)
             ----------------------- ("named")
             (check_type(env, NamedTy { name, parameters }) => ())
         )
@@ -143,7 +148,7 @@ judgment_fn! {
 /// - **copy**: all branches satisfy `is copy`
 ///
 /// Returns `Ok(())` if all branches are in a single category, or an error otherwise.
-fn check_or_same_category(env: &Env, perms: &Set<Perm>) -> Fallible<()> {
+pub(super) fn check_or_same_category(env: &Env, perms: &Set<Perm>) -> Fallible<()> {
     let all_given = perms
         .iter()
         .all(|p| prove_is_given(env, Parameter::Perm(p.clone())).is_proven());
@@ -170,12 +175,25 @@ fn check_class_name(program: &Program, name: &TypeName) -> Fallible<Binder<Vec<P
             let parameters: Vec<_> = (0..*n).map(|_| BoundVar::fresh(Kind::Ty)).collect();
             Ok(Binder::new(parameters, vec![]))
         }
-        TypeName::Int | TypeName::Bool => Ok(Binder::dummy(vec![])),
+        TypeName::Int | TypeName::Bool | TypeName::Char | TypeName::String => {
+            Ok(Binder::dummy(vec![]))
+        }
         TypeName::Array => {
             let parameters = vec![BoundVar::fresh(Kind::Ty)];
             Ok(Binder::new(parameters, vec![]))
         }
+        TypeName::Future => {
+            let parameters = vec![BoundVar::fresh(Kind::Ty), BoundVar::fresh(Kind::Ty)];
+            Ok(Binder::new(parameters, vec![]))
+        }
+        TypeName::Closure(_) => {
+            let parameters: Vec<_> = (0..3).map(|_| BoundVar::fresh(Kind::Ty)).collect();
+            Ok(Binder::new(parameters, vec![]))
+        }
         TypeName::Id(id) => {
+            if let Ok(decl) = program.enum_named(id) {
+                return Ok(decl.binder.map(|b| b.predicates.clone()));
+            }
             let decl = program.class_named(id)?;
             Ok(decl.binder.map(|b| b.predicates.clone()))
         }